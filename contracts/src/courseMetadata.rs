@@ -1,5 +1,4 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, Map, Symbol, U256};
+use soroban_sdk::{contract, contractimpl, contracttype, xdr::ToXdr, Address, Env, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -66,6 +65,46 @@ pub struct InstructorProfile {
     pub created_at: u64,
 }
 
+/// Everything an instructor supplies when creating a course
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CourseDetails {
+    pub title: String,
+    pub description: String,
+    pub category: String,
+    pub level: String,
+    pub duration: u64,
+    pub price: u64,
+    pub prerequisites: Vec<String>,
+    pub learning_objectives: Vec<String>,
+    pub syllabus: String,
+    pub thumbnail_url: String,
+    pub tags: Vec<String>,
+    pub language: String,
+    pub certificate_enabled: bool,
+    pub max_students: u64,
+}
+
+/// Partial update of a course; `None` leaves the field unchanged
+#[contracttype]
+#[derive(Clone, Debug, Default)]
+pub struct CourseUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub level: Option<String>,
+    pub duration: Option<u64>,
+    pub price: Option<u64>,
+    pub prerequisites: Option<Vec<String>>,
+    pub learning_objectives: Option<Vec<String>>,
+    pub syllabus: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub language: Option<String>,
+    pub certificate_enabled: Option<bool>,
+    pub max_students: Option<u64>,
+}
+
 #[contracttype]
 pub enum CourseMetadataKey {
     Course(String),
@@ -95,57 +134,46 @@ impl CourseMetadataContract {
     }
 
     /// Create and store course metadata
-    pub fn create_course(
-        env: Env,
-        instructor: Address,
-        title: String,
-        description: String,
-        category: String,
-        level: String,
-        duration: u64,
-        price: u64,
-        prerequisites: Vec<String>,
-        learning_objectives: Vec<String>,
-        syllabus: String,
-        thumbnail_url: String,
-        tags: Vec<String>,
-        language: String,
-        certificate_enabled: bool,
-        max_students: u64,
-    ) -> String {
+    pub fn create_course(env: Env, instructor: Address, details: CourseDetails) -> String {
         // Check if instructor exists, create if not
         if !env.storage().instance().has(&CourseMetadataKey::Instructor(instructor.clone())) {
-            Self::create_instructor_profile(env, instructor.clone());
+            Self::create_instructor_profile(env.clone(), instructor.clone());
         }
 
         let course_count: u64 = env.storage().instance()
             .get(&CourseMetadataKey::CourseCount)
             .unwrap_or(0);
         
-        let course_id = format!("course_{}", course_count + 1);
+        let course_id = numbered_id(&env, "course_", course_count + 1);
         let timestamp = env.ledger().timestamp();
         
         // Create verification hash
-        let verification_data = format!("{}{}{}{}{}", title, description, instructor, price, timestamp);
-        let verification_hash = Self::generate_hash(env, verification_data);
+        let verification_hash = Self::generate_hash(
+            &env,
+            &details.title,
+            &details.description,
+            &instructor,
+            details.price,
+            timestamp,
+        );
 
         let course_metadata = CourseMetadata {
             id: course_id.clone(),
             instructor: instructor.clone(),
-            title,
-            description,
-            category,
-            level,
-            duration,
-            price,
-            prerequisites,
-            learning_objectives,
-            syllabus,
-            thumbnail_url,
-            tags,
-            language,
-            certificate_enabled,
-            max_students,
+            title: details.title,
+            description: details.description,
+            category: details.category,
+            level: details.level,
+            duration: details.duration,
+            price: details.price,
+            prerequisites: details.prerequisites,
+            learning_objectives: details.learning_objectives,
+            syllabus: details.syllabus,
+            thumbnail_url: details.thumbnail_url,
+            tags: details.tags,
+            language: details.language,
+            certificate_enabled: details.certificate_enabled,
+            max_students: details.max_students,
             current_enrollments: 0,
             rating: 0,
             review_count: 0,
@@ -159,7 +187,7 @@ impl CourseMetadataContract {
         env.storage().instance().set(&CourseMetadataKey::CourseCount, &(course_count + 1));
 
         // Update instructor course count
-        let mut instructor_profile = Self::get_instructor_profile(env, instructor.clone());
+        let mut instructor_profile = Self::get_instructor_profile(env.clone(), instructor.clone());
         instructor_profile.course_count += 1;
         env.storage().instance().set(&CourseMetadataKey::Instructor(instructor), &instructor_profile);
 
@@ -171,20 +199,7 @@ impl CourseMetadataContract {
         env: Env,
         course_id: String,
         instructor: Address,
-        title: Option<String>,
-        description: Option<String>,
-        category: Option<String>,
-        level: Option<String>,
-        duration: Option<u64>,
-        price: Option<u64>,
-        prerequisites: Option<Vec<String>>,
-        learning_objectives: Option<Vec<String>>,
-        syllabus: Option<String>,
-        thumbnail_url: Option<String>,
-        tags: Option<Vec<String>>,
-        language: Option<String>,
-        certificate_enabled: Option<bool>,
-        max_students: Option<u64>,
+        update: CourseUpdate,
         status: Option<CourseStatus>,
     ) -> bool {
         let mut course_metadata: CourseMetadata = env.storage().instance()
@@ -197,46 +212,46 @@ impl CourseMetadataContract {
         }
 
         // Update fields if provided
-        if let Some(new_title) = title {
+        if let Some(new_title) = update.title {
             course_metadata.title = new_title;
         }
-        if let Some(new_description) = description {
+        if let Some(new_description) = update.description {
             course_metadata.description = new_description;
         }
-        if let Some(new_category) = category {
+        if let Some(new_category) = update.category {
             course_metadata.category = new_category;
         }
-        if let Some(new_level) = level {
+        if let Some(new_level) = update.level {
             course_metadata.level = new_level;
         }
-        if let Some(new_duration) = duration {
+        if let Some(new_duration) = update.duration {
             course_metadata.duration = new_duration;
         }
-        if let Some(new_price) = price {
+        if let Some(new_price) = update.price {
             course_metadata.price = new_price;
         }
-        if let Some(new_prerequisites) = prerequisites {
+        if let Some(new_prerequisites) = update.prerequisites {
             course_metadata.prerequisites = new_prerequisites;
         }
-        if let Some(new_learning_objectives) = learning_objectives {
+        if let Some(new_learning_objectives) = update.learning_objectives {
             course_metadata.learning_objectives = new_learning_objectives;
         }
-        if let Some(new_syllabus) = syllabus {
+        if let Some(new_syllabus) = update.syllabus {
             course_metadata.syllabus = new_syllabus;
         }
-        if let Some(new_thumbnail_url) = thumbnail_url {
+        if let Some(new_thumbnail_url) = update.thumbnail_url {
             course_metadata.thumbnail_url = new_thumbnail_url;
         }
-        if let Some(new_tags) = tags {
+        if let Some(new_tags) = update.tags {
             course_metadata.tags = new_tags;
         }
-        if let Some(new_language) = language {
+        if let Some(new_language) = update.language {
             course_metadata.language = new_language;
         }
-        if let Some(new_certificate_enabled) = certificate_enabled {
+        if let Some(new_certificate_enabled) = update.certificate_enabled {
             course_metadata.certificate_enabled = new_certificate_enabled;
         }
-        if let Some(new_max_students) = max_students {
+        if let Some(new_max_students) = update.max_students {
             course_metadata.max_students = new_max_students;
         }
         if let Some(new_status) = status {
@@ -246,14 +261,14 @@ impl CourseMetadataContract {
         course_metadata.updated_at = env.ledger().timestamp();
 
         // Update verification hash
-        let verification_data = format!("{}{}{}{}{}", 
-            course_metadata.title, 
-            course_metadata.description, 
-            course_metadata.instructor, 
-            course_metadata.price, 
-            course_metadata.updated_at
+        course_metadata.verification_hash = Self::generate_hash(
+            &env,
+            &course_metadata.title,
+            &course_metadata.description,
+            &course_metadata.instructor,
+            course_metadata.price,
+            course_metadata.updated_at,
         );
-        course_metadata.verification_hash = Self::generate_hash(env, verification_data);
 
         env.storage().instance().set(&CourseMetadataKey::Course(course_id), &course_metadata);
         true
@@ -266,14 +281,14 @@ impl CourseMetadataContract {
             .unwrap_or_else(|| panic!("Course not found"));
 
         // Recreate verification hash and compare
-        let verification_data = format!("{}{}{}{}{}", 
-            course_metadata.title, 
-            course_metadata.description, 
-            course_metadata.instructor, 
-            course_metadata.price, 
-            course_metadata.updated_at
+        let expected_hash = Self::generate_hash(
+            &env,
+            &course_metadata.title,
+            &course_metadata.description,
+            &course_metadata.instructor,
+            course_metadata.price,
+            course_metadata.updated_at,
         );
-        let expected_hash = Self::generate_hash(env, verification_data);
 
         expected_hash == course_metadata.verification_hash
     }
@@ -328,7 +343,7 @@ impl CourseMetadataContract {
             .get(&CourseMetadataKey::CompletionCount)
             .unwrap_or(0);
 
-        let completion_id = format!("completion_{}", completion_count + 1);
+        let completion_id = numbered_id(&env, "completion_", completion_count + 1);
         let completion = CourseCompletion {
             id: completion_id.clone(),
             course_id: course_id.clone(),
@@ -349,7 +364,7 @@ impl CourseMetadataContract {
         env.storage().instance().set(&CourseMetadataKey::Course(course_id), &updated_course);
 
         // Update instructor total students
-        let mut instructor_profile = Self::get_instructor_profile(env, updated_course.instructor);
+        let mut instructor_profile = Self::get_instructor_profile(env.clone(), updated_course.instructor.clone());
         instructor_profile.total_students += 1;
         env.storage().instance().set(&CourseMetadataKey::Instructor(updated_course.instructor), &instructor_profile);
 
@@ -376,14 +391,14 @@ impl CourseMetadataContract {
     }
 
     /// Get student's course completions
-    pub fn get_student_completions(env: Env, student: Address) -> Vec<String> {
+    pub fn get_student_completions(env: Env, _student: Address) -> Vec<String> {
         // This is a simplified implementation
         // In production, you'd maintain an index of student completions
         Vec::new(&env)
     }
 
     /// Get instructor's courses
-    pub fn get_instructor_courses(env: Env, instructor: Address) -> Vec<String> {
+    pub fn get_instructor_courses(env: Env, _instructor: Address) -> Vec<String> {
         // This is a simplified implementation
         // In production, you'd maintain an index of instructor courses
         Vec::new(&env)
@@ -403,19 +418,27 @@ impl CourseMetadataContract {
             .unwrap_or(0)
     }
 
-    /// Generate simple hash (in production, use proper cryptographic hash)
-    fn generate_hash(env: Env, data: String) -> String {
-        // Simple hash implementation for demonstration
-        // In production, use SHA-256 or similar
-        let mut hash = 0u64;
-        for byte in data.into_bytes() {
-            hash = hash.wrapping_mul(31).wrapping_add(byte as u64);
+    /// SHA-256 over the fields that define a course version, hex encoded
+    fn generate_hash(
+        env: &Env,
+        title: &String,
+        description: &String,
+        instructor: &Address,
+        price: u64,
+        timestamp: u64,
+    ) -> String {
+        let data = (title.clone(), description.clone(), instructor.clone(), price, timestamp).to_xdr(env);
+        let digest = env.crypto().sha256(&data).to_array();
+        let mut hex = [0u8; 64];
+        for (i, byte) in digest.iter().enumerate() {
+            hex[i * 2] = HEX_DIGITS[(byte >> 4) as usize];
+            hex[i * 2 + 1] = HEX_DIGITS[(byte & 0x0f) as usize];
         }
-        format!("{:x}", hash)
+        String::from_bytes(env, &hex)
     }
 
     /// Rate a course
-    pub fn rate_course(env: Env, course_id: String, rater: Address, rating: u32) -> bool {
+    pub fn rate_course(env: Env, course_id: String, _rater: Address, rating: u32) -> bool {
         if rating > 100 {
             panic!("Rating must be between 0 and 100");
         }
@@ -426,9 +449,34 @@ impl CourseMetadataContract {
 
         // Simple rating calculation (in production, store individual ratings)
         course_metadata.review_count += 1;
-        course_metadata.rating = ((course_metadata.rating * (course_metadata.review_count - 1) + rating) / course_metadata.review_count) as u32;
+        let total = course_metadata.rating as u64 * (course_metadata.review_count - 1) + rating as u64;
+        course_metadata.rating = (total / course_metadata.review_count) as u32;
 
         env.storage().instance().set(&CourseMetadataKey::Course(course_id), &course_metadata);
         true
     }
 }
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// `prefix` followed by the decimal form of `n`, e.g. `course_12`
+pub(crate) fn numbered_id(env: &Env, prefix: &str, n: u64) -> String {
+    let mut buf = [0u8; 44];
+    let head = prefix.len();
+    buf[..head].copy_from_slice(prefix.as_bytes());
+    let mut digits = [0u8; 20];
+    let mut len = 0;
+    let mut rest = n;
+    loop {
+        digits[len] = b'0' + (rest % 10) as u8;
+        len += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    for i in 0..len {
+        buf[head + i] = digits[len - 1 - i];
+    }
+    String::from_bytes(env, &buf[..head + len])
+}
@@ -1,72 +1,84 @@
 #![cfg(test)]
 
-use soroban_sdk::{vec, Address, Env, String, Vec};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
 use crate::courseMetadata::{
-    CourseMetadataContract, CourseMetadata, CourseStatus, CourseCompletion, InstructorProfile, CourseMetadataKey
+    CourseDetails, CourseMetadata, CourseMetadataContract, CourseMetadataContractClient, CourseMetadataKey,
+    CourseStatus, CourseUpdate,
 };
 
+fn setup(env: &Env) -> (CourseMetadataContractClient<'_>, Address) {
+    let contract_id = env.register_contract(None, CourseMetadataContract);
+    let client = CourseMetadataContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+fn details(env: &Env, title: &str, description: &str) -> CourseDetails {
+    CourseDetails {
+        title: String::from_str(env, title),
+        description: String::from_str(env, description),
+        category: String::from_str(env, "Programming"),
+        level: String::from_str(env, "beginner"),
+        duration: 40,
+        price: 1000000,
+        prerequisites: vec![env],
+        learning_objectives: vec![env],
+        syllabus: String::from_str(env, "QmHash123"),
+        thumbnail_url: String::from_str(env, "https://example.com/thumbnail.jpg"),
+        tags: vec![env],
+        language: String::from_str(env, "English"),
+        certificate_enabled: true,
+        max_students: 100,
+    }
+}
+
 #[test]
 fn test_initialize() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
 
-    // Test successful initialization
-    CourseMetadataContract::initialize(env.clone(), admin.clone());
-    
     // Verify admin is set
-    let stored_admin: Address = env.storage().instance()
-        .get(&CourseMetadataKey::Admin)
-        .unwrap();
+    let stored_admin: Address = env.as_contract(&client.address, || {
+        env.storage().instance().get(&CourseMetadataKey::Admin).unwrap()
+    });
     assert_eq!(stored_admin, admin);
 
     // Test double initialization fails
-    let result = std::panic::catch_unwind(|| {
-        CourseMetadataContract::initialize(env, admin);
-    });
-    assert!(result.is_err());
+    assert!(client.try_initialize(&admin).is_err());
 }
 
 #[test]
 fn test_create_course() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor.clone(),
-        String::from_str(&env, "Introduction to Rust"),
-        String::from_str(&env, "Learn the basics of Rust programming"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40, // 40 hours
-        1000000, // price in stroops
-        vec![&env, String::from_str(&env, "Basic programming")],
-        vec![&env, String::from_str(&env, "Understand Rust syntax"), String::from_str(&env, "Write basic Rust programs")],
-        String::from_str(&env, "QmHash123"), // IPFS hash
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env, String::from_str(&env, "rust"), String::from_str(&env, "programming")],
-        String::from_str(&env, "English"),
-        true, // certificate enabled
-        100, // max students
-    );
+    let mut course_details = details(&env, "Introduction to Rust", "Learn the basics of Rust programming");
+    course_details.prerequisites = vec![&env, String::from_str(&env, "Basic programming")];
+    course_details.learning_objectives = vec![
+        &env,
+        String::from_str(&env, "Understand Rust syntax"),
+        String::from_str(&env, "Write basic Rust programs"),
+    ];
+    course_details.tags = vec![&env, String::from_str(&env, "rust"), String::from_str(&env, "programming")];
+    let course_id = client.create_course(&instructor, &course_details);
+    assert_eq!(course_id, String::from_str(&env, "course_1"));
 
     // Verify course was created
-    let course = CourseMetadataContract::get_course(env.clone(), course_id.clone());
+    let course = client.get_course(&course_id);
     assert_eq!(course.title, String::from_str(&env, "Introduction to Rust"));
     assert_eq!(course.instructor, instructor);
     assert_eq!(course.price, 1000000);
     assert_eq!(course.current_enrollments, 0);
     assert_eq!(course.rating, 0);
     assert_eq!(course.review_count, 0);
+    assert_eq!(course.learning_objectives.len(), 2);
     assert!(matches!(course.status, CourseStatus::Active));
 
     // Verify instructor profile was created
-    let instructor_profile = CourseMetadataContract::get_instructor_profile(env, instructor);
+    let instructor_profile = client.get_instructor_profile(&instructor);
     assert_eq!(instructor_profile.address, instructor);
     assert_eq!(instructor_profile.course_count, 1);
     assert_eq!(instructor_profile.total_students, 0);
@@ -75,58 +87,25 @@ fn test_create_course() {
 #[test]
 fn test_update_course() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor.clone(),
-        String::from_str(&env, "Original Title"),
-        String::from_str(&env, "Original description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
-
-    // Update the course
-    let update_result = CourseMetadataContract::update_course(
-        env.clone(),
-        course_id.clone(),
-        instructor.clone(),
-        Some(String::from_str(&env, "Updated Title")),
-        Some(String::from_str(&env, "Updated description")),
-        None, // category unchanged
-        Some(String::from_str(&env, "intermediate")),
-        Some(60), // updated duration
-        Some(2000000), // updated price
-        None, // prerequisites unchanged
-        None, // learning objectives unchanged
-        None, // syllabus unchanged
-        None, // thumbnail unchanged
-        None, // tags unchanged
-        None, // language unchanged
-        None, // certificate enabled unchanged
-        None, // max students unchanged
-        Some(CourseStatus::Inactive),
-    );
-
-    assert!(update_result);
+    let course_id = client.create_course(&instructor, &details(&env, "Original Title", "Original description"));
+
+    // Update the course; fields left as `None` are unchanged
+    let update = CourseUpdate {
+        title: Some(String::from_str(&env, "Updated Title")),
+        description: Some(String::from_str(&env, "Updated description")),
+        level: Some(String::from_str(&env, "intermediate")),
+        duration: Some(60),
+        price: Some(2000000),
+        ..Default::default()
+    };
+    assert!(client.update_course(&course_id, &instructor, &update, &Some(CourseStatus::Inactive)));
 
     // Verify updates
-    let updated_course = CourseMetadataContract::get_course(env, course_id);
+    let updated_course = client.get_course(&course_id);
     assert_eq!(updated_course.title, String::from_str(&env, "Updated Title"));
     assert_eq!(updated_course.description, String::from_str(&env, "Updated description"));
     assert_eq!(updated_course.level, String::from_str(&env, "intermediate"));
@@ -135,93 +114,68 @@ fn test_update_course() {
     assert!(matches!(updated_course.status, CourseStatus::Inactive));
     // Category should remain unchanged
     assert_eq!(updated_course.category, String::from_str(&env, "Programming"));
+    // The verification hash follows the update
+    assert!(client.verify_course(&course_id));
 }
 
 #[test]
-fn test_verify_course() {
+fn test_update_course_by_other_instructor() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
+
+    let other = Address::generate(&env);
+    assert!(client.try_update_course(&course_id, &other, &CourseUpdate::default(), &None).is_err());
+}
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
+#[test]
+fn test_verify_course() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let instructor = Address::generate(&env);
 
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor.clone(),
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Verify course authenticity
-    let is_valid = CourseMetadataContract::verify_course(env.clone(), course_id.clone());
-    assert!(is_valid);
-
-    // Get course and manually tamper with verification hash test
-    let mut course = CourseMetadataContract::get_course(env.clone(), course_id);
-    course.verification_hash = String::from_str(&env, "tampered_hash");
-    
-    // This would require direct storage access to test tampering
-    // For now, we just verify the verification logic works
-    let original_verification = CourseMetadataContract::verify_course(env, course_id);
-    assert!(original_verification); // Should still be valid as we didn't actually tamper
+    assert!(client.verify_course(&course_id));
+    let course = client.get_course(&course_id);
+    assert_eq!(course.verification_hash.len(), 64);
+
+    // Tamper with the stored title; the hash no longer matches
+    env.as_contract(&client.address, || {
+        let mut tampered: CourseMetadata = env.storage().instance()
+            .get(&CourseMetadataKey::Course(course_id.clone()))
+            .unwrap();
+        tampered.title = String::from_str(&env, "Tampered Title");
+        env.storage().instance().set(&CourseMetadataKey::Course(course_id.clone()), &tampered);
+    });
+    assert!(!client.verify_course(&course_id));
 }
 
 #[test]
 fn test_record_completion() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
     let student = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor.clone(),
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Record course completion
-    let completion_id = CourseMetadataContract::record_completion(
-        env.clone(),
-        course_id.clone(),
-        student.clone(),
-        85, // final grade
-        String::from_str(&env, "QmCertHash456"), // certificate hash
-        vec![&env, String::from_str(&env, "Rust basics"), String::from_str(&env, "Memory management")],
+    let completion_id = client.record_completion(
+        &course_id,
+        &student,
+        &85, // final grade
+        &String::from_str(&env, "QmCertHash456"), // certificate hash
+        &vec![&env, String::from_str(&env, "Rust basics"), String::from_str(&env, "Memory management")],
     );
+    assert_eq!(completion_id, String::from_str(&env, "completion_1"));
 
     // Verify completion was recorded
-    let completion = CourseMetadataContract::get_completion(env.clone(), completion_id.clone());
+    let completion = client.get_completion(&completion_id);
     assert_eq!(completion.course_id, course_id);
     assert_eq!(completion.student, student);
     assert_eq!(completion.final_grade, 85);
@@ -230,112 +184,66 @@ fn test_record_completion() {
     assert_eq!(completion.skills_acquired.len(), 2);
 
     // Verify course enrollment count updated
-    let updated_course = CourseMetadataContract::get_course(env.clone(), course_id);
+    let updated_course = client.get_course(&course_id);
     assert_eq!(updated_course.current_enrollments, 1);
 
     // Verify instructor student count updated
-    let updated_instructor = CourseMetadataContract::get_instructor_profile(env, instructor);
+    let updated_instructor = client.get_instructor_profile(&instructor);
     assert_eq!(updated_instructor.total_students, 1);
 }
 
 #[test]
 fn test_verify_completion() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
     let student = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor,
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Record completion
-    let completion_id = CourseMetadataContract::record_completion(
-        env.clone(),
-        course_id,
-        student,
-        85,
-        String::from_str(&env, "QmCertHash456"),
-        vec![&env, String::from_str(&env, "Rust basics")],
+    let completion_id = client.record_completion(
+        &course_id,
+        &student,
+        &85,
+        &String::from_str(&env, "QmCertHash456"),
+        &vec![&env, String::from_str(&env, "Rust basics")],
     );
 
     // Verify completion is initially not verified
-    let completion = CourseMetadataContract::get_completion(env.clone(), completion_id.clone());
-    assert!(!completion.is_verified);
+    assert!(!client.get_completion(&completion_id).is_verified);
 
     // Verify completion
-    let verify_result = CourseMetadataContract::verify_completion(env.clone(), completion_id.clone());
-    assert!(verify_result);
+    assert!(client.verify_completion(&completion_id));
 
     // Check that completion is now verified
-    let verified_completion = CourseMetadataContract::get_completion(env, completion_id);
-    assert!(verified_completion.is_verified);
+    assert!(client.get_completion(&completion_id).is_verified);
 }
 
 #[test]
 fn test_rate_course() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
     let rater = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor,
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Rate the course
-    let rate_result = CourseMetadataContract::rate_course(env.clone(), course_id.clone(), rater, 80);
-    assert!(rate_result);
+    assert!(client.rate_course(&course_id, &rater, &80));
 
     // Check rating was updated
-    let rated_course = CourseMetadataContract::get_course(env.clone(), course_id);
+    let rated_course = client.get_course(&course_id);
     assert_eq!(rated_course.rating, 80);
     assert_eq!(rated_course.review_count, 1);
 
     // Rate again
-    let rate_result2 = CourseMetadataContract::rate_course(env.clone(), course_id.clone(), rater, 90);
-    assert!(rate_result2);
+    assert!(client.rate_course(&course_id, &rater, &90));
 
     // Check average rating calculation
-    let final_course = CourseMetadataContract::get_course(env, course_id);
+    let final_course = client.get_course(&course_id);
     assert_eq!(final_course.rating, 85); // (80 + 90) / 2 = 85
     assert_eq!(final_course.review_count, 2);
 }
@@ -343,147 +251,69 @@ fn test_rate_course() {
 #[test]
 fn test_get_course_count() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Initially no courses
-    assert_eq!(CourseMetadataContract::get_course_count(env.clone()), 0);
+    assert_eq!(client.get_course_count(), 0);
 
     // Create courses
-    CourseMetadataContract::create_course(
-        env.clone(),
-        instructor.clone(),
-        String::from_str(&env, "Course 1"),
-        String::from_str(&env, "Description 1"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
-
-    assert_eq!(CourseMetadataContract::get_course_count(env.clone()), 1);
-
-    CourseMetadataContract::create_course(
-        env.clone(),
-        instructor,
-        String::from_str(&env, "Course 2"),
-        String::from_str(&env, "Description 2"),
-        String::from_str(&env, "Design"),
-        String::from_str(&env, "intermediate"),
-        60,
-        2000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash456"),
-        String::from_str(&env, "https://example.com/thumbnail2.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        50,
-    );
-
-    assert_eq!(CourseMetadataContract::get_course_count(env), 2);
+    client.create_course(&instructor, &details(&env, "Course 1", "Description 1"));
+    assert_eq!(client.get_course_count(), 1);
+
+    let mut second = details(&env, "Course 2", "Description 2");
+    second.category = String::from_str(&env, "Design");
+    second.max_students = 50;
+    let second_id = client.create_course(&instructor, &second);
+    assert_eq!(second_id, String::from_str(&env, "course_2"));
+    assert_eq!(client.get_course_count(), 2);
+    assert_eq!(client.get_instructor_profile(&instructor).course_count, 2);
 }
 
 #[test]
 fn test_get_completion_count() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
     let student = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Initially no completions
-    assert_eq!(CourseMetadataContract::get_completion_count(env.clone()), 0);
+    assert_eq!(client.get_completion_count(), 0);
 
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor,
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Record completions
-    CourseMetadataContract::record_completion(
-        env.clone(),
-        course_id.clone(),
-        student.clone(),
-        85,
-        String::from_str(&env, "QmCertHash456"),
-        vec![&env, String::from_str(&env, "Rust basics")],
+    client.record_completion(
+        &course_id,
+        &student,
+        &85,
+        &String::from_str(&env, "QmCertHash456"),
+        &vec![&env, String::from_str(&env, "Rust basics")],
     );
-
-    assert_eq!(CourseMetadataContract::get_completion_count(env.clone()), 1);
-
-    CourseMetadataContract::record_completion(
-        env.clone(),
-        course_id,
-        student,
-        90,
-        String::from_str(&env, "QmCertHash789"),
-        vec![&env, String::from_str(&env, "Advanced Rust")],
+    assert_eq!(client.get_completion_count(), 1);
+
+    client.record_completion(
+        &course_id,
+        &student,
+        &90,
+        &String::from_str(&env, "QmCertHash789"),
+        &vec![&env, String::from_str(&env, "Advanced Rust")],
     );
-
-    assert_eq!(CourseMetadataContract::get_completion_count(env), 2);
+    assert_eq!(client.get_completion_count(), 2);
 }
 
 #[test]
 #[should_panic(expected = "Rating must be between 0 and 100")]
 fn test_invalid_rating() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let instructor = Address::generate(&env);
     let rater = Address::generate(&env);
 
-    // Initialize contract
-    CourseMetadataContract::initialize(env.clone(), admin);
-
     // Create a course
-    let course_id = CourseMetadataContract::create_course(
-        env.clone(),
-        instructor,
-        String::from_str(&env, "Test Course"),
-        String::from_str(&env, "Test description"),
-        String::from_str(&env, "Programming"),
-        String::from_str(&env, "beginner"),
-        40,
-        1000000,
-        vec![&env],
-        vec![&env],
-        String::from_str(&env, "QmHash123"),
-        String::from_str(&env, "https://example.com/thumbnail.jpg"),
-        vec![&env],
-        String::from_str(&env, "English"),
-        true,
-        100,
-    );
+    let course_id = client.create_course(&instructor, &details(&env, "Test Course", "Test description"));
 
     // Try to rate with invalid rating (should panic)
-    CourseMetadataContract::rate_course(env, course_id, rater, 150); // Invalid rating > 100
+    client.rate_course(&course_id, &rater, &150); // Invalid rating > 100
 }
@@ -1,13 +1,458 @@
-use soroban_sdk::{contracttype, Address, Env, String, Vec, Symbol};
+use soroban_sdk::{contracterror, contracttype, panic_with_error, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, String, Vec, Symbol, Val};
+
+use crate::user_profile;
+
+const DEFAULT_MAX_TITLE_LEN: u32 = 128;
+const DEFAULT_MAX_DESCRIPTION_LEN: u32 = 1024;
+/// Longest title `sanitize_title` will rewrite
+const MAX_SANITIZED_TITLE_LEN: usize = 1024;
+/// Depth of the append-only credential merkle tree (room for 2^32 credentials)
+pub const MERKLE_TREE_DEPTH: u32 = 32;
+/// How many valid credentials one revocation cancels out in the issuer reputation score
+const REVOCATION_WEIGHT: u64 = 2;
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+/// Leading topic on every event until the admin sets a deployment-specific namespace
+pub const DEFAULT_EVENT_NAMESPACE: Symbol = symbol_short!("starked");
+pub const CONTRACT_VERSION: u32 = 1;
+const EXPORT_PAGE_SIZE: u32 = 50;
+/// Seconds a supplied completion date may run ahead of the ledger, to absorb client clock drift
+const DEFAULT_MAX_FUTURE_SKEW: u64 = 300;
+const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+/// Entries kept on the XP leaderboard; also the most `get_top_users` will return
+pub const LEADERBOARD_SIZE: u32 = 20;
+/// Longest display id, supplied or rendered from the template
+pub const MAX_DISPLAY_ID_LEN: u32 = 64;
+/// Most credentials `get_histories` accepts per call
+pub const MAX_HISTORY_BATCH: u32 = 20;
+pub const MAX_PINNED_CREDENTIALS: u32 = 5;
+const SECONDS_PER_DAY: u64 = 86_400;
+/// Longest range `get_issuance_stats` serves in one call
+const MAX_STATS_DAYS: u64 = 366;
+/// How long after a bulk revocation `undo_bulk_action` may reverse it, unless configured
+const DEFAULT_BULK_UNDO_WINDOW: u64 = 7 * SECONDS_PER_DAY;
+/// What `time_to_expiry` reports for credentials that never expire
+pub const NEVER_EXPIRES: i64 = i64::MAX;
+/// Longest `ipfs_gateway` accepted
+pub const MAX_GATEWAY_LEN: u32 = 128;
+/// Longest URL `get_credential_document_url` will compose
+const MAX_DOCUMENT_URL_LEN: usize = 512;
+/// Span of expiry dates sharing one `ExpiryBucket`, so no single entry holds every expiring credential
+const EXPIRY_BUCKET_SECS: u64 = 7 * SECONDS_PER_DAY;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CredentialError {
+    FieldTooLong = 1,
+    NotPurgeable = 2,
+    ReinstatementNotAllowed = 3,
+    AlreadyRevoked = 4,
+    NotCourseIssuer = 5,
+    DuplicateRecipient = 6,
+    InvalidCompletionDate = 7,
+    NotYourCredential = 8,
+    UserLimitReached = 9,
+    InvalidRecipient = 10,
+    BatchTooLarge = 11,
+    CounterOverflow = 12,
+    AlreadyValid = 13,
+    DuplicateDisplayId = 14,
+    InvalidConfig = 15,
+    VerifierNotGranted = 16,
+    InvalidCharacters = 17,
+    QuotaExhausted = 18,
+    IssuerExpired = 19,
+    TemporalInconsistency = 20,
+    TamperedImport = 21,
+    AlreadySuspended = 22,
+    NotSuspended = 23,
+    AlreadyEndorsed = 24,
+    UndoWindowClosed = 25,
+    IdTaken = 26,
+}
 
 #[contracttype]
 pub enum CredentialKey {
     Credential(u64),
     UserCredentials(Address),
     CredentialCount,
+    FingerprintIndex(BytesN<32>),
+    RevocationDelay,
+    PendingRevocation(u64),
+    EmergencyRevokeCount,
+    RequireCoSignatures,
+    Appeal(u64),
+    Paused,
+    MaxTitleLength,
+    MaxDescriptionLength,
+    MerkleBranch,
+    MerkleRoot,
+    GracePeriod,
+    PurgedIndex(u64),
+    Issuers,
+    IssuerPolicy(Address),
+    ShareToken(BytesN<32>),
+    ShareTokenNonce,
+    IssuerStats(Address),
+    IssuedLeaf(u64),
+    CourseIssuers(String),
+    RevocationsByReason(RevocationReason),
+    AdminSigners,
+    AdminThreshold,
+    ActionApprovals(BytesN<32>),
+    Anchor(u64),
+    AnchorCount,
+    Operators(Address),
+    VerificationNotes(u64),
+    IssuerCredentials(Address),
+    DedupeRecipients,
+    MinCompletionDate,
+    MaxFutureSkew,
+    AllowIssuerSelfRevoke,
+    MaxCredentialsPerUser,
+    ExpirySwept(u64),
+    ExpiredCount,
+    MigrationTarget,
+    Migrated(u64),
+    VerifierHook(u64), // template id
+    HookFailureMode,
+    BlockedRecipient(Address),
+    MaxBatchSize,
+    PinnedCredentials(Address),
+    DependentIndex(u64),
+    CascadeMode,
+    NeedsReview(u64),
+    IssuanceByDay(u64),
+}
+
+/// Overflow storage keys: `CredentialKey` has hit the 50-variant limit for contracttype enums
+#[contracttype]
+#[derive(Clone)]
+pub enum CredentialKeyExt {
+    RenewalConfig,
+    IssuerSigningKey(Address),
+    CohortIndex(String),
+    EventNamespace,
+    StrictIssuerInvalidates,
+    UserXp(Address),
+    Leaderboard,
+    Template(u64),
+    CourseCredentials(String),
+    VerificationLog(u64),
+    VerificationLogLimit,
+    EmailCredentials(BytesN<32>),
+    ClaimVerifierKey,
+    DisplayIdTemplate,
+    CourseSequence(String),
+    DisplayIdIndex(String),
+    VerifierGrants(u64),
+    History(u64),
+    SanitizeText,
+    Quota(Address),
+    IssuerExpiry(Address),
+    Imported(u64),
+    ExpiryWeeks,
+    ExpiryBucket(u64),
+    ExtensionNonce(u64),
+    IssuerPrivate(Address),
+    BundleMembers(u64),
+    IpfsGateway,
+    RequireRecipientAuth,
+    Endorsements(u64),
+    BulkActionLog(u64),
+    BulkActionCount,
+    BulkUndoWindow,
+    IssuerName(Address),
+    PendingAppeals,
+    AppealTimeout,
+    AppealAutoReinstate,
+    RevocationListener,
+    TemplateCount,
+}
+
+/// Reusable credential content. `version` starts at 1 and bumps on every `update_template`;
+/// each issued credential records the version it came from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialTemplate {
+    pub id: u64,
+    pub issuer: Address,
+    pub title: String,
+    pub description: String,
+    pub course_id: String,
+    pub expires_after: u64, // seconds from issuance; 0 = never expires
+    pub version: u32,
+    pub prerequisite_courses: Vec<String>,
+    pub auto_bundle_prereqs: bool, // bundle the recipient's prerequisite credentials under each issue
+}
+
+/// One member credential of an `issue_bundle` call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberSpec {
+    pub title: String,
+    pub description: String,
+    pub course_id: String,
+    pub ipfs_hash: String,
+}
+
+/// Fields `update_credential_fields` may change; `None` leaves the stored value as is
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub ipfs_hash: Option<String>,
+    pub expires_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalConfig {
+    pub token: Address,
+    pub renewal_period: u64,
+    pub allow_lapsed: bool,
+}
+
+/// What happens to dependents when their prerequisite is revoked
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CascadeMode {
+    Off,
+    Revoke,
+    FlagForReview,
+}
+
+/// What verification does when a verifier hook traps or returns something other than a bool
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HookFailureMode {
+    TreatInvalid,
+    Skip,
+}
+
+/// A credential packaged for import by a newer contract version
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationRecord {
+    pub credential: Credential,
+    pub fingerprint: BytesN<32>,
+    pub source_contract: Address,
+    pub target_contract: Address,
+    pub exported_at: u64,
+}
+
+/// Provenance kept for a credential brought in by `import_migrated_credential`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportedCredential {
+    pub source_contract: Address,
+    pub source_id: u64,
+    pub fingerprint: BytesN<32>,
+}
+
+/// Backup/migration blob returned by `export_issuer_credentials`, XDR-encoded. `version`
+/// is bumped whenever `Credential` changes shape, so importers can reject formats they
+/// don't understand. `next_after` is the cursor for the next page, or 0 on the last page.
+#[contracttype]
+#[derive(Clone)]
+pub struct ExportEnvelope {
+    pub version: u32,
+    pub issuer: Address,
+    pub credentials: Vec<Credential>,
+    pub next_after: u64,
+}
+
+/// A verifier's remark on a credential (e.g. "confirmed with registrar") for later verifiers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationNote {
+    pub verifier: Address,
+    pub note: String,
+    pub created_at: u64,
+}
+
+/// One audited verification: who checked, when, and what they were told
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationLogEntry {
+    pub verifier: Address,
+    pub verified_at: u64,
+    pub status: VerificationStatus,
+}
+
+/// A credential addressed to a hashed email, waiting for the learner to claim it to a wallet
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingEmailCredential {
+    pub issuer: Address,
+    pub title: String,
+    pub description: String,
+    pub course_id: String,
+    pub ipfs_hash: String,
+    pub issued_at: u64,
+}
+
+/// One entry in a credential's lifecycle: issued (`Valid`), `Revoked`, or reinstated (`Valid`)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusChange {
+    pub status: VerificationStatus,
+    pub changed_at: u64,
+}
+
+/// Credentials a bulk revocation actually revoked, kept so `undo_bulk_action` can reverse it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BulkAction {
+    pub admin: Address,
+    pub credential_ids: Vec<u64>,
+    pub performed_at: u64,
+    pub undone: bool,
 }
 
+/// A third party vouching for a credential. `weight` is the endorser's reputation when they
+/// endorsed: 1 plus the number of valid credentials they held at the time.
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Endorsement {
+    pub endorser: Address,
+    pub weight: u64,
+    pub endorsed_at: u64,
+}
+
+/// Timestamped proof that a document existed, before a credential is issued for it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocumentAnchor {
+    pub id: u64,
+    pub issuer: Address,
+    pub content_sha256: BytesN<32>,
+    pub anchored_at: u64,
+    pub credential_id: u64, // 0 until promoted
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerStats {
+    pub valid_count: u32,
+    pub revoked_count: u32,
+}
+
+/// One row of the public institutions page, as returned by `get_issuer_directory`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerInfo {
+    pub issuer: Address,
+    pub name: String,
+    pub issued_count: u32,
+    pub revoked_count: u32,
+    pub authorized: bool, // false once the issuer's authorization has expired
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ShareToken {
+    pub credential_id: u64,
+    pub expires_at: u64,
+}
+
+/// Per-institution revocation rules; when set, `revocation_delay` overrides the global delay
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerPolicy {
+    pub allow_reinstate: bool,
+    pub revocation_delay: u64,
+}
+
+/// Every scalar setting in one place, for `get_config` / `set_config`. On/off switches live in
+/// `FeatureFlags`; allowlists, hooks and other keyed settings keep their dedicated entry points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub max_title_len: u32,
+    pub max_description_len: u32,
+    pub max_batch_size: u32,
+    pub max_credentials_per_user: u32,
+    pub min_completion_date: u64,
+    pub max_future_skew: u64,
+    pub revocation_delay: u64,
+    pub grace_period: u64,
+    pub cascade_mode: CascadeMode,
+    pub hook_failure_mode: HookFailureMode,
+    pub verification_log_limit: u32,
+    pub event_namespace: Symbol,
+    pub ipfs_gateway: String,
+    pub bulk_undo_window: u64,
+    pub appeal_timeout: u64,
+    pub appeal_auto_reinstate: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractStatus {
+    pub initialized: bool,
+    pub paused: bool,
+    pub version: u32,
+    pub admin: Option<Address>,
+    pub credential_count: u64,
+}
+
+/// Every on/off switch, for `get_feature_flags` / `set_feature_flags` and conditional client UI
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeatureFlags {
+    pub paused: bool,
+    pub require_co_signatures: bool,
+    pub revocation_delay_enabled: bool, // reported only; follows `set_revocation_delay`
+    pub allow_issuer_self_revoke: bool, // issuers may revoke what they issued, not others'
+    pub dedupe_recipients: bool, // batches listing a recipient twice are rejected
+    pub strict_issuer_invalidates: bool, // see `verify_credential_strict`; on by default
+    pub sanitize_text: bool, // see `sanitize_title`; on by default
+    pub require_recipient_auth: bool, // the recipient co-signs every issuance
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingRevocation {
+    pub revoker: Address,
+    pub reason: RevocationReason,
+    pub note: String,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RevocationReason {
+    Fraud,
+    IssuanceError, // "Error" would clash with the conversion impls contracttype derives
+    Expired,
+    PolicyViolation,
+    Other,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AppealStatus {
+    Pending,
+    Upheld,
+    Reinstated,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Appeal {
+    pub credential_id: u64,
+    pub recipient: Address,
+    pub reason: String,
+    pub status: AppealStatus,
+    pub filed_at: u64,
+    pub resolved_at: u64,
+    pub resolution_note: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Credential {
     pub id: u64,
     pub issuer: Address,
@@ -18,8 +463,75 @@ pub struct Credential {
     pub completion_date: u64,
     pub ipfs_hash: String,
     pub is_revoked: bool,  // Changed from is_verified → revocation is more useful
+    pub co_issuers: Vec<Address>,
+    pub co_signers: Vec<Address>,
+    pub active_from: u64,
+    pub expires_at: u64, // 0 = never expires
+    pub credential_type: CredentialType,
+    pub translations: Map<String, (String, String)>, // language code -> (title, description)
+    pub supersedes: u64, // 0 = not a reissue
+    pub superseded_by: u64, // 0 = current
+    pub metadata: Map<String, String>,
+    pub renewal_fee: i128, // 0 = not self-service renewable
+    pub cohort_id: String, // empty = no cohort
+    pub xp: u32,
+    pub template_id: u64, // 0 = not issued from a template
+    pub template_version: u32,
+    pub encrypted: bool, // document on IPFS is encrypted; informational only
+    pub encryption_pubkey_hint: String, // who can decrypt; empty = unspecified
+    pub display_id: String, // human-readable number like "2024-CS-0001"; empty = none
+    pub revoked_from: u64, // when a revocation takes effect; 0 = as soon as it's applied
+    pub is_private: bool, // verify_credential_as only answers granted verifiers
+    pub grade: u32, // issuer-defined scale, higher is better; 0 = ungraded
+    pub hidden_by_recipient: bool, // left out of profile summaries; still verifies
+    pub is_suspended: bool,
+    pub credits: u32, // course credit weight for `compute_gpa`; 0 = not counted
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CredentialType {
+    Certificate,
+    Degree,
+    Diploma,
+    Badge,
+    License,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerificationStatus {
+    Valid,
+    NotFound,
+    Revoked,
+    NotYetActive,
+    AwaitingCoSignatures,
+    Expired,
+    GracePeriod,
+    Purged,
+    TokenExpired,
+    DocumentPending,
+    Superseded,
+    Migrated,
+    Invalid,
+    IssuerRevoked,
+    Suspended,
+}
+
+/// One row of a profile page, as returned by `get_user_profile_summary`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationSummary {
+    pub credential_id: u64,
+    pub status: VerificationStatus,
+    pub issuer: Address,
+    pub credential_type: CredentialType,
+    pub completion_date: u64,
+    pub active_from: u64,
+    pub expires_at: u64,
 }
 
+/// An empty `ipfs_hash` defers the document; see `attach_document`
 pub fn issue_credential(
     env: &Env,
     issuer: Address,
@@ -29,81 +541,4231 @@ pub fn issue_credential(
     course_id: String,
     ipfs_hash: String,
 ) -> u64 {
+    let credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    record_issuance(env, credential).id
+}
+
+/// Issue every member credential and then a bundle credential (e.g. a degree) grouping them,
+/// all in one invocation: any failure rolls back the whole call. Returns the bundle id and the
+/// member ids in `members` order, of which there may be at most `get_max_batch_size`. The
+/// bundle carries no document of its own, so it reports `DocumentPending` until one is attached.
+pub fn issue_bundle(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    members: Vec<MemberSpec>,
+    bundle_title: String,
+) -> (u64, Vec<u64>) {
     issuer.require_auth();
+    if members.is_empty() {
+        panic!("Bundle has no members");
+    }
+    if members.len() > get_max_batch_size(env) {
+        panic_with_error!(env, CredentialError::BatchTooLarge);
+    }
 
-    let admin: Address = env.storage().instance().get(&Symbol::new(env, "admin"));
-    if issuer != admin {
-        panic!("Unauthorized issuer");
+    let mut member_ids = Vec::new(env);
+    for member in members.iter() {
+        let credential = build_credential(
+            env,
+            issuer.clone(),
+            recipient.clone(),
+            member.title,
+            member.description,
+            member.course_id,
+            member.ipfs_hash,
+        );
+        member_ids.push_back(record_issuance(env, credential).id);
     }
 
-    let mut count: u64 = env.storage().instance().get(&CredentialKey::CredentialCount).unwrap_or(0);
-    count += 1;
+    let bundle = build_credential(
+        env,
+        issuer,
+        recipient,
+        bundle_title,
+        String::from_str(env, ""),
+        String::from_str(env, ""),
+        String::from_str(env, ""),
+    );
+    let bundle_id = record_issuance(env, bundle).id;
+    env.storage().persistent().set(&CredentialKeyExt::BundleMembers(bundle_id), &member_ids);
 
-    let credential = Credential {
-        id: count,
-        issuer: issuer.clone(),
-        recipient: recipient.clone(),
+    publish_event(env, (symbol_short!("cred"), symbol_short!("bundled")), (bundle_id, member_ids.clone()));
+    (bundle_id, member_ids)
+}
+
+/// Member ids of a bundle credential; empty for anything that isn't one
+pub fn get_bundle_members(env: &Env, bundle_id: u64) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::BundleMembers(bundle_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// `issue_credential`, returning the stored credential to save a follow-up read
+pub fn issue_credential_full(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> Credential {
+    let credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    record_issuance(env, credential)
+}
+
+/// Issue with a caller-supplied completion date, checked against `get_completion_date_bounds`
+pub fn issue_credential_with_date(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    completion_date: u64,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.completion_date = completion_date;
+    record_issuance(env, credential).id
+}
+
+/// Issue to a learner known only by `email_hash` (sha256 of the normalized address). Nothing is
+/// minted until `claim_credential`; returns how many credentials now await that email.
+pub fn issue_to_email(
+    env: &Env,
+    issuer: Address,
+    email_hash: BytesN<32>,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u32 {
+    issuer.require_auth();
+    require_not_paused(env);
+    require_authorized_issuer(env, &issuer, &course_id);
+    validate_text_fields(env, &title, &description);
+
+    let mut pending = load_email_credentials(env, email_hash.clone());
+    pending.push_back(PendingEmailCredential {
+        issuer,
         title,
         description,
         course_id,
-        completion_date: env.ledger().timestamp(),
         ipfs_hash,
-        is_revoked: false,
-    };
+        issued_at: env.ledger().timestamp(),
+    });
+    env.storage().persistent().set(&CredentialKeyExt::EmailCredentials(email_hash.clone()), &pending);
 
-    env.storage().persistent().set(&CredentialKey::Credential(count), &credential);
+    publish_event(env, (symbol_short!("email"), symbol_short!("issued")), (email_hash, pending.len()));
+    pending.len()
+}
 
-    // Integrate with user profile
-    user_profile::add_credential(env, recipient.clone(), count);
+/// Credentials awaiting a claim by `email_hash`; a private issuer's address is masked
+pub fn get_email_credentials(env: &Env, email_hash: BytesN<32>) -> Vec<PendingEmailCredential> {
+    let mut pending = Vec::new(env);
+    for mut item in load_email_credentials(env, email_hash).iter() {
+        item.issuer = public_issuer(env, item.issuer);
+        pending.push_back(item);
+    }
+    pending
+}
 
-    env.storage().instance().set(&CredentialKey::CredentialCount, &count);
+fn load_email_credentials(env: &Env, email_hash: BytesN<32>) -> Vec<PendingEmailCredential> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::EmailCredentials(email_hash))
+        .unwrap_or(Vec::new(env))
+}
 
-    count
+/// ed25519 key of the off-chain service that confirms email ownership for claims
+pub fn set_claim_verifier_key(env: &Env, admin: Address, pubkey: BytesN<32>) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::ClaimVerifierKey, &pubkey);
 }
 
-pub fn verify_credential(env: &Env, credential_id: u64) -> bool {
-    let mut credential: Credential = env.storage().persistent()
-        .get(&CredentialKey::Credential(credential_id))
-        .unwrap_or_else(|| panic!("Credential not found"));
+/// Mint everything awaiting `email_hash` to `recipient`. `proof` is the claim verifier's
+/// signature over the XDR of `(contract address, email_hash, recipient)`. Items whose issuer
+/// is no longer authorized for the course stay pending. Returns the new credential ids.
+pub fn claim_credential(env: &Env, email_hash: BytesN<32>, proof: BytesN<64>, recipient: Address) -> Vec<u64> {
+    recipient.require_auth();
+    require_not_paused(env);
 
-    if credential.is_revoked {
-        return false;
+    let verifier_key: BytesN<32> = env.storage().instance()
+        .get(&CredentialKeyExt::ClaimVerifierKey)
+        .unwrap_or_else(|| panic!("Claims not configured"));
+    let message = (env.current_contract_address(), email_hash.clone(), recipient.clone()).to_xdr(env);
+    env.crypto().ed25519_verify(&verifier_key, &message, &proof);
+
+    let pending = load_email_credentials(env, email_hash.clone());
+    if pending.is_empty() {
+        panic!("Nothing to claim");
+    }
+
+    // Issuer authorization is re-checked per item, so a since-removed or expired issuer's
+    // credentials wait instead of minting or failing the rest of the claim
+    let mut ids = Vec::new(env);
+    let mut remaining = Vec::new(env);
+    for item in pending.iter() {
+        if !can_issue_for(env, &item.issuer, &item.course_id) {
+            remaining.push_back(item);
+            continue;
+        }
+        let mut credential = build_credential(
+            env,
+            item.issuer,
+            recipient.clone(),
+            item.title,
+            item.description,
+            item.course_id,
+            item.ipfs_hash,
+        );
+        credential.completion_date = item.issued_at;
+        ids.push_back(record_issuance(env, credential).id);
+    }
+    if remaining.is_empty() {
+        env.storage().persistent().remove(&CredentialKeyExt::EmailCredentials(email_hash.clone()));
+    } else {
+        env.storage().persistent().set(&CredentialKeyExt::EmailCredentials(email_hash.clone()), &remaining);
     }
 
-    // Here you can add more verification logic (e.g. check issuer signature, expiration)
-    true
+    publish_event(env, (symbol_short!("email"), symbol_short!("claimed")), (email_hash, recipient, ids.clone()));
+    ids
 }
 
-pub fn revoke_credential(env: &Env, credential_id: u64, revoker: Address) {
-    revoker.require_auth();
+/// Issue with a caller-chosen display id instead of one rendered from the template
+pub fn issue_credential_with_display_id(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    display_id: String,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    if display_id.is_empty() {
+        panic!("Empty display id");
+    }
+    credential.display_id = display_id;
+    record_issuance(env, credential).id
+}
 
-    let admin: Address = env.storage().instance().get(&Symbol::new(env, "admin"));
-    if revoker != admin {
-        panic!("Only admin can revoke");
+/// Template for auto-generated display ids, e.g. "{year}-{course}-{seq}". `{year}` is the
+/// completion year (UTC), `{course}` the course id and `{seq}` a per-course counter padded to
+/// four digits. Credentials issued without an explicit display id get none until this is set.
+pub fn set_display_id_template(env: &Env, admin: Address, template: String) {
+    require_admin(env, &admin);
+    if template.len() > MAX_DISPLAY_ID_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
     }
+    env.storage().instance().set(&CredentialKeyExt::DisplayIdTemplate, &template);
+}
 
-    let mut credential: Credential = env.storage().persistent()
-        .get(&CredentialKey::Credential(credential_id))
-        .unwrap_or_else(|| panic!("Credential not found"));
+pub fn get_display_id_template(env: &Env) -> Option<String> {
+    env.storage().instance().get(&CredentialKeyExt::DisplayIdTemplate)
+}
 
-    credential.is_revoked = true;
+pub fn get_by_display_id(env: &Env, display_id: String) -> Option<u64> {
+    env.storage().persistent().get(&CredentialKeyExt::DisplayIdIndex(display_id))
+}
+
+/// Issue a credential whose IPFS document is encrypted, with a hint (e.g. a key fingerprint)
+/// telling clients who can decrypt it. Verification is unaffected.
+pub fn issue_encrypted_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    encryption_pubkey_hint: String,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    if encryption_pubkey_hint.len() > get_field_limits(env).0 {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    credential.encrypted = true;
+    credential.encryption_pubkey_hint = encryption_pubkey_hint;
+    record_issuance(env, credential).id
+}
+
+/// Issue a credential carrying a grade (issuer-defined scale, higher is better)
+pub fn issue_graded_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    grade: u32,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.grade = grade;
+    record_issuance(env, credential).id
+}
+
+/// Set the credit weight a credential carries in `compute_gpa`; issuer or admin only
+pub fn set_credential_credits(env: &Env, credential_id: u64, credits: u32, caller: Address) {
+    let mut credential = require_issuer_or_admin(env, credential_id, &caller);
+    credential.credits = credits;
     env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
 }
 
-pub fn get_user_credentials(env: &Env, user: Address) -> Vec<u64> {
-    env.storage().persistent()
-        .get(&CredentialKey::UserCredentials(user))
-        .unwrap_or(Vec::new(env))
+/// Credit-weighted mean grade, x100, over the user's valid credentials whose course id starts
+/// with `course_prefix` (empty matches every course):
+/// `round(100 * sum(grade * credits) / sum(credits))`, rounding half up. Ungraded (grade 0)
+/// and zero-credit credentials are left out; with nothing left the result is 0. The grade
+/// scale is whatever the issuers use.
+pub fn compute_gpa(env: &Env, user: Address, course_prefix: String) -> u32 {
+    let prefix = string_bytes(env, &course_prefix);
+    let mut weighted: u128 = 0;
+    let mut total_credits: u128 = 0;
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if credential.grade == 0 || credential.credits == 0 || !counts_as_valid(env, &credential) {
+            continue;
+        }
+        let course = string_bytes(env, &credential.course_id);
+        if course.len() < prefix.len() || course.slice(..prefix.len()) != prefix {
+            continue;
+        }
+        weighted += credential.grade as u128 * credential.credits as u128;
+        total_credits += credential.credits as u128;
+    }
+
+    if total_credits == 0 {
+        return 0;
+    }
+    let gpa = (weighted * 100 + total_credits / 2) / total_credits;
+    u32::try_from(gpa).unwrap_or(u32::MAX)
 }
 
-pub fn get_credential(env: &Env, credential_id: u64) -> Credential {
-    env.storage().persistent()
-        .get(&CredentialKey::Credential(credential_id))
-        .unwrap_or_else(|| panic!("Credential not found"))
+/// Issue a credential worth `xp` achievement points toward the recipient's `get_user_xp` total
+pub fn issue_credential_with_xp(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    xp: u32,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.xp = xp;
+    record_issuance(env, credential).id
 }
 
-pub fn get_credential_count(env: &Env) -> u64 {
-    env.storage().instance()
-        .get(&CredentialKey::CredentialCount)
-        .unwrap_or(0)
-}
\ No newline at end of file
+/// Highest-XP users, best first, at most `limit` (capped at LEADERBOARD_SIZE).
+///
+/// The board is a sorted vector of LEADERBOARD_SIZE entries rewritten on every XP change,
+/// so reads are a slice and writes cost O(LEADERBOARD_SIZE). Users who fall off it aren't
+/// tracked; they re-enter on their next XP gain, so after revocations shrink the board it can
+/// briefly list fewer users than are eligible.
+pub fn get_top_users(env: &Env, limit: u32) -> Vec<(Address, u64)> {
+    let board = get_leaderboard(env);
+    board.slice(0..limit.min(board.len()))
+}
+
+pub fn create_template(
+    env: &Env,
+    issuer: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    expires_after: u64,
+) -> u64 {
+    issuer.require_auth();
+    let title = sanitize_text_fields(env, title, &description);
+    validate_text_fields(env, &title, &description);
+
+    let id = next_id(env, env.storage().instance().get(&CredentialKeyExt::TemplateCount).unwrap_or(0));
+    let template = CredentialTemplate {
+        id,
+        issuer,
+        title,
+        description,
+        course_id,
+        expires_after,
+        version: 1,
+        prerequisite_courses: Vec::new(env),
+        auto_bundle_prereqs: false,
+    };
+    env.storage().persistent().set(&CredentialKeyExt::Template(id), &template);
+    env.storage().instance().set(&CredentialKeyExt::TemplateCount, &id);
+
+    id
+}
+
+/// Revise a template's content; credentials already issued keep their recorded version
+pub fn update_template(
+    env: &Env,
+    issuer: Address,
+    template_id: u64,
+    title: String,
+    description: String,
+    expires_after: u64,
+) -> u32 {
+    issuer.require_auth();
+
+    let mut template = load_template(env, template_id);
+    if template.issuer != issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    let title = sanitize_text_fields(env, title, &description);
+    validate_text_fields(env, &title, &description);
+
+    template.title = title;
+    template.description = description;
+    template.expires_after = expires_after;
+    template.version = template.version.checked_add(1)
+        .unwrap_or_else(|| panic_with_error!(env, CredentialError::CounterOverflow));
+    env.storage().persistent().set(&CredentialKeyExt::Template(template_id), &template);
+
+    publish_event(env, (symbol_short!("template"), symbol_short!("updated")), (template_id, template.version));
+    template.version
+}
+
+/// Courses a template's credential builds on. With `auto_bundle_prereqs`, every credential
+/// issued from it becomes a bundle of the recipient's best valid credential in each of them.
+pub fn set_template_prerequisites(
+    env: &Env,
+    issuer: Address,
+    template_id: u64,
+    prerequisite_courses: Vec<String>,
+    auto_bundle_prereqs: bool,
+) {
+    issuer.require_auth();
+
+    let mut template = load_template(env, template_id);
+    if template.issuer != issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+
+    template.prerequisite_courses = prerequisite_courses;
+    template.auto_bundle_prereqs = auto_bundle_prereqs;
+    env.storage().persistent().set(&CredentialKeyExt::Template(template_id), &template);
+}
+
+/// A private issuer's address is masked, as for credentials
+pub fn get_template(env: &Env, template_id: u64) -> CredentialTemplate {
+    let mut template = load_template(env, template_id);
+    template.issuer = public_issuer(env, template.issuer);
+    template
+}
+
+fn load_template(env: &Env, template_id: u64) -> CredentialTemplate {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::Template(template_id))
+        .unwrap_or_else(|| panic!("Template not found"))
+}
+
+pub fn get_template_version(env: &Env, template_id: u64) -> u32 {
+    load_template(env, template_id).version
+}
+
+/// Issue the template's current revision to `recipient`, bundling prerequisites if the template asks
+pub fn issue_from_template(env: &Env, issuer: Address, template_id: u64, recipient: Address, ipfs_hash: String) -> u64 {
+    let template = load_template(env, template_id);
+    if template.issuer != issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+
+    // Gathered before issuing, so the new credential can't count as its own prerequisite
+    let mut prerequisites = Vec::new(env);
+    if template.auto_bundle_prereqs {
+        let best = get_best_per_course(env, recipient.clone());
+        for course_id in template.prerequisite_courses.iter() {
+            if let Some(credential_id) = best.get(course_id) {
+                prerequisites.push_back(credential_id);
+            }
+        }
+    }
+
+    let mut credential = new_credential(
+        env,
+        issuer,
+        recipient,
+        template.title,
+        template.description,
+        template.course_id,
+        ipfs_hash,
+    );
+    if template.expires_after > 0 {
+        credential.expires_at = checked_sum(env, env.ledger().timestamp(), template.expires_after);
+    }
+    credential.template_id = template_id;
+    credential.template_version = template.version;
+    let credential_id = record_issuance(env, credential).id;
+
+    if !prerequisites.is_empty() {
+        env.storage().persistent().set(&CredentialKeyExt::BundleMembers(credential_id), &prerequisites);
+        publish_event(env, (symbol_short!("cred"), symbol_short!("bundled")), (credential_id, prerequisites));
+    }
+    credential_id
+}
+
+/// Sum of XP across a user's unrevoked credentials, for leaderboards
+pub fn get_user_xp(env: &Env, user: Address) -> u64 {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::UserXp(user))
+        .unwrap_or(0)
+}
+
+/// Issue a credential that builds on the recipient's `prerequisite_id`, so revoking the
+/// prerequisite can cascade to it (see `set_cascade_mode`). `completion_date` defaults to now
+/// and can't precede the prerequisite's.
+pub fn issue_dependent_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    prerequisite_id: u64,
+    completion_date: Option<u64>,
+) -> u64 {
+    let prerequisite = get_credential(env, prerequisite_id);
+    if prerequisite.recipient != recipient {
+        panic!("Prerequisite belongs to another recipient");
+    }
+
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    if let Some(completion_date) = completion_date {
+        credential.completion_date = completion_date;
+    }
+    // Finishing the advanced course before the basic one points to backdating
+    if credential.completion_date < prerequisite.completion_date {
+        panic_with_error!(env, CredentialError::TemporalInconsistency);
+    }
+    let credential_id = record_issuance(env, credential).id;
+
+    let mut dependents = get_dependents(env, prerequisite_id);
+    dependents.push_back(credential_id);
+    env.storage().persistent().set(&CredentialKey::DependentIndex(prerequisite_id), &dependents);
+
+    credential_id
+}
+
+pub fn get_dependents(env: &Env, prerequisite_id: u64) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKey::DependentIndex(prerequisite_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_cascade_mode(env: &Env, admin: Address, mode: CascadeMode) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::CascadeMode, &mode);
+}
+
+pub fn get_cascade_mode(env: &Env) -> CascadeMode {
+    env.storage().instance()
+        .get(&CredentialKey::CascadeMode)
+        .unwrap_or(CascadeMode::Off)
+}
+
+/// The revoked prerequisite that flagged this credential for review, if any
+pub fn get_review_flag(env: &Env, credential_id: u64) -> Option<u64> {
+    env.storage().persistent().get(&CredentialKey::NeedsReview(credential_id))
+}
+
+pub fn clear_review_flag(env: &Env, admin: Address, credential_id: u64) {
+    require_admin(env, &admin);
+    env.storage().persistent().remove(&CredentialKey::NeedsReview(credential_id));
+}
+
+/// Issue the same course credential to every recipient under a single issuer authorization
+pub fn issue_credentials_batch(
+    env: &Env,
+    issuer: Address,
+    recipients: Vec<Address>,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> Vec<u64> {
+    issuer.require_auth();
+
+    // Fail before any work rather than running out of resources part-way through
+    if recipients.len() > get_max_batch_size(env) {
+        panic_with_error!(env, CredentialError::BatchTooLarge);
+    }
+
+    if get_dedupe_recipients(env) {
+        for (index, recipient) in recipients.iter().enumerate() {
+            if recipients.first_index_of(&recipient) != Some(index as u32) {
+                panic_with_error!(env, CredentialError::DuplicateRecipient);
+            }
+        }
+    }
+
+    let mut credential_ids = Vec::new(env);
+    for recipient in recipients.iter() {
+        let credential = build_credential(
+            env,
+            issuer.clone(),
+            recipient,
+            title.clone(),
+            description.clone(),
+            course_id.clone(),
+            ipfs_hash.clone(),
+        );
+        credential_ids.push_back(record_issuance(env, credential).id);
+    }
+
+    credential_ids
+}
+
+pub fn set_max_batch_size(env: &Env, admin: Address, max_batch_size: u32) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::MaxBatchSize, &max_batch_size);
+}
+
+pub fn get_max_batch_size(env: &Env) -> u32 {
+    env.storage().instance()
+        .get(&CredentialKey::MaxBatchSize)
+        .unwrap_or(DEFAULT_MAX_BATCH_SIZE)
+}
+
+fn get_dedupe_recipients(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKey::DedupeRecipients)
+        .unwrap_or(false)
+}
+
+/// Issue a credential for a joint program that each listed co-issuer must also sign
+pub fn issue_cosigned_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    co_issuers: Vec<Address>,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.co_issuers = co_issuers;
+    record_issuance(env, credential).id
+}
+
+/// Issue a credential that is recorded now but only verifies from `active_from` onwards
+pub fn issue_scheduled_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    active_from: u64,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.active_from = active_from;
+    record_issuance(env, credential).id
+}
+
+pub fn verify_credential(env: &Env, credential_id: u64) -> bool {
+    let credential: Credential = env.storage().persistent()
+        .get(&CredentialKey::Credential(credential_id))
+        .unwrap_or_else(|| panic!("Credential not found"));
+
+    counts_as_valid(env, &credential)
+}
+
+/// Like `verify_credential`, but reports why a credential doesn't verify (see `resolve_status`)
+pub fn verify_credential_detailed(env: &Env, credential_id: u64) -> VerificationStatus {
+    resolve_status(env, credential_id)
+}
+
+/// The one authoritative status when several states overlap. The first match wins:
+///
+/// 1. `NotFound` / `Purged` - nothing stored under the id
+/// 2. `Migrated` - the new contract is authoritative, whatever the state here
+/// 3. `Revoked` - once the revocation is effective
+/// 4. `Suspended` - frozen pending investigation
+/// 5. `Superseded` - a reissue replaced it
+/// 6. `NotYetActive` - before `active_from`
+/// 7. `AwaitingCoSignatures` - when co-signatures are required
+/// 8. `DocumentPending` - no document attached yet
+/// 9. `Expired`, or `GracePeriod` while within grace after `expires_at`
+/// 10. `Invalid` - the issuer's verifier hook rejected it
+/// 11. `Valid`
+pub fn resolve_status(env: &Env, credential_id: u64) -> VerificationStatus {
+    match env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(credential_id)) {
+        Some(credential) => credential_status(env, &credential),
+        None if env.storage().persistent().has(&CredentialKey::PurgedIndex(credential_id)) => VerificationStatus::Purged,
+        None => VerificationStatus::NotFound,
+    }
+}
+
+/// Like `verify_credential_detailed`, but an otherwise-valid credential whose issuer has since
+/// lost authorization (removed from the issuer set and any course allowlist) reports
+/// `IssuerRevoked`, unless the admin has switched off `FeatureFlags::strict_issuer_invalidates`
+pub fn verify_credential_strict(env: &Env, credential_id: u64) -> VerificationStatus {
+    let status = verify_credential_detailed(env, credential_id);
+    if !matches!(status, VerificationStatus::Valid | VerificationStatus::GracePeriod)
+        || !get_strict_issuer_invalidates(env)
+    {
+        return status;
+    }
+
+    let credential = get_credential(env, credential_id);
+    let still_authorized = credential.issuer == get_admin(env)
+        || is_issuer(env, credential.issuer.clone())
+        || get_course_issuers(env, credential.course_id).contains(&credential.issuer);
+    if still_authorized {
+        status
+    } else {
+        VerificationStatus::IssuerRevoked
+    }
+}
+
+fn get_strict_issuer_invalidates(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKeyExt::StrictIssuerInvalidates)
+        .unwrap_or(true)
+}
+
+/// Recipient marks their credential private (or public again)
+pub fn set_credential_private(env: &Env, credential_id: u64, recipient: Address, private: bool) {
+    let mut credential = require_recipient(env, credential_id, &recipient);
+    credential.is_private = private;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+}
+
+/// Recipient pre-authorizes `verifier` (e.g. an employer) to verify a private credential
+pub fn grant_verifier(env: &Env, credential_id: u64, recipient: Address, verifier: Address) {
+    require_recipient(env, credential_id, &recipient);
+
+    let mut grants = get_verifier_grants(env, credential_id);
+    if !grants.contains(&verifier) {
+        grants.push_back(verifier);
+        env.storage().persistent().set(&CredentialKeyExt::VerifierGrants(credential_id), &grants);
+    }
+}
+
+pub fn revoke_verifier(env: &Env, credential_id: u64, recipient: Address, verifier: Address) {
+    require_recipient(env, credential_id, &recipient);
+
+    let mut grants = get_verifier_grants(env, credential_id);
+    if let Some(index) = grants.first_index_of(&verifier) {
+        grants.remove(index);
+        env.storage().persistent().set(&CredentialKeyExt::VerifierGrants(credential_id), &grants);
+    }
+}
+
+pub fn get_verifier_grants(env: &Env, credential_id: u64) -> Vec<Address> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::VerifierGrants(credential_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Verify as an authenticated `verifier`. Private credentials answer only verifiers the
+/// recipient has granted; public ones ignore grants.
+pub fn verify_credential_as(env: &Env, credential_id: u64, verifier: Address) -> VerificationStatus {
+    verifier.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if credential.is_private && !get_verifier_grants(env, credential_id).contains(&verifier) {
+        panic_with_error!(env, CredentialError::VerifierNotGranted);
+    }
+    credential_status(env, &credential)
+}
+
+/// `get_credential` for unauthenticated readers: a private issuer's address is replaced
+/// with this contract's own address. Verification always uses the real issuer.
+pub fn get_public_credential(env: &Env, credential_id: u64) -> Credential {
+    mask_issuer(env, get_credential(env, credential_id))
+}
+
+/// `get_credential` for an authenticated `caller`; only the admin and the issuer itself see
+/// a private issuer's real address
+pub fn get_credential_as(env: &Env, credential_id: u64, caller: Address) -> Credential {
+    caller.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if caller == credential.issuer || caller == get_admin(env) {
+        credential
+    } else {
+        mask_issuer(env, credential)
+    }
+}
+
+/// Swap a private issuer's address for this contract's own
+pub fn mask_issuer(env: &Env, mut credential: Credential) -> Credential {
+    credential.issuer = public_issuer(env, credential.issuer);
+    credential
+}
+
+fn public_issuer(env: &Env, issuer: Address) -> Address {
+    if is_issuer_private(env, issuer.clone()) {
+        env.current_contract_address()
+    } else {
+        issuer
+    }
+}
+
+// Load a credential on behalf of its authenticated recipient
+fn require_recipient(env: &Env, credential_id: u64, recipient: &Address) -> Credential {
+    recipient.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if credential.recipient != *recipient {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    credential
+}
+
+/// Auth-free validity check for other contracts; unknown ids are simply invalid
+pub fn is_valid(env: &Env, credential_id: u64) -> bool {
+    matches!(
+        verify_credential_detailed(env, credential_id),
+        VerificationStatus::Valid | VerificationStatus::GracePeriod
+    )
+}
+
+/// Auth-free check that `holder` owns a currently valid credential, for gating in other contracts
+pub fn verify_credential_for(env: &Env, credential_id: u64, holder: Address) -> bool {
+    match env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(credential_id)) {
+        Some(credential) => credential.recipient == holder && counts_as_valid(env, &credential),
+        None => false,
+    }
+}
+
+/// Record a listed co-issuer's signature on a credential
+pub fn cosign_credential(env: &Env, credential_id: u64, co_issuer: Address) {
+    co_issuer.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+
+    if !credential.co_issuers.contains(&co_issuer) {
+        panic!("Not a co-issuer of this credential");
+    }
+    if credential.co_signers.contains(&co_issuer) {
+        panic!("Already co-signed");
+    }
+
+    credential.co_signers.push_back(co_issuer.clone());
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("cosigned")), (credential_id, co_issuer));
+}
+
+/// Whether every listed co-issuer has signed the credential
+pub fn fully_signed(env: &Env, credential_id: u64) -> bool {
+    is_fully_signed(&get_credential(env, credential_id))
+}
+
+/// When enabled, credentials only verify once all of their co-issuers have signed
+pub fn set_require_co_signatures(env: &Env, admin: Address, required: bool) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::RequireCoSignatures, &required);
+}
+
+pub fn get_require_co_signatures(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKey::RequireCoSignatures)
+        .unwrap_or(false)
+}
+
+fn get_allow_issuer_self_revoke(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKey::AllowIssuerSelfRevoke)
+        .unwrap_or(false)
+}
+
+pub fn revoke_credential(
+    env: &Env,
+    credential_id: u64,
+    revoker: Address,
+    reason: RevocationReason,
+    note: Option<String>,
+) {
+    let note = note.unwrap_or(String::from_str(env, ""));
+    revoke(env, credential_id, revoker, reason, note);
+}
+
+/// Revoke effective from a future `effective_from` (e.g. a suspension starting next month): the
+/// credential keeps verifying, and reads as unrevoked everywhere else, until then. It is
+/// scheduled as a pending revocation no earlier than the revocation delay allows; stats, history
+/// and cascades wait for `execute_revocation`, though it reads as revoked from the effective
+/// date on. A past or zero date is an ordinary revocation.
+pub fn revoke_credential_effective(
+    env: &Env,
+    credential_id: u64,
+    revoker: Address,
+    reason: RevocationReason,
+    note: Option<String>,
+    effective_from: u64,
+) {
+    let note = note.unwrap_or(String::from_str(env, ""));
+    if effective_from <= env.ledger().timestamp() {
+        revoke(env, credential_id, revoker, reason, note);
+        return;
+    }
+
+    let mut credential = check_revocable(env, credential_id, &revoker);
+    let executable_at = effective_from.max(revocation_executable_at(env, &credential.issuer));
+    credential.revoked_from = executable_at;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+
+    schedule_revocation(env, credential_id, PendingRevocation { revoker, reason, note, executable_at });
+}
+
+/// Revoke a credential identified only by its content fingerprint (e.g. scanned from a QR code)
+pub fn revoke_by_fingerprint(
+    env: &Env,
+    fingerprint: BytesN<32>,
+    revoker: Address,
+    reason: RevocationReason,
+    note: Option<String>,
+) {
+    let credential_id: u64 = env.storage().persistent()
+        .get(&CredentialKey::FingerprintIndex(fingerprint))
+        .unwrap_or_else(|| panic!("Credential not found"));
+
+    revoke_credential(env, credential_id, revoker, reason, note);
+}
+
+/// Apply an external blocklist (e.g. a regulator's list of fraudulent fingerprints) in one
+/// call. Matches are revoked immediately, bypassing any revocation delay; unknown fingerprints
+/// and credentials already revoked or pending revocation are skipped. Returns how many were revoked.
+pub fn revoke_by_fingerprints(env: &Env, admin: Address, fingerprints: Vec<BytesN<32>>, reason: RevocationReason) -> u32 {
+    require_admin(env, &admin);
+    require_not_paused(env);
+    if fingerprints.len() > get_max_batch_size(env) {
+        panic_with_error!(env, CredentialError::BatchTooLarge);
+    }
+
+    let mut revoked = Vec::new(env);
+    for fingerprint in fingerprints.iter() {
+        let Some(credential_id) = env.storage().persistent()
+            .get::<_, u64>(&CredentialKey::FingerprintIndex(fingerprint))
+        else {
+            continue;
+        };
+        let already_handled = get_credential(env, credential_id).is_revoked
+            || env.storage().persistent().has(&CredentialKey::PendingRevocation(credential_id));
+        if already_handled {
+            continue;
+        }
+
+        revoked.append(&apply_revocation(env, credential_id, admin.clone(), reason, String::from_str(env, "blocklist")));
+    }
+
+    let count = revoked.len();
+    log_bulk_action(env, admin, revoked);
+    count
+}
+
+/// Every revocation applied under `reason`, in order, for reporting. Reinstated
+/// credentials stay listed; check their current status if that matters.
+pub fn get_revocations_by_reason(env: &Env, reason: RevocationReason) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKey::RevocationsByReason(reason))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn get_user_credentials(env: &Env, user: Address) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKey::UserCredentials(user))
+        .unwrap_or(Vec::new(env))
+}
+
+/// A user's credential ids in ascending order. `get_user_credentials` is in insertion order,
+/// which only matches id order as long as nothing is ever appended out of sequence.
+pub fn get_user_credentials_sorted(env: &Env, user: Address) -> Vec<u64> {
+    let mut sorted: Vec<u64> = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let position = sorted.iter().position(|existing| existing > credential_id);
+        match position {
+            Some(index) => sorted.insert(index as u32, credential_id),
+            None => sorted.push_back(credential_id),
+        }
+    }
+
+    sorted
+}
+
+/// Cursor-based paging over a user's credentials: returns up to `limit` ids strictly
+/// greater than `after_id`. Ids are monotonic, so pages stay stable under concurrent issuance.
+pub fn get_user_credentials_after(env: &Env, user: Address, after_id: u64, limit: u32) -> Vec<u64> {
+    let mut page = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        if page.len() >= limit {
+            break;
+        }
+        if credential_id > after_id {
+            page.push_back(credential_id);
+        }
+    }
+
+    page
+}
+
+/// A user's non-revoked credential ids, also dropping lapsed ones when `exclude_expired` is set
+pub fn get_active_user_credentials(env: &Env, user: Address, exclude_expired: bool) -> Vec<u64> {
+    let mut active = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if is_revoked_now(env, &credential) || (exclude_expired && is_purgeable(env, &credential)) {
+            continue;
+        }
+        active.push_back(credential_id);
+    }
+
+    active
+}
+
+/// A user's credentials whose title starts with `prefix`. Matching is on bytes, prefix
+/// only, and case-insensitive for ASCII letters; other characters must match exactly.
+/// (Contract function names are capped at 32 characters, hence the shorter name.)
+pub fn search_by_title_prefix(env: &Env, user: Address, prefix: String) -> Vec<u64> {
+    let prefix = string_bytes(env, &prefix);
+
+    let mut matching = Vec::new(env);
+    for credential_id in get_user_credentials(env, user).iter() {
+        let title = string_bytes(env, &get_credential(env, credential_id).title);
+        if title.len() >= prefix.len()
+            && title.iter().zip(prefix.iter()).all(|(a, b)| a.eq_ignore_ascii_case(&b))
+        {
+            matching.push_back(credential_id);
+        }
+    }
+
+    matching
+}
+
+/// A user's credential ids whose `verify_credential_detailed` status equals `status`
+pub fn get_user_credentials_by_status(env: &Env, user: Address, status: VerificationStatus) -> Vec<u64> {
+    let mut matching = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        if credential_status(env, &get_credential(env, credential_id)) == status {
+            matching.push_back(credential_id);
+        }
+    }
+
+    matching
+}
+
+/// Every credential issued under `course_id`, in issuance order
+pub fn get_course_credentials(env: &Env, course_id: String) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::CourseCredentials(course_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Roster of what `issuer` gave for `course_id`: the course index filtered to that issuer,
+/// in course-index order. Purged credentials drop out.
+pub fn get_credentials_by_issuer_and_course(env: &Env, issuer: Address, course_id: String) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+    for credential_id in get_course_credentials(env, course_id).iter() {
+        if try_get_credential(env, credential_id).is_some_and(|c| c.issuer == issuer) {
+            ids.push_back(credential_id);
+        }
+    }
+    ids
+}
+
+/// Point every credential of `old_course_id` at `new_course_id` after a catalog merge,
+/// moving them to the end of the new course's index. Returns how many were remapped.
+pub fn remap_course(env: &Env, admin: Address, old_course_id: String, new_course_id: String) -> u32 {
+    require_admin(env, &admin);
+    if old_course_id == new_course_id {
+        return 0;
+    }
+
+    let moved = get_course_credentials(env, old_course_id.clone());
+    let mut merged = get_course_credentials(env, new_course_id.clone());
+    for credential_id in moved.iter() {
+        if let Some(mut credential) = try_get_credential(env, credential_id) {
+            let old_fingerprint = compute_fingerprint(env, &credential);
+            credential.course_id = new_course_id.clone();
+            store_with_fingerprint(env, &credential, old_fingerprint);
+        }
+        merged.push_back(credential_id);
+    }
+
+    env.storage().persistent().set(&CredentialKeyExt::CourseCredentials(new_course_id.clone()), &merged);
+    env.storage().persistent().remove(&CredentialKeyExt::CourseCredentials(old_course_id.clone()));
+
+    publish_event(env, (symbol_short!("course"), symbol_short!("remapped")), (old_course_id, new_course_id, moved.len()));
+    moved.len()
+}
+
+/// For each course, the user's best currently valid credential: highest grade, and among
+/// equal grades (including ungraded retakes) the latest issued
+pub fn get_best_per_course(env: &Env, user: Address) -> Map<String, u64> {
+    let mut best: Map<String, u64> = Map::new(env);
+    let mut best_grades: Map<String, u32> = Map::new(env);
+
+    // Ids ascend, so `>=` lets later credentials win ties
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if !counts_as_valid(env, &credential) {
+            continue;
+        }
+        let beats_current = best_grades
+            .get(credential.course_id.clone())
+            .is_none_or(|grade| credential.grade >= grade);
+        if beats_current {
+            best_grades.set(credential.course_id.clone(), credential.grade);
+            best.set(credential.course_id, credential_id);
+        }
+    }
+
+    best
+}
+
+/// Every pair of the user's unrevoked credentials that share a course id, as (earlier, later)
+/// id pairs; credentials without a course are never reported. Surfaces double-issuance.
+pub fn find_duplicate_credentials(env: &Env, user: Address) -> Vec<(u64, u64)> {
+    let mut seen: Map<String, Vec<u64>> = Map::new(env);
+    let mut duplicates = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if is_revoked_now(env, &credential) || credential.course_id.is_empty() {
+            continue;
+        }
+        let mut same_course = seen.get(credential.course_id.clone()).unwrap_or(Vec::new(env));
+        for earlier in same_course.iter() {
+            duplicates.push_back((earlier, credential_id));
+        }
+        same_course.push_back(credential_id);
+        seen.set(credential.course_id, same_course);
+    }
+
+    duplicates
+}
+
+/// Course ids for which both users hold a currently valid credential, in `user_a`'s order
+pub fn get_common_courses(env: &Env, user_a: Address, user_b: Address) -> Vec<String> {
+    let courses_b = valid_courses(env, user_b);
+
+    let mut common = Vec::new(env);
+    for course_id in valid_courses(env, user_a).iter() {
+        if courses_b.contains(&course_id) {
+            common.push_back(course_id);
+        }
+    }
+
+    common
+}
+
+/// Feature one of the recipient's own credentials at the top of their profile
+pub fn pin_credential(env: &Env, recipient: Address, credential_id: u64) {
+    recipient.require_auth();
+
+    if get_credential(env, credential_id).recipient != recipient {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+
+    let mut pinned = get_pinned_credentials(env, recipient.clone());
+    if pinned.contains(credential_id) {
+        return;
+    }
+    if pinned.len() >= MAX_PINNED_CREDENTIALS {
+        panic!("Too many pinned credentials");
+    }
+    pinned.push_back(credential_id);
+    env.storage().persistent().set(&CredentialKey::PinnedCredentials(recipient), &pinned);
+}
+
+pub fn unpin_credential(env: &Env, recipient: Address, credential_id: u64) {
+    recipient.require_auth();
+
+    let mut pinned = get_pinned_credentials(env, recipient.clone());
+    if let Some(index) = pinned.first_index_of(credential_id) {
+        pinned.remove(index);
+        env.storage().persistent().set(&CredentialKey::PinnedCredentials(recipient), &pinned);
+    }
+}
+
+/// Pinned credential ids, in the order they were pinned
+pub fn get_pinned_credentials(env: &Env, user: Address) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKey::PinnedCredentials(user))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Recipient removes a credential from their public profile (summaries and pins) without
+/// touching its validity: it still verifies by id and stays in the issuer's records
+pub fn recipient_hide_credential(env: &Env, credential_id: u64, recipient: Address) {
+    let mut credential = require_recipient(env, credential_id, &recipient);
+    credential.hidden_by_recipient = true;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+
+    let mut pinned = get_pinned_credentials(env, recipient.clone());
+    if let Some(index) = pinned.first_index_of(credential_id) {
+        pinned.remove(index);
+        env.storage().persistent().set(&CredentialKey::PinnedCredentials(recipient), &pinned);
+    }
+}
+
+/// Put a hidden credential back on the profile; it isn't re-pinned
+pub fn recipient_unhide(env: &Env, credential_id: u64, recipient: Address) {
+    let mut credential = require_recipient(env, credential_id, &recipient);
+    credential.hidden_by_recipient = false;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+}
+
+/// Status, issuer, type and dates of every credential a user shows on their profile, in one call
+pub fn get_user_profile_summary(env: &Env, user: Address) -> Vec<VerificationSummary> {
+    summarize(env, get_user_credentials(env, user))
+}
+
+/// Paged `get_user_profile_summary`, using the same cursor as `get_user_credentials_after`.
+/// Hidden credentials are skipped without using up the page, so continue from the last
+/// returned id; a short page means the walk is done.
+pub fn get_user_profile_summary_after(
+    env: &Env,
+    user: Address,
+    after_id: u64,
+    limit: u32,
+) -> Vec<VerificationSummary> {
+    let mut page = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        if page.len() >= limit {
+            break;
+        }
+        if credential_id > after_id && !get_credential(env, credential_id).hidden_by_recipient {
+            page.push_back(credential_id);
+        }
+    }
+
+    summarize(env, page)
+}
+
+pub fn get_credential(env: &Env, credential_id: u64) -> Credential {
+    env.storage().persistent()
+        .get(&CredentialKey::Credential(credential_id))
+        .unwrap_or_else(|| panic!("Credential not found"))
+}
+
+/// Place a credential in a cohort (e.g. "Fall 2024"). Cohorts are independent of course_id
+/// and may span courses; a credential belongs to at most one.
+pub fn assign_cohort(env: &Env, issuer: Address, credential_id: u64, cohort_id: String) {
+    issuer.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+    if credential.issuer != issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    if cohort_id.is_empty() || cohort_id.len() > get_field_limits(env).0 {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    if !credential.cohort_id.is_empty() {
+        panic!("Credential already in a cohort");
+    }
+
+    let mut members = get_cohort_credentials(env, cohort_id.clone());
+    members.push_back(credential_id);
+    env.storage().persistent().set(&CredentialKeyExt::CohortIndex(cohort_id.clone()), &members);
+
+    credential.cohort_id = cohort_id;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+}
+
+pub fn get_cohort_credentials(env: &Env, cohort_id: String) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::CohortIndex(cohort_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Hash signers approve to authorize `revoke_cohort(cohort_id, reason)`
+pub fn revoke_cohort_action_hash(env: &Env, cohort_id: String, reason: RevocationReason) -> BytesN<32> {
+    let action = (Symbol::new(env, "revoke_cohort"), cohort_id, reason, get_admin(env));
+    env.crypto().sha256(&action.to_xdr(env)).into()
+}
+
+/// Immediately revoke every still-valid credential in a cohort; logged for `undo_bulk_action`.
+/// Returns how many were revoked. Once a signer set is configured this also needs its threshold
+/// of approvals on `revoke_cohort_action_hash`.
+pub fn revoke_cohort(env: &Env, cohort_id: String, revoker: Address, reason: RevocationReason) -> u32 {
+    require_admin(env, &revoker);
+    require_not_paused(env);
+
+    let (_, threshold) = get_admin_signers(env);
+    if threshold > 0 {
+        consume_approvals(env, revoke_cohort_action_hash(env, cohort_id.clone(), reason), threshold);
+    }
+
+    let note = String::from_str(env, "Cohort revocation");
+    let revoked = revoke_bulk(env, &revoker, get_cohort_credentials(env, cohort_id.clone()), reason, note);
+
+    publish_event(env, (symbol_short!("cohort"), symbol_short!("revoked")), (cohort_id, revoked));
+    revoked
+}
+
+/// Hash signers approve to authorize `revoke_by_course(course_id, reason)`
+pub fn revoke_by_course_action_hash(env: &Env, course_id: String, reason: RevocationReason) -> BytesN<32> {
+    let action = (Symbol::new(env, "revoke_by_course"), course_id, reason, get_admin(env));
+    env.crypto().sha256(&action.to_xdr(env)).into()
+}
+
+/// Revoke every unrevoked credential issued for a course, immediately; logged for `undo_bulk_action`.
+/// Once a signer set is configured this also needs its threshold of approvals on
+/// `revoke_by_course_action_hash`.
+pub fn revoke_by_course(env: &Env, admin: Address, course_id: String, reason: RevocationReason) -> u32 {
+    require_admin(env, &admin);
+    require_not_paused(env);
+
+    let (_, threshold) = get_admin_signers(env);
+    if threshold > 0 {
+        consume_approvals(env, revoke_by_course_action_hash(env, course_id.clone(), reason), threshold);
+    }
+
+    let note = String::from_str(env, "Course revocation");
+    let revoked = revoke_bulk(env, &admin, get_course_credentials(env, course_id.clone()), reason, note);
+
+    publish_event(env, (symbol_short!("course"), symbol_short!("revoked")), (course_id, revoked));
+    revoked
+}
+
+/// Hash signers approve to authorize `revoke_all_by_issuer(issuer, reason)`
+pub fn revoke_all_by_issuer_action_hash(env: &Env, issuer: Address, reason: RevocationReason) -> BytesN<32> {
+    let action = (Symbol::new(env, "revoke_all_by_issuer"), issuer, reason, get_admin(env));
+    env.crypto().sha256(&action.to_xdr(env)).into()
+}
+
+/// Revoke every unrevoked credential an issuer has issued, immediately; logged for `undo_bulk_action`.
+/// Once a signer set is configured this also needs its threshold of approvals on
+/// `revoke_all_by_issuer_action_hash`.
+pub fn revoke_all_by_issuer(env: &Env, admin: Address, issuer: Address, reason: RevocationReason) -> u32 {
+    require_admin(env, &admin);
+    require_not_paused(env);
+
+    let (_, threshold) = get_admin_signers(env);
+    if threshold > 0 {
+        consume_approvals(env, revoke_all_by_issuer_action_hash(env, issuer.clone(), reason), threshold);
+    }
+
+    let note = String::from_str(env, "Issuer revocation");
+    let revoked = revoke_bulk(env, &admin, issuer_credential_ids(env, issuer.clone()), reason, note);
+
+    publish_event(env, (symbol_short!("issuer"), symbol_short!("revoked")), (issuer, revoked));
+    revoked
+}
+
+/// Reinstate everything a logged bulk revocation revoked, cascaded dependents included, within
+/// `get_bulk_undo_window` of it. Credentials reinstated individually since, and those whose
+/// issuer policy forbids reinstatement, are left alone.
+pub fn undo_bulk_action(env: &Env, admin: Address, action_id: u64) {
+    require_admin(env, &admin);
+
+    let mut action = get_bulk_action(env, action_id).unwrap_or_else(|| panic!("Bulk action not found"));
+    if action.undone {
+        panic!("Bulk action already undone");
+    }
+    if env.ledger().timestamp() > action.performed_at.saturating_add(get_bulk_undo_window(env)) {
+        panic_with_error!(env, CredentialError::UndoWindowClosed);
+    }
+
+    for credential_id in action.credential_ids.iter() {
+        let reinstate = try_get_credential(env, credential_id).is_some_and(|credential| credential.is_revoked)
+            && reinstatement_allowed(env, credential_id);
+        if reinstate {
+            apply_reinstatement(env, credential_id);
+            close_open_appeal(env, credential_id, "Bulk revocation undone");
+        }
+    }
+
+    action.undone = true;
+    env.storage().persistent().set(&CredentialKeyExt::BulkActionLog(action_id), &action);
+
+    publish_event(env, (symbol_short!("bulk"), symbol_short!("undone")), (action_id, admin));
+}
+
+/// Logged bulk revocation by id; ids run from 1 to `get_bulk_action_count`
+pub fn get_bulk_action(env: &Env, action_id: u64) -> Option<BulkAction> {
+    env.storage().persistent().get(&CredentialKeyExt::BulkActionLog(action_id))
+}
+
+pub fn get_bulk_action_count(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKeyExt::BulkActionCount)
+        .unwrap_or(0)
+}
+
+pub fn set_bulk_undo_window(env: &Env, admin: Address, window: u64) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::BulkUndoWindow, &window);
+}
+
+pub fn get_bulk_undo_window(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKeyExt::BulkUndoWindow)
+        .unwrap_or(DEFAULT_BULK_UNDO_WINDOW)
+}
+
+/// Revoked flag for each id, in input order, without computing expiry or hook status.
+/// Unknown (or purged) ids read as `false`.
+pub fn check_revoked_batch(env: &Env, ids: Vec<u64>) -> Vec<bool> {
+    let mut flags = Vec::new(env);
+    for credential_id in ids.iter() {
+        let revoked = try_get_credential(env, credential_id).is_some_and(|c| is_revoked_now(env, &c));
+        flags.push_back(revoked);
+    }
+    flags
+}
+
+/// Namespace prepended to every event topic, so indexers can tell deployments apart
+pub fn set_event_namespace(env: &Env, admin: Address, namespace: Symbol) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::EventNamespace, &namespace);
+}
+
+pub fn get_event_namespace(env: &Env) -> Symbol {
+    env.storage().instance()
+        .get(&CredentialKeyExt::EventNamespace)
+        .unwrap_or(DEFAULT_EVENT_NAMESPACE)
+}
+
+/// Whether two credentials carry the same content. Identity and bookkeeping are ignored: ids,
+/// display ids, renewal-chain links, the template they came from, co-signing progress and the
+/// recipient's hidden flag. With `ignore_revocation`, revocation and suspension are ignored too.
+/// Meant for dedup and migration checks.
+pub fn credentials_equal(env: &Env, id_a: u64, id_b: u64, ignore_revocation: bool) -> bool {
+    let a = get_credential(env, id_a);
+    let mut b = get_credential(env, id_b);
+
+    b.id = a.id;
+    b.display_id = a.display_id.clone();
+    b.supersedes = a.supersedes;
+    b.superseded_by = a.superseded_by;
+    b.template_id = a.template_id;
+    b.template_version = a.template_version;
+    b.co_signers = a.co_signers.clone();
+    b.hidden_by_recipient = a.hidden_by_recipient;
+    if ignore_revocation {
+        b.is_revoked = a.is_revoked;
+        b.revoked_from = a.revoked_from;
+        b.is_suspended = a.is_suspended;
+    }
+    a == b
+}
+
+/// Vouch for someone else's valid credential, once per endorser
+pub fn endorse_credential(env: &Env, credential_id: u64, endorser: Address) {
+    endorser.require_auth();
+    require_not_paused(env);
+
+    let credential = get_credential(env, credential_id);
+    if credential.recipient == endorser {
+        panic!("Cannot endorse own credential");
+    }
+    if !counts_as_valid(env, &credential) {
+        panic!("Credential is not valid");
+    }
+
+    let mut endorsements = get_endorsements(env, credential_id);
+    if endorsements.iter().any(|endorsement| endorsement.endorser == endorser) {
+        panic_with_error!(env, CredentialError::AlreadyEndorsed);
+    }
+
+    let held = get_user_credentials(env, endorser.clone())
+        .iter()
+        .filter(|id| counts_as_valid(env, &get_credential(env, *id)))
+        .count() as u64;
+    let weight = 1 + held;
+    endorsements.push_back(Endorsement { endorser: endorser.clone(), weight, endorsed_at: env.ledger().timestamp() });
+    env.storage().persistent().set(&CredentialKeyExt::Endorsements(credential_id), &endorsements);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("endorsed")), (credential_id, endorser, weight));
+}
+
+/// Endorsements of a credential, oldest first
+pub fn get_endorsements(env: &Env, credential_id: u64) -> Vec<Endorsement> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::Endorsements(credential_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Sum of endorsement weights, so one endorsement from a well-credentialed professional
+/// outweighs several from addresses with no credentials
+pub fn get_endorsement_score(env: &Env, credential_id: u64) -> u64 {
+    get_endorsements(env, credential_id)
+        .iter()
+        .fold(0u64, |score, endorsement| score.saturating_add(endorsement.weight))
+}
+
+/// Status changes of a credential, oldest first
+pub fn get_history(env: &Env, credential_id: u64) -> Vec<StatusChange> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::History(credential_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// `get_history` for up to MAX_HISTORY_BATCH credentials at once, keyed by id
+pub fn get_histories(env: &Env, ids: Vec<u64>) -> Map<u64, Vec<StatusChange>> {
+    if ids.len() > MAX_HISTORY_BATCH {
+        panic_with_error!(env, CredentialError::BatchTooLarge);
+    }
+
+    let mut histories = Map::new(env);
+    for credential_id in ids.iter() {
+        histories.set(credential_id, get_history(env, credential_id));
+    }
+    histories
+}
+
+/// XDR encoding of the persistent-storage key a credential lives under, for tooling that
+/// builds transaction footprints or preflights storage reads
+pub fn credential_storage_key(env: &Env, credential_id: u64) -> Bytes {
+    CredentialKey::Credential(credential_id).to_xdr(env)
+}
+
+/// Non-panicking lookup: `None` for ids that were never issued (or have been purged)
+pub fn try_get_credential(env: &Env, credential_id: u64) -> Option<Credential> {
+    env.storage().persistent().get(&CredentialKey::Credential(credential_id))
+}
+
+/// Fill in the document of a credential issued with an empty `ipfs_hash`; until then it
+/// verifies as `DocumentPending`
+pub fn attach_document(env: &Env, credential_id: u64, ipfs_hash: String, issuer: Address) {
+    issuer.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+    if credential.issuer != issuer {
+        panic!("Only the issuer can attach the document");
+    }
+    if !credential.ipfs_hash.is_empty() {
+        panic!("Document already attached");
+    }
+    if ipfs_hash.is_empty() {
+        panic!("Empty IPFS hash");
+    }
+
+    // The content fingerprint covers the document, so re-index it. The merkle tree is
+    // append-only, so keep the issuance-time leaf for inclusion proofs.
+    let current_leaf = compute_fingerprint(env, &credential);
+    env.storage().persistent().remove(&CredentialKey::FingerprintIndex(current_leaf.clone()));
+    if !env.storage().persistent().has(&CredentialKey::IssuedLeaf(credential_id)) {
+        env.storage().persistent().set(&CredentialKey::IssuedLeaf(credential_id), &current_leaf);
+    }
+
+    credential.ipfs_hash = ipfs_hash;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    env.storage().persistent().set(&CredentialKey::FingerprintIndex(compute_fingerprint(env, &credential)), &credential_id);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("document")), credential_id);
+}
+
+/// Apply only the provided fields of `updates` in one write; issuer or admin only.
+/// Revoked credentials are frozen.
+pub fn update_credential_fields(env: &Env, credential_id: u64, updates: CredentialUpdate, caller: Address) {
+    caller.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+    if caller != get_admin(env) && credential.issuer != caller {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    if is_revoked_now(env, &credential) {
+        panic_with_error!(env, CredentialError::AlreadyRevoked);
+    }
+
+    let old_fingerprint = compute_fingerprint(env, &credential);
+    let sanitize = get_sanitize_text(env);
+    if let Some(title) = updates.title {
+        credential.title = if sanitize { sanitize_title(env, &title) } else { title };
+    }
+    if let Some(description) = updates.description {
+        if sanitize {
+            reject_control_bytes(env, &description, true);
+        }
+        credential.description = description;
+    }
+    if let Some(ipfs_hash) = updates.ipfs_hash {
+        credential.ipfs_hash = ipfs_hash;
+    }
+    if let Some(expires_at) = updates.expires_at {
+        reindex_expiry(env, credential_id, credential.expires_at, expires_at);
+        credential.expires_at = expires_at;
+    }
+    validate_text_fields(env, &credential.title, &credential.description);
+
+    store_with_fingerprint(env, &credential, old_fingerprint);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("updated")), (credential_id, caller));
+}
+
+/// Set a free-form metadata entry (e.g. "semester" -> "2024-fall"); issuer or admin only
+pub fn set_metadata(env: &Env, caller: Address, credential_id: u64, key: String, value: String) {
+    set_metadata_batch(env, caller, Vec::from_array(env, [credential_id]), key, value);
+}
+
+/// Apply the same metadata entry across a cohort. The whole batch is rejected if the
+/// caller isn't the issuer (or admin) of every credential in it.
+pub fn set_metadata_batch(env: &Env, caller: Address, ids: Vec<u64>, key: String, value: String) {
+    caller.require_auth();
+
+    let is_admin = caller == get_admin(env);
+    let mut credentials = Vec::new(env);
+    for credential_id in ids.iter() {
+        let credential = get_credential(env, credential_id);
+        if !is_admin && credential.issuer != caller {
+            panic_with_error!(env, CredentialError::NotYourCredential);
+        }
+        credentials.push_back(credential);
+    }
+
+    for mut credential in credentials.iter() {
+        credential.metadata.set(key.clone(), value.clone());
+        env.storage().persistent().set(&CredentialKey::Credential(credential.id), &credential);
+    }
+}
+
+pub fn get_metadata(env: &Env, credential_id: u64, key: String) -> Option<String> {
+    get_credential(env, credential_id).metadata.get(key)
+}
+
+/// Add or replace the localized title and description for a language code (e.g. "es")
+pub fn add_translation(
+    env: &Env,
+    credential_id: u64,
+    lang: String,
+    title: String,
+    description: String,
+    issuer: Address,
+) {
+    issuer.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+    if credential.issuer != issuer {
+        panic!("Only the issuer can translate this credential");
+    }
+    let title = sanitize_text_fields(env, title, &description);
+    validate_text_fields(env, &title, &description);
+
+    credential.translations.set(lang, (title, description));
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+}
+
+/// Localized (title, description), falling back to the default fields for missing languages
+pub fn get_localized(env: &Env, credential_id: u64, lang: String) -> (String, String) {
+    let credential = get_credential(env, credential_id);
+    credential.translations
+        .get(lang)
+        .unwrap_or((credential.title, credential.description))
+}
+
+/// `caller` is only needed while the issuer is private: then just the issuer itself and the
+/// admin may list its credentials
+pub fn get_issuer_credentials(env: &Env, issuer: Address, caller: Option<Address>) -> Vec<u64> {
+    require_issuer_listing_access(env, &issuer, caller);
+    issuer_credential_ids(env, issuer)
+}
+
+fn issuer_credential_ids(env: &Env, issuer: Address) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKey::IssuerCredentials(issuer))
+        .unwrap_or(Vec::new(env))
+}
+
+fn require_issuer_listing_access(env: &Env, issuer: &Address, caller: Option<Address>) {
+    if !is_issuer_private(env, issuer.clone()) {
+        return;
+    }
+
+    let Some(caller) = caller else {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    };
+    caller.require_auth();
+    if caller != *issuer && caller != get_admin(env) {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+}
+
+/// First page of an issuer's credentials as an XDR `ExportEnvelope`; continue with
+/// `export_issuer_credentials_after` while `next_after` is non-zero. Purged credentials are
+/// skipped. `caller` is gated as for `get_issuer_credentials`.
+pub fn export_issuer_credentials(env: &Env, issuer: Address, caller: Option<Address>) -> Bytes {
+    export_issuer_credentials_after(env, issuer, 0, caller)
+}
+
+pub fn export_issuer_credentials_after(env: &Env, issuer: Address, after_id: u64, caller: Option<Address>) -> Bytes {
+    require_issuer_listing_access(env, &issuer, caller);
+
+    let mut credentials = Vec::new(env);
+    let mut next_after = 0;
+
+    for credential_id in issuer_credential_ids(env, issuer.clone()).iter() {
+        if credential_id <= after_id {
+            continue;
+        }
+        if credentials.len() >= EXPORT_PAGE_SIZE {
+            next_after = credentials.last().map(|last: Credential| last.id).unwrap_or(0);
+            break;
+        }
+        if let Some(credential) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(credential_id)) {
+            credentials.push_back(credential);
+        }
+    }
+
+    ExportEnvelope { version: EXPORT_FORMAT_VERSION, issuer, credentials, next_after }.to_xdr(env)
+}
+
+/// Leave a note for future verifiers; each verifier gets one note per credential
+pub fn add_verification_note(env: &Env, credential_id: u64, verifier: Address, note: String) {
+    verifier.require_auth();
+    get_credential(env, credential_id);
+
+    let (_, max_description_len) = get_field_limits(env);
+    if note.len() > max_description_len {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+
+    let mut notes = get_verification_notes(env, credential_id);
+    if notes.iter().any(|existing| existing.verifier == verifier) {
+        panic!("Verifier already left a note");
+    }
+
+    notes.push_back(VerificationNote { verifier, note, created_at: env.ledger().timestamp() });
+    env.storage().persistent().set(&CredentialKey::VerificationNotes(credential_id), &notes);
+}
+
+/// Up to `limit` notes starting at index `start`, oldest first
+pub fn get_verification_notes_paged(env: &Env, credential_id: u64, start: u32, limit: u32) -> Vec<VerificationNote> {
+    let notes = get_verification_notes(env, credential_id);
+    let end = start.saturating_add(limit).min(notes.len());
+    if start >= end {
+        return Vec::new(env);
+    }
+    notes.slice(start..end)
+}
+
+/// Verify on the record: the verifier authenticates and the attempt is logged against the
+/// credential with its result. Plain `verify_credential_detailed` stays anonymous and unlogged.
+pub fn verify_credential_audited(env: &Env, credential_id: u64, verifier: Address) -> VerificationStatus {
+    verifier.require_auth();
+
+    let status = verify_credential_detailed(env, credential_id);
+    let mut log = get_verification_log(env, credential_id);
+    log.push_back(VerificationLogEntry { verifier, verified_at: env.ledger().timestamp(), status });
+
+    // Oldest entries make way once the admin-configured retention is reached
+    let limit = get_verification_log_limit(env);
+    while limit > 0 && log.len() > limit {
+        log.pop_front();
+    }
+    env.storage().persistent().set(&CredentialKeyExt::VerificationLog(credential_id), &log);
+
+    status
+}
+
+/// Up to `limit` audit entries starting at index `start`, oldest first
+pub fn get_verification_log_paged(env: &Env, credential_id: u64, start: u32, limit: u32) -> Vec<VerificationLogEntry> {
+    let log = get_verification_log(env, credential_id);
+    let end = start.saturating_add(limit).min(log.len());
+    if start >= end {
+        return Vec::new(env);
+    }
+    log.slice(start..end)
+}
+
+/// Entries retained per credential in the verification log; 0 keeps everything
+pub fn set_verification_log_limit(env: &Env, admin: Address, limit: u32) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::VerificationLogLimit, &limit);
+}
+
+pub fn get_verification_log_limit(env: &Env) -> u32 {
+    env.storage().instance()
+        .get(&CredentialKeyExt::VerificationLogLimit)
+        .unwrap_or(0)
+}
+
+/// Issuance time series as `(day, count)` for every day in `start_day..=end_day`, zeros
+/// included. Days are `ledger timestamp / 86400`; ranges are capped at 366 days.
+pub fn get_issuance_stats(env: &Env, start_day: u64, end_day: u64) -> Vec<(u64, u32)> {
+    if end_day < start_day || end_day - start_day >= MAX_STATS_DAYS {
+        panic!("Invalid day range");
+    }
+
+    let mut stats = Vec::new(env);
+    for day in start_day..=end_day {
+        let count = env.storage().persistent()
+            .get(&CredentialKey::IssuanceByDay(day))
+            .unwrap_or(0u32);
+        stats.push_back((day, count));
+    }
+
+    stats
+}
+
+/// Id the next issuance will receive, for optimistic UI and off-chain references
+pub fn peek_next_id(env: &Env) -> u64 {
+    next_id(env, get_credential_count(env))
+}
+
+pub fn get_credential_count(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKey::CredentialCount)
+        .unwrap_or(0)
+}
+
+/// Up to `limit` issued ids from `start` (inclusive) upward, skipping purged ones and ids an
+/// import skipped over. Continue from the last returned id + 1; an empty page means the walk is done.
+pub fn get_all_credential_ids(env: &Env, start: u64, limit: u32) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+
+    for credential_id in start.max(1)..=get_credential_count(env) {
+        if ids.len() >= limit {
+            break;
+        }
+        if env.storage().persistent().has(&CredentialKey::Credential(credential_id)) {
+            ids.push_back(credential_id);
+        }
+    }
+
+    ids
+}
+
+/// Derive the content fingerprint of a stored credential
+pub fn credential_fingerprint(env: &Env, credential_id: u64) -> BytesN<32> {
+    // Purged credentials keep their archived fingerprint so inclusion proofs still work
+    if let Some(fingerprint) = env.storage().persistent().get(&CredentialKey::PurgedIndex(credential_id)) {
+        return fingerprint;
+    }
+
+    let credential = get_credential(env, credential_id);
+    compute_fingerprint(env, &credential)
+}
+
+/// Issue a credential that expires at `expires_at` (ledger timestamp)
+pub fn issue_expiring_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    expires_at: u64,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.expires_at = expires_at;
+    record_issuance(env, credential).id
+}
+
+/// Timestamp a document's hash now; `promote_anchor_to_credential` can issue for it later
+pub fn anchor_document(env: &Env, issuer: Address, content_sha256: BytesN<32>) -> u64 {
+    issuer.require_auth();
+    require_not_paused(env);
+
+    if issuer != get_admin(env) && !is_issuer(env, issuer.clone()) {
+        panic!("Unauthorized issuer");
+    }
+
+    let id = next_id(env, env.storage().instance().get(&CredentialKey::AnchorCount).unwrap_or(0));
+    let anchor = DocumentAnchor {
+        id,
+        issuer,
+        content_sha256,
+        anchored_at: env.ledger().timestamp(),
+        credential_id: 0,
+    };
+    env.storage().persistent().set(&CredentialKey::Anchor(id), &anchor);
+    env.storage().instance().set(&CredentialKey::AnchorCount, &id);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("anchored")), (id, anchor.content_sha256));
+
+    id
+}
+
+/// A private issuer's address is masked, as for credentials
+pub fn get_anchor(env: &Env, anchor_id: u64) -> DocumentAnchor {
+    let mut anchor = load_anchor(env, anchor_id);
+    anchor.issuer = public_issuer(env, anchor.issuer);
+    anchor
+}
+
+fn load_anchor(env: &Env, anchor_id: u64) -> DocumentAnchor {
+    env.storage().persistent()
+        .get(&CredentialKey::Anchor(anchor_id))
+        .unwrap_or_else(|| panic!("Anchor not found"))
+}
+
+/// Issue the full credential for an anchored document, backdating `completion_date` to the anchor
+pub fn promote_anchor_to_credential(
+    env: &Env,
+    anchor_id: u64,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u64 {
+    let mut anchor = load_anchor(env, anchor_id);
+    if anchor.credential_id != 0 {
+        panic!("Anchor already promoted");
+    }
+
+    let mut credential = new_credential(env, anchor.issuer.clone(), recipient, title, description, course_id, ipfs_hash);
+    credential.completion_date = anchor.anchored_at;
+
+    anchor.credential_id = credential.id;
+    env.storage().persistent().set(&CredentialKey::Anchor(anchor_id), &anchor);
+
+    record_issuance(env, credential).id
+}
+
+/// Renew a credential: the copy points back at the original through `supersedes`, and the
+/// original then verifies as `Superseded`. Grade, credits, cohort, metadata and the privacy and
+/// encryption settings carry over and the XP moves across; the display id, template link,
+/// co-signatures and renewal fee start fresh.
+pub fn reissue_credential(
+    env: &Env,
+    issuer: Address,
+    original_id: u64,
+    new_expiry: u64,
+    new_ipfs_hash: String,
+) -> u64 {
+    let mut original = get_credential(env, original_id);
+    if original.issuer != issuer {
+        panic!("Only the issuer can reissue this credential");
+    }
+    if original.superseded_by != 0 {
+        panic!("Credential already superseded");
+    }
+
+    let mut credential = new_credential(
+        env,
+        issuer,
+        original.recipient.clone(),
+        original.title.clone(),
+        original.description.clone(),
+        original.course_id.clone(),
+        new_ipfs_hash,
+    );
+    credential.credential_type = original.credential_type;
+    credential.co_issuers = original.co_issuers.clone();
+    credential.translations = original.translations.clone();
+    credential.expires_at = new_expiry;
+    credential.supersedes = original_id;
+    credential.grade = original.grade;
+    credential.credits = original.credits;
+    credential.cohort_id = original.cohort_id.clone();
+    credential.metadata = original.metadata.clone();
+    credential.is_private = original.is_private;
+    credential.encrypted = original.encrypted;
+    credential.encryption_pubkey_hint = original.encryption_pubkey_hint.clone();
+
+    // Counted once: the copy's issuance grants what the original gives up
+    credential.xp = original.xp;
+    if !original.is_revoked {
+        adjust_user_xp(env, original.recipient.clone(), -(original.xp as i64));
+    }
+    original.xp = 0;
+    original.superseded_by = credential.id;
+    env.storage().persistent().set(&CredentialKey::Credential(original_id), &original);
+
+    let cohort_id = credential.cohort_id.clone();
+    let credential_id = record_issuance(env, credential).id;
+    if !cohort_id.is_empty() {
+        let mut members = get_cohort_credentials(env, cohort_id.clone());
+        members.push_back(credential_id);
+        env.storage().persistent().set(&CredentialKeyExt::CohortIndex(cohort_id), &members);
+    }
+    credential_id
+}
+
+/// Every credential in `credential_id`'s renewal chain, oldest first. Purged links end the walk.
+pub fn get_credential_lineage(env: &Env, credential_id: u64) -> Vec<u64> {
+    let mut lineage = Vec::new(env);
+
+    let mut current = get_credential(env, credential_id);
+    while let Some(previous) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(current.supersedes)) {
+        current = previous;
+    }
+
+    lineage.push_back(current.id);
+    while let Some(next) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(current.superseded_by)) {
+        lineage.push_back(next.id);
+        current = next;
+    }
+
+    lineage
+}
+
+/// Token, extension period and lapsed-credential rule shared by all paid renewals
+pub fn set_renewal_config(env: &Env, admin: Address, config: RenewalConfig) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::RenewalConfig, &config);
+}
+
+pub fn get_renewal_config(env: &Env) -> Option<RenewalConfig> {
+    env.storage().instance().get(&CredentialKeyExt::RenewalConfig)
+}
+
+/// Make an expiring credential self-service renewable for `fee`, paid to the issuer
+pub fn set_renewal_fee(env: &Env, issuer: Address, credential_id: u64, fee: i128) {
+    issuer.require_auth();
+
+    let mut credential = get_credential(env, credential_id);
+    if credential.issuer != issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    if credential.expires_at == 0 || fee < 0 {
+        panic!("Invalid renewal fee");
+    }
+
+    credential.renewal_fee = fee;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+}
+
+/// Collect the renewal fee from `payer` and extend the expiry by the configured period,
+/// counted from the later of the current expiry and now. Credentials already expired beyond
+/// grace only renew when the config allows it. Returns the new expiry.
+pub fn renew(env: &Env, credential_id: u64, payer: Address) -> u64 {
+    payer.require_auth();
+
+    let config = get_renewal_config(env)
+        .unwrap_or_else(|| panic!("Renewals not configured"));
+    let mut credential = get_credential(env, credential_id);
+    if credential.renewal_fee == 0 || is_revoked_now(env, &credential) {
+        panic!("Credential is not renewable");
+    }
+    if is_purgeable(env, &credential) && !config.allow_lapsed {
+        panic!("Credential lapsed beyond grace period");
+    }
+
+    token::Client::new(env, &config.token).transfer(&payer, &credential.issuer, &credential.renewal_fee);
+
+    let renewed_until = checked_sum(env, credential.expires_at.max(env.ledger().timestamp()), config.renewal_period);
+    reindex_expiry(env, credential_id, credential.expires_at, renewed_until);
+    credential.expires_at = renewed_until;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    // A renewed credential is live again, so a past sweep no longer applies
+    env.storage().persistent().remove(&CredentialKey::ExpirySwept(credential_id));
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("renewed")), (credential_id, payer, credential.expires_at));
+
+    credential.expires_at
+}
+
+/// Register the ed25519 key an issuer signs off-chain expiry extensions with
+pub fn set_issuer_signing_key(env: &Env, issuer: Address, pubkey: BytesN<32>) {
+    issuer.require_auth();
+    env.storage().persistent().set(&CredentialKeyExt::IssuerSigningKey(issuer), &pubkey);
+}
+
+pub fn get_issuer_signing_key(env: &Env, issuer: Address) -> Option<BytesN<32>> {
+    env.storage().persistent().get(&CredentialKeyExt::IssuerSigningKey(issuer))
+}
+
+/// Apply an expiry extension processed off-chain. `signature` must be the issuer's ed25519
+/// signature over the XDR of `(contract, credential_id, new_expiry, nonce)`, made with the key
+/// registered via `set_issuer_signing_key`; anyone may relay it. `nonce` is the credential's
+/// current `get_extension_nonce`, so each signature applies once, on this contract only.
+pub fn extend_expiry_signed(
+    env: &Env,
+    credential_id: u64,
+    new_expiry: u64,
+    nonce: u64,
+    signature: BytesN<64>,
+    issuer_pubkey: BytesN<32>,
+) {
+    require_not_paused(env);
+
+    let mut credential = get_credential(env, credential_id);
+    if get_issuer_signing_key(env, credential.issuer.clone()) != Some(issuer_pubkey.clone()) {
+        panic!("Key is not registered to the credential's issuer");
+    }
+    if is_revoked_now(env, &credential) || credential.expires_at == 0 || new_expiry <= credential.expires_at {
+        panic!("Invalid expiry extension");
+    }
+
+    if nonce != get_extension_nonce(env, credential_id) {
+        panic!("Stale extension nonce");
+    }
+
+    // Traps the invocation on a bad signature
+    let message = (env.current_contract_address(), credential_id, new_expiry, nonce).to_xdr(env);
+    env.crypto().ed25519_verify(&issuer_pubkey, &message, &signature);
+    env.storage().persistent().set(&CredentialKeyExt::ExtensionNonce(credential_id), &next_id(env, nonce));
+
+    reindex_expiry(env, credential_id, credential.expires_at, new_expiry);
+    credential.expires_at = new_expiry;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    env.storage().persistent().remove(&CredentialKey::ExpirySwept(credential_id));
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("extended")), (credential_id, new_expiry, signature));
+}
+
+/// Nonce the next `extend_expiry_signed` for a credential must be signed with
+pub fn get_extension_nonce(env: &Env, credential_id: u64) -> u64 {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::ExtensionNonce(credential_id))
+        .unwrap_or(0)
+}
+
+/// Set how long (in seconds) an expired credential keeps verifying before it lapses
+pub fn set_grace_period(env: &Env, admin: Address, grace_period: u64) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::GracePeriod, &grace_period);
+}
+
+/// Issue a credential of a specific type (plain issuance defaults to `Certificate`)
+pub fn issue_typed_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    credential_type: CredentialType,
+) -> u64 {
+    let mut credential = new_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    credential.credential_type = credential_type;
+    record_issuance(env, credential).id
+}
+
+/// Count a user's valid credentials by type, e.g. for "3 certificates, 1 degree" badges
+pub fn get_user_type_counts(env: &Env, user: Address) -> Map<CredentialType, u32> {
+    let mut counts = Map::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if !counts_as_valid(env, &credential) {
+            continue;
+        }
+        let count = counts.get(credential.credential_type).unwrap_or(0);
+        counts.set(credential.credential_type, count + 1);
+    }
+
+    counts
+}
+
+pub fn get_grace_period(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKey::GracePeriod)
+        .unwrap_or(0)
+}
+
+/// Remove a credential that has expired beyond its grace period to reclaim storage rent.
+/// Its fingerprint is archived in `PurgedIndex` and it then verifies as `Purged`; every other
+/// index drops it, and its display id is freed.
+pub fn purge_credential(env: &Env, credential_id: u64, admin: Address) {
+    require_admin(env, &admin);
+
+    let credential = get_credential(env, credential_id);
+    if !is_purgeable(env, &credential) {
+        panic_with_error!(env, CredentialError::NotPurgeable);
+    }
+
+    let fingerprint = compute_fingerprint(env, &credential);
+    env.storage().persistent().remove(&CredentialKey::FingerprintIndex(fingerprint.clone()));
+    env.storage().persistent().set(&CredentialKey::PurgedIndex(credential_id), &fingerprint);
+    env.storage().persistent().remove(&CredentialKey::Credential(credential_id));
+    reindex_expiry(env, credential_id, credential.expires_at, 0);
+
+    let mut user_creds = get_user_credentials(env, credential.recipient.clone());
+    if let Some(index) = user_creds.first_index_of(credential_id) {
+        user_creds.remove(index);
+        env.storage().persistent().set(&CredentialKey::UserCredentials(credential.recipient.clone()), &user_creds);
+    }
+    remove_from_id_list(env, &CredentialKey::PinnedCredentials(credential.recipient.clone()), credential_id);
+    remove_from_id_list(env, &CredentialKey::IssuerCredentials(credential.issuer.clone()), credential_id);
+    if !credential.cohort_id.is_empty() {
+        remove_from_id_list(env, &CredentialKeyExt::CohortIndex(credential.cohort_id.clone()), credential_id);
+    }
+    if !credential.display_id.is_empty() {
+        env.storage().persistent().remove(&CredentialKeyExt::DisplayIdIndex(credential.display_id.clone()));
+    }
+    env.storage().persistent().remove(&CredentialKey::PendingRevocation(credential_id));
+
+    // A revoked credential's XP and valid count were already taken back when it was revoked
+    if !credential.is_revoked {
+        let mut stats = get_issuer_stats(env, credential.issuer.clone());
+        stats.valid_count = stats.valid_count.saturating_sub(1);
+        env.storage().persistent().set(&CredentialKey::IssuerStats(credential.issuer.clone()), &stats);
+        adjust_user_xp(env, credential.recipient, -(credential.xp as i64));
+    }
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("purged")), credential_id);
+}
+
+// Drop `credential_id` from a stored id list, if it's there
+fn remove_from_id_list<K: IntoVal<Env, Val>>(env: &Env, key: &K, credential_id: u64) {
+    let Some(mut ids) = env.storage().persistent().get::<_, Vec<u64>>(key) else {
+        return;
+    };
+    if let Some(index) = ids.first_index_of(credential_id) {
+        ids.remove(index);
+        env.storage().persistent().set(key, &ids);
+    }
+}
+
+/// Register a contract consulted before credentials issued from `issuer`'s template
+/// `template_id` verify as `Valid`. It must expose
+/// `check_credential(credential_id: u64, recipient: Address) -> bool`; returning false
+/// downgrades the status to `Invalid`.
+pub fn set_verifier_hook(env: &Env, issuer: Address, template_id: u64, hook: Address) {
+    require_template_owner(env, &issuer, template_id);
+    env.storage().persistent().set(&CredentialKey::VerifierHook(template_id), &hook);
+}
+
+pub fn remove_verifier_hook(env: &Env, issuer: Address, template_id: u64) {
+    require_template_owner(env, &issuer, template_id);
+    env.storage().persistent().remove(&CredentialKey::VerifierHook(template_id));
+}
+
+pub fn get_verifier_hook(env: &Env, template_id: u64) -> Option<Address> {
+    env.storage().persistent().get(&CredentialKey::VerifierHook(template_id))
+}
+
+fn require_template_owner(env: &Env, issuer: &Address, template_id: u64) {
+    issuer.require_auth();
+    if load_template(env, template_id).issuer != *issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+}
+
+/// Register a contract told about every revocation when it is applied (e.g. an access-control
+/// contract gating a portal); for delayed and future-dated revocations that is when
+/// `execute_revocation` runs. It must expose `on_revoked(credential_id: u64)`. A listener that
+/// traps is reported with an event, but one that exhausts the resource budget aborts the
+/// revocation itself, so only register contracts you trust.
+pub fn set_revocation_listener(env: &Env, admin: Address, listener: Address) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::RevocationListener, &listener);
+}
+
+pub fn remove_revocation_listener(env: &Env, admin: Address) {
+    require_admin(env, &admin);
+    env.storage().instance().remove(&CredentialKeyExt::RevocationListener);
+}
+
+pub fn get_revocation_listener(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CredentialKeyExt::RevocationListener)
+}
+
+/// Whether a failing hook invalidates the credential (the default) or is ignored
+pub fn set_hook_failure_mode(env: &Env, admin: Address, mode: HookFailureMode) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::HookFailureMode, &mode);
+}
+
+pub fn get_hook_failure_mode(env: &Env) -> HookFailureMode {
+    env.storage().instance()
+        .get(&CredentialKey::HookFailureMode)
+        .unwrap_or(HookFailureMode::TreatInvalid)
+}
+
+/// Point verifiers of migrated credentials at the contract version that now holds them
+pub fn set_migration_target(env: &Env, admin: Address, target: Address) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::MigrationTarget, &target);
+}
+
+pub fn get_migration_target(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CredentialKey::MigrationTarget)
+}
+
+/// Hand a credential over to the contract set by `set_migration_target`. The record carries
+/// the credential verbatim (id, timestamps, co-signatures) plus its fingerprint; the new
+/// contract's import should check `source_contract`, store `credential` under the same id and
+/// confirm it re-derives `fingerprint`. From here on this contract reports it as `Migrated`.
+pub fn export_for_migration(env: &Env, credential_id: u64, admin: Address) -> MigrationRecord {
+    require_admin(env, &admin);
+
+    let target = get_migration_target(env)
+        .unwrap_or_else(|| panic!("Migration target not set"));
+    if env.storage().persistent().has(&CredentialKey::Migrated(credential_id)) {
+        panic!("Credential already migrated");
+    }
+
+    let credential = get_credential(env, credential_id);
+    env.storage().persistent().set(&CredentialKey::Migrated(credential_id), &target);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("migrated")), (credential_id, target.clone()));
+
+    MigrationRecord {
+        fingerprint: compute_fingerprint(env, &credential),
+        credential,
+        source_contract: env.current_contract_address(),
+        target_contract: target,
+        exported_at: env.ledger().timestamp(),
+    }
+}
+
+/// Target-side half of `export_for_migration`: store the record's credential verbatim under its
+/// source id, renewal links included. Ids must be imported in increasing order past any local
+/// issuance (`IdTaken`). Quotas and completion-date bounds don't apply, since the credential was
+/// issued long ago. Records addressed to another contract are refused, and so are records whose
+/// credential doesn't re-derive to the exported fingerprint (`TamperedImport`).
+pub fn import_migrated_credential(env: &Env, admin: Address, record: MigrationRecord) -> u64 {
+    require_admin(env, &admin);
+    if record.target_contract != env.current_contract_address() {
+        panic!("Record is addressed to another contract");
+    }
+    if compute_fingerprint(env, &record.credential) != record.fingerprint {
+        panic_with_error!(env, CredentialError::TamperedImport);
+    }
+
+    let credential = record.credential;
+    let credential_id = credential.id;
+    store_credential(env, &credential);
+    if !credential.display_id.is_empty() {
+        let index_key = CredentialKeyExt::DisplayIdIndex(credential.display_id.clone());
+        if env.storage().persistent().has(&index_key) {
+            panic_with_error!(env, CredentialError::DuplicateDisplayId);
+        }
+        env.storage().persistent().set(&index_key, &credential_id);
+    }
+
+    let provenance = ImportedCredential {
+        source_contract: record.source_contract,
+        source_id: credential_id,
+        fingerprint: record.fingerprint,
+    };
+    env.storage().persistent().set(&CredentialKeyExt::Imported(credential_id), &provenance);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("imported")), (credential_id, provenance.source_contract, provenance.source_id));
+    credential_id
+}
+
+/// Whether an imported credential still re-derives to the fingerprint its source exported.
+/// False for credentials that weren't imported, were purged, or were edited since.
+pub fn validate_imported(env: &Env, credential_id: u64) -> bool {
+    let Some(provenance) = get_import_provenance(env, credential_id) else {
+        return false;
+    };
+    let Some(credential) = try_get_credential(env, credential_id) else {
+        return false;
+    };
+
+    compute_fingerprint(env, &credential) == provenance.fingerprint
+}
+
+pub fn get_import_provenance(env: &Env, credential_id: u64) -> Option<ImportedCredential> {
+    env.storage().persistent().get(&CredentialKeyExt::Imported(credential_id))
+}
+
+/// Seconds until a credential expires by ledger time; negative once it has expired,
+/// `NEVER_EXPIRES` when it has no expiry
+pub fn time_to_expiry(env: &Env, credential_id: u64) -> i64 {
+    let credential = get_credential(env, credential_id);
+    if credential.expires_at == 0 {
+        return NEVER_EXPIRES;
+    }
+
+    let now = env.ledger().timestamp();
+    if credential.expires_at >= now {
+        i64::try_from(credential.expires_at - now).unwrap_or(NEVER_EXPIRES - 1)
+    } else {
+        i64::try_from(now - credential.expires_at).map_or(i64::MIN, |elapsed| -elapsed)
+    }
+}
+
+/// Ids of up to `limit` currently valid credentials, nearest upcoming expiry first.
+/// Credentials that never expire are not included.
+pub fn get_soonest_expiring(env: &Env, limit: u32) -> Vec<u64> {
+    let now = env.ledger().timestamp();
+
+    let mut soonest = Vec::new(env);
+    for week in get_expiry_weeks(env).iter() {
+        for (expires_at, credential_id) in get_expiry_bucket(env, week).iter() {
+            if soonest.len() >= limit {
+                return soonest;
+            }
+            if expires_at > now && resolve_status(env, credential_id) == VerificationStatus::Valid {
+                soonest.push_back(credential_id);
+            }
+        }
+    }
+    soonest
+}
+
+/// Permissionless expiry bookkeeping: emits one `expired` event per lapsed credential in
+/// `ids` and counts it. Ids that aren't expired, are missing, or were already swept are skipped.
+pub fn sweep_expired(env: &Env, ids: Vec<u64>) -> u32 {
+    let mut swept = 0;
+
+    for credential_id in ids.iter() {
+        if env.storage().persistent().has(&CredentialKey::ExpirySwept(credential_id)) {
+            continue;
+        }
+        let Some(credential) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(credential_id)) else {
+            continue;
+        };
+        if credential_status(env, &credential) != VerificationStatus::Expired {
+            continue;
+        }
+
+        env.storage().persistent().set(&CredentialKey::ExpirySwept(credential_id), &true);
+        publish_event(env, (symbol_short!("cred"), symbol_short!("expired")), credential_id);
+        swept += 1;
+    }
+
+    if swept > 0 {
+        let total = checked_sum(env, get_expired_count(env), swept as u64);
+        env.storage().instance().set(&CredentialKey::ExpiredCount, &total);
+    }
+
+    swept
+}
+
+/// Credentials recorded as expired by `sweep_expired` so far
+pub fn get_expired_count(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKey::ExpiredCount)
+        .unwrap_or(0)
+}
+
+/// Preview for a cleanup run: up to `limit` ids, lowest first, that `purge_credential` would accept
+pub fn get_purgeable_credentials(env: &Env, limit: u32) -> Vec<u64> {
+    let mut purgeable = Vec::new(env);
+
+    for credential_id in 1..=get_credential_count(env) {
+        if purgeable.len() >= limit {
+            break;
+        }
+        if let Some(credential) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(credential_id)) {
+            if is_purgeable(env, &credential) {
+                purgeable.push_back(credential_id);
+            }
+        }
+    }
+
+    purgeable
+}
+
+/// Set the delay (in seconds) between a revocation request and its execution; 0 revokes immediately
+pub fn set_revocation_delay(env: &Env, admin: Address, delay: u64) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::RevocationDelay, &delay);
+}
+
+pub fn get_revocation_delay(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKey::RevocationDelay)
+        .unwrap_or(0)
+}
+
+pub fn get_pending_revocation(env: &Env, credential_id: u64) -> Option<PendingRevocation> {
+    env.storage().persistent().get(&CredentialKey::PendingRevocation(credential_id))
+}
+
+/// Carry out a delayed revocation once its delay has elapsed; callable by anyone
+pub fn execute_revocation(env: &Env, credential_id: u64) {
+    let pending = get_pending_revocation(env, credential_id)
+        .unwrap_or_else(|| panic!("No pending revocation"));
+
+    if env.ledger().timestamp() < pending.executable_at {
+        panic!("Revocation delay not elapsed");
+    }
+
+    apply_revocation(env, credential_id, pending.revoker, pending.reason, pending.note);
+}
+
+/// Revoke immediately, bypassing the revocation delay. Every use is counted and
+/// emits a distinct `emergency_revoke` event so governance can monitor overrides.
+pub fn emergency_revoke(
+    env: &Env,
+    credential_id: u64,
+    admin: Address,
+    reason: RevocationReason,
+    note: Option<String>,
+) {
+    require_admin(env, &admin);
+
+    // A scheduled revocation may be overridden, but one in effect is final
+    if is_revoked_now(env, &get_credential(env, credential_id)) {
+        panic_with_error!(env, CredentialError::AlreadyRevoked);
+    }
+
+    let note = note.unwrap_or(String::from_str(env, ""));
+    apply_revocation(env, credential_id, admin.clone(), reason, note.clone());
+
+    let count = next_count(env, get_emergency_revoke_count(env));
+    env.storage().instance().set(&CredentialKey::EmergencyRevokeCount, &count);
+
+    publish_event(env, (Symbol::new(env, "emergency_revoke"), credential_id), (admin, reason, note));
+}
+
+pub fn get_emergency_revoke_count(env: &Env) -> u32 {
+    env.storage().instance()
+        .get(&CredentialKey::EmergencyRevokeCount)
+        .unwrap_or(0)
+}
+
+/// Serialized (XDR) byte length of a stored credential, for storage and TTL budgeting
+pub fn credential_storage_size(env: &Env, credential_id: u64) -> u32 {
+    get_credential(env, credential_id).to_xdr(env).len()
+}
+
+/// Contest the revocation of one's own credential
+pub fn file_appeal(env: &Env, credential_id: u64, recipient: Address, reason: String) {
+    recipient.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if credential.recipient != recipient {
+        panic!("Only the recipient can appeal");
+    }
+    if !is_revoked_now(env, &credential) {
+        panic!("Credential is not revoked");
+    }
+    if let Some(existing) = get_appeal(env, credential_id) {
+        if existing.status == AppealStatus::Pending {
+            panic!("Appeal already pending");
+        }
+    }
+
+    let appeal = Appeal {
+        credential_id,
+        recipient: recipient.clone(),
+        reason,
+        status: AppealStatus::Pending,
+        filed_at: env.ledger().timestamp(),
+        resolved_at: 0,
+        resolution_note: String::from_str(env, ""),
+    };
+    env.storage().persistent().set(&CredentialKey::Appeal(credential_id), &appeal);
+
+    let mut pending = get_pending_appeals(env);
+    pending.push_back(credential_id);
+    env.storage().persistent().set(&CredentialKeyExt::PendingAppeals, &pending);
+
+    publish_event(env, (symbol_short!("appeal"), symbol_short!("filed")), (credential_id, recipient));
+}
+
+/// Resolve a pending appeal: `uphold` keeps the revocation, otherwise the credential is reinstated
+pub fn resolve_appeal(env: &Env, credential_id: u64, admin: Address, uphold: bool, note: String) {
+    require_admin(env, &admin);
+
+    let appeal = get_appeal(env, credential_id)
+        .unwrap_or_else(|| panic!("No pending appeal"));
+    if appeal.status != AppealStatus::Pending {
+        panic!("No pending appeal");
+    }
+
+    if !uphold && try_get_credential(env, credential_id).is_some_and(|credential| credential.is_revoked) {
+        apply_reinstatement(env, credential_id);
+    }
+    settle_appeal(env, appeal, uphold, note);
+}
+
+// A credential reinstated outside the appeal process has nothing left to appeal
+fn close_open_appeal(env: &Env, credential_id: u64, note: &str) {
+    if let Some(appeal) = get_appeal(env, credential_id).filter(|appeal| appeal.status == AppealStatus::Pending) {
+        settle_appeal(env, appeal, false, String::from_str(env, note));
+    }
+}
+
+// `close_appeal`, then take the appeal off the pending queue
+fn settle_appeal(env: &Env, appeal: Appeal, uphold: bool, note: String) {
+    let credential_id = appeal.credential_id;
+    close_appeal(env, appeal, uphold, note);
+
+    let mut pending = get_pending_appeals(env);
+    if let Some(index) = pending.first_index_of(credential_id) {
+        pending.remove(index);
+        env.storage().persistent().set(&CredentialKeyExt::PendingAppeals, &pending);
+    }
+}
+
+/// How long an appeal may wait for the admin before `process_expired_appeals` settles it, and
+/// whether that default resolution reinstates (otherwise the revocation is upheld). A timeout
+/// of 0, the default, leaves appeals pending until resolved by hand.
+pub fn set_appeal_timeout(env: &Env, admin: Address, timeout: u64, auto_reinstate: bool) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKeyExt::AppealTimeout, &timeout);
+    env.storage().instance().set(&CredentialKeyExt::AppealAutoReinstate, &auto_reinstate);
+}
+
+pub fn get_appeal_timeout(env: &Env) -> u64 {
+    env.storage().instance()
+        .get(&CredentialKeyExt::AppealTimeout)
+        .unwrap_or(0)
+}
+
+pub fn get_appeal_auto_reinstate(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKeyExt::AppealAutoReinstate)
+        .unwrap_or(false)
+}
+
+/// Credential ids with an appeal awaiting resolution, oldest first
+pub fn get_pending_appeals(env: &Env) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::PendingAppeals)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Permissionless sweeper: apply the configured default resolution to appeals older than
+/// `get_appeal_timeout`, at most `get_max_batch_size` per call. Auto-reinstatement falls back
+/// to upholding where the issuer's policy forbids reinstating. Returns how many were settled.
+pub fn process_expired_appeals(env: &Env) -> u32 {
+    let timeout = get_appeal_timeout(env);
+    if timeout == 0 {
+        return 0;
+    }
+
+    let now = env.ledger().timestamp();
+    let auto_reinstate = get_appeal_auto_reinstate(env);
+    let note = String::from_str(env, "Auto-resolved after appeal timeout");
+    let mut pending = get_pending_appeals(env);
+    let mut settled = 0u32;
+
+    // Filed in order under one timeout, so the first appeal still in time ends the sweep
+    while settled < get_max_batch_size(env) {
+        let Some(credential_id) = pending.first() else {
+            break;
+        };
+        let Some(appeal) = get_appeal(env, credential_id).filter(|appeal| appeal.status == AppealStatus::Pending) else {
+            pending.pop_front();
+            continue;
+        };
+        if now < appeal.filed_at.saturating_add(timeout) {
+            break;
+        }
+
+        let reinstate = auto_reinstate
+            && try_get_credential(env, credential_id).is_some_and(|credential| credential.is_revoked)
+            && reinstatement_allowed(env, credential_id);
+        if reinstate {
+            apply_reinstatement(env, credential_id);
+        }
+        close_appeal(env, appeal, !reinstate, note.clone());
+        pending.pop_front();
+        settled += 1;
+    }
+
+    env.storage().persistent().set(&CredentialKeyExt::PendingAppeals, &pending);
+    settled
+}
+
+pub fn get_appeal(env: &Env, credential_id: u64) -> Option<Appeal> {
+    env.storage().persistent().get(&CredentialKey::Appeal(credential_id))
+}
+
+/// Undo a revocation, subject to the issuing institution's policy
+pub fn reinstate_credential(env: &Env, credential_id: u64, admin: Address) {
+    require_admin(env, &admin);
+
+    // Nothing to undo; rewriting would emit a misleading reinstate event
+    if !get_credential(env, credential_id).is_revoked {
+        panic_with_error!(env, CredentialError::AlreadyValid);
+    }
+
+    apply_reinstatement(env, credential_id);
+    close_open_appeal(env, credential_id, "Reinstated by the admin");
+}
+
+/// Temporarily stop a credential verifying (e.g. while it is investigated) without revoking
+/// it; the admin or the credential's issuer may suspend and unsuspend
+pub fn suspend_credential(env: &Env, credential_id: u64, actor: Address) {
+    let mut credential = require_issuer_or_admin(env, credential_id, &actor);
+    if is_revoked_now(env, &credential) {
+        panic_with_error!(env, CredentialError::AlreadyRevoked);
+    }
+    if credential.is_suspended {
+        panic_with_error!(env, CredentialError::AlreadySuspended);
+    }
+
+    credential.is_suspended = true;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    record_status_change(env, credential_id, VerificationStatus::Suspended);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("suspended")), (credential_id, actor));
+}
+
+/// Lift a suspension; the credential goes back to whatever status it would otherwise have
+pub fn unsuspend_credential(env: &Env, credential_id: u64, actor: Address) {
+    let mut credential = require_issuer_or_admin(env, credential_id, &actor);
+    if !credential.is_suspended {
+        panic_with_error!(env, CredentialError::NotSuspended);
+    }
+
+    credential.is_suspended = false;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    record_status_change(env, credential_id, credential_status(env, &credential));
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("unsuspend")), (credential_id, actor));
+}
+
+fn require_issuer_or_admin(env: &Env, credential_id: u64, actor: &Address) -> Credential {
+    actor.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if *actor != get_admin(env) && *actor != credential.issuer {
+        panic_with_error!(env, CredentialError::NotYourCredential);
+    }
+    credential
+}
+
+/// Authorize an institution to issue credentials alongside the admin
+pub fn add_issuer(env: &Env, admin: Address, issuer: Address) {
+    require_admin(env, &admin);
+
+    let mut issuers = get_issuers(env);
+    if !issuers.contains(&issuer) {
+        issuers.push_back(issuer.clone());
+        env.storage().instance().set(&CredentialKey::Issuers, &issuers);
+    }
+
+    publish_event(env, (symbol_short!("issuer"), symbol_short!("added")), issuer);
+}
+
+pub fn remove_issuer(env: &Env, admin: Address, issuer: Address) {
+    require_admin(env, &admin);
+
+    let mut issuers = get_issuers(env);
+    if let Some(index) = issuers.first_index_of(&issuer) {
+        issuers.remove(index);
+        env.storage().instance().set(&CredentialKey::Issuers, &issuers);
+    }
+
+    publish_event(env, (symbol_short!("issuer"), symbol_short!("removed")), issuer);
+}
+
+/// Display name an issuer goes by in the directory, e.g. "Lagos School of Engineering"
+pub fn set_issuer_name(env: &Env, issuer: Address, name: String) {
+    issuer.require_auth();
+    let (max_title_len, _) = get_field_limits(env);
+    if name.len() > max_title_len {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    env.storage().persistent().set(&CredentialKeyExt::IssuerName(issuer), &name);
+}
+
+pub fn get_issuer_name(env: &Env, issuer: Address) -> String {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::IssuerName(issuer))
+        .unwrap_or(String::from_str(env, ""))
+}
+
+/// Up to `limit` listed issuers starting at index `start`, in the order they were added, each
+/// with name, issuance counts and whether they may currently issue. Private issuers are left
+/// out but still occupy their index, so a page can come back short.
+pub fn get_issuer_directory(env: &Env, start: u32, limit: u32) -> Vec<IssuerInfo> {
+    let issuers = get_issuers(env);
+    let end = start.saturating_add(limit).min(issuers.len());
+
+    let mut directory = Vec::new(env);
+    for index in start..end {
+        let issuer = issuers.get(index).unwrap();
+        if is_issuer_private(env, issuer.clone()) {
+            continue;
+        }
+        let stats = get_issuer_stats(env, issuer.clone());
+        directory.push_back(IssuerInfo {
+            name: get_issuer_name(env, issuer.clone()),
+            issued_count: stats.valid_count.saturating_add(stats.revoked_count),
+            revoked_count: stats.revoked_count,
+            authorized: !is_issuer_expired(env, &issuer),
+            issuer,
+        });
+    }
+    directory
+}
+
+/// Listed and not past any authorization expiry
+pub fn is_issuer(env: &Env, issuer: Address) -> bool {
+    !is_issuer_expired(env, &issuer) && get_issuers(env).contains(&issuer)
+}
+
+/// End an issuer's authorization at `expiry` (ledger timestamp), e.g. when accreditation
+/// lapses; 0 clears it. Covers course allowlists too.
+pub fn set_issuer_expiry(env: &Env, admin: Address, issuer: Address, expiry: u64) {
+    require_admin(env, &admin);
+    if expiry == 0 {
+        env.storage().persistent().remove(&CredentialKeyExt::IssuerExpiry(issuer));
+    } else {
+        env.storage().persistent().set(&CredentialKeyExt::IssuerExpiry(issuer), &expiry);
+    }
+}
+
+pub fn get_issuer_expiry(env: &Env, issuer: Address) -> Option<u64> {
+    env.storage().persistent().get(&CredentialKeyExt::IssuerExpiry(issuer))
+}
+
+/// Keep an issuer's address off the public credential getters (e.g. medical boards)
+pub fn set_issuer_private(env: &Env, issuer: Address, private: bool) {
+    issuer.require_auth();
+    if private {
+        env.storage().persistent().set(&CredentialKeyExt::IssuerPrivate(issuer), &true);
+    } else {
+        env.storage().persistent().remove(&CredentialKeyExt::IssuerPrivate(issuer));
+    }
+}
+
+pub fn is_issuer_private(env: &Env, issuer: Address) -> bool {
+    env.storage().persistent().has(&CredentialKeyExt::IssuerPrivate(issuer))
+}
+
+pub fn get_issuers(env: &Env) -> Vec<Address> {
+    env.storage().instance()
+        .get(&CredentialKey::Issuers)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Let a staff member issue on the institution's behalf without holding its key
+pub fn add_operator(env: &Env, issuer: Address, operator: Address) {
+    issuer.require_auth();
+
+    let mut operators = get_operators(env, issuer.clone());
+    if !operators.contains(&operator) {
+        operators.push_back(operator);
+        env.storage().persistent().set(&CredentialKey::Operators(issuer), &operators);
+    }
+}
+
+pub fn remove_operator(env: &Env, issuer: Address, operator: Address) {
+    issuer.require_auth();
+
+    let mut operators = get_operators(env, issuer.clone());
+    if let Some(index) = operators.first_index_of(&operator) {
+        operators.remove(index);
+        env.storage().persistent().set(&CredentialKey::Operators(issuer), &operators);
+    }
+}
+
+pub fn get_operators(env: &Env, issuer: Address) -> Vec<Address> {
+    env.storage().persistent()
+        .get(&CredentialKey::Operators(issuer))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Issue with an operator's auth; the credential still records the institution as `issuer`
+pub fn issue_as(
+    env: &Env,
+    operator: Address,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u64 {
+    operator.require_auth();
+
+    if !get_operators(env, issuer.clone()).contains(&operator) {
+        panic!("Not an operator for this issuer");
+    }
+
+    let credential = build_credential(env, issuer, recipient, title, description, course_id, ipfs_hash);
+    record_issuance(env, credential).id
+}
+
+/// Restrict a course to specific institutions; an empty list restores the global issuer check
+pub fn set_course_issuers(env: &Env, admin: Address, course_id: String, issuers: Vec<Address>) {
+    require_admin(env, &admin);
+
+    if issuers.is_empty() {
+        env.storage().persistent().remove(&CredentialKey::CourseIssuers(course_id));
+    } else {
+        env.storage().persistent().set(&CredentialKey::CourseIssuers(course_id), &issuers);
+    }
+}
+
+pub fn get_course_issuers(env: &Env, course_id: String) -> Vec<Address> {
+    env.storage().persistent()
+        .get(&CredentialKey::CourseIssuers(course_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Set the revocation rules applied to credentials from `issuer`
+pub fn set_issuer_policy(env: &Env, admin: Address, issuer: Address, policy: IssuerPolicy) {
+    require_admin(env, &admin);
+    env.storage().persistent().set(&CredentialKey::IssuerPolicy(issuer), &policy);
+}
+
+pub fn get_issuer_policy(env: &Env, issuer: Address) -> Option<IssuerPolicy> {
+    env.storage().persistent().get(&CredentialKey::IssuerPolicy(issuer))
+}
+
+/// Let a recipient share a credential for a limited time: the returned token
+/// verifies through `verify_by_token` only until `expires_at`.
+pub fn create_share_token(env: &Env, credential_id: u64, recipient: Address, expires_at: u64) -> BytesN<32> {
+    recipient.require_auth();
+
+    let credential = get_credential(env, credential_id);
+    if credential.recipient != recipient {
+        panic!("Only the recipient can share a credential");
+    }
+    if expires_at <= env.ledger().timestamp() {
+        panic!("Token expiry must be in the future");
+    }
+
+    let nonce = next_id(env, env.storage().instance().get(&CredentialKey::ShareTokenNonce).unwrap_or(0));
+    env.storage().instance().set(&CredentialKey::ShareTokenNonce, &nonce);
+
+    let seed = (credential_id, recipient, expires_at, nonce, env.ledger().sequence());
+    let token: BytesN<32> = env.crypto().sha256(&seed.to_xdr(env)).into();
+
+    let share = ShareToken { credential_id, expires_at };
+    env.storage().persistent().set(&CredentialKey::ShareToken(token.clone()), &share);
+
+    token
+}
+
+/// Raw inputs to `get_issuer_reputation`, so clients can recompute or weigh them differently
+pub fn get_issuer_stats(env: &Env, issuer: Address) -> IssuerStats {
+    env.storage().persistent()
+        .get(&CredentialKey::IssuerStats(issuer))
+        .unwrap_or(IssuerStats { valid_count: 0, revoked_count: 0 })
+}
+
+/// Issuer trust score from 0 to 100: `100 * valid / (valid + REVOCATION_WEIGHT * revoked)`,
+/// rounded down. Each revocation weighs double, so heavy revokers score lower even while most
+/// of their credentials remain valid. An issuer with no credentials yet scores 100.
+pub fn get_issuer_reputation(env: &Env, issuer: Address) -> u32 {
+    let stats = get_issuer_stats(env, issuer);
+
+    let valid = stats.valid_count as u64;
+    let weighted_total = valid + REVOCATION_WEIGHT * stats.revoked_count as u64;
+    if weighted_total == 0 {
+        return 100;
+    }
+
+    (valid * 100 / weighted_total).min(100) as u32
+}
+
+pub fn verify_by_token(env: &Env, token: BytesN<32>) -> VerificationStatus {
+    let share: ShareToken = match env.storage().persistent().get(&CredentialKey::ShareToken(token)) {
+        Some(share) => share,
+        None => return VerificationStatus::NotFound,
+    };
+
+    if env.ledger().timestamp() >= share.expires_at {
+        return VerificationStatus::TokenExpired;
+    }
+
+    verify_credential_detailed(env, share.credential_id)
+}
+
+/// Configure the M-of-N signer set that must approve high-risk actions such as `transfer_admin`.
+/// Once a threshold is set, replacing the set also needs that many approvals on
+/// `set_admin_signers_action_hash`, so the admin alone can't swap signers out.
+pub fn set_admin_signers(env: &Env, admin: Address, signers: Vec<Address>, threshold: u32) {
+    require_admin(env, &admin);
+
+    if threshold == 0 || threshold > signers.len() {
+        panic!("Invalid signer threshold");
+    }
+
+    let (_, current_threshold) = get_admin_signers(env);
+    if current_threshold > 0 {
+        consume_approvals(env, set_admin_signers_action_hash(env, signers.clone(), threshold), current_threshold);
+    }
+
+    env.storage().instance().set(&CredentialKey::AdminSigners, &signers);
+    env.storage().instance().set(&CredentialKey::AdminThreshold, &threshold);
+}
+
+/// Hash signers approve to authorize `set_admin_signers(signers, threshold)`
+pub fn set_admin_signers_action_hash(env: &Env, signers: Vec<Address>, threshold: u32) -> BytesN<32> {
+    let action = (Symbol::new(env, "set_admin_signers"), signers, threshold, get_admin(env));
+    env.crypto().sha256(&action.to_xdr(env)).into()
+}
+
+/// (signers, threshold); a threshold of 0 means high-risk actions need only the admin
+pub fn get_admin_signers(env: &Env) -> (Vec<Address>, u32) {
+    let signers = env.storage().instance()
+        .get(&CredentialKey::AdminSigners)
+        .unwrap_or(Vec::new(env));
+    let threshold = env.storage().instance()
+        .get(&CredentialKey::AdminThreshold)
+        .unwrap_or(0);
+    (signers, threshold)
+}
+
+/// Record a signer's approval of a high-risk action, returning the approvals collected so far
+pub fn approve_action(env: &Env, signer: Address, action_hash: BytesN<32>) -> u32 {
+    signer.require_auth();
+
+    let (signers, _) = get_admin_signers(env);
+    if !signers.contains(&signer) {
+        panic!("Not an admin signer");
+    }
+
+    let mut approvals = get_action_approvals(env, action_hash.clone());
+    if approvals.contains(&signer) {
+        panic!("Already approved");
+    }
+    approvals.push_back(signer.clone());
+    env.storage().persistent().set(&CredentialKey::ActionApprovals(action_hash.clone()), &approvals);
+
+    publish_event(env, (symbol_short!("admin"), symbol_short!("approved")), (action_hash, signer));
+
+    approvals.len()
+}
+
+pub fn get_action_approvals(env: &Env, action_hash: BytesN<32>) -> Vec<Address> {
+    env.storage().persistent()
+        .get(&CredentialKey::ActionApprovals(action_hash))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Hash signers approve to authorize `transfer_admin(new_admin)`
+pub fn transfer_admin_action_hash(env: &Env, new_admin: Address) -> BytesN<32> {
+    let action = (Symbol::new(env, "transfer_admin"), new_admin, get_admin(env));
+    env.crypto().sha256(&action.to_xdr(env)).into()
+}
+
+/// Hand the admin role to `new_admin`. Once a signer set is configured this needs its
+/// threshold of approvals on `transfer_admin_action_hash`; otherwise the current admin signs.
+pub fn transfer_admin(env: &Env, new_admin: Address) {
+    let old_admin = get_admin(env);
+
+    let (_, threshold) = get_admin_signers(env);
+    if threshold == 0 {
+        old_admin.require_auth();
+    } else {
+        consume_approvals(env, transfer_admin_action_hash(env, new_admin.clone()), threshold);
+    }
+
+    env.storage().instance().set(&Symbol::new(env, "admin"), &new_admin);
+
+    publish_event(env, (symbol_short!("admin"), symbol_short!("transfer")), (old_admin, new_admin));
+}
+
+/// Pause or resume issuance and regular revocation; emergency revocation stays available
+pub fn set_paused(env: &Env, admin: Address, paused: bool) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::Paused, &paused);
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKey::Paused)
+        .unwrap_or(false)
+}
+
+/// Single endpoint for health dashboards
+pub fn get_contract_status(env: &Env) -> ContractStatus {
+    let admin = try_get_admin(env);
+    ContractStatus {
+        initialized: admin.is_some(),
+        paused: is_paused(env),
+        version: CONTRACT_VERSION,
+        admin,
+        credential_count: get_credential_count(env),
+    }
+}
+
+pub fn get_feature_flags(env: &Env) -> FeatureFlags {
+    FeatureFlags {
+        paused: is_paused(env),
+        require_co_signatures: get_require_co_signatures(env),
+        revocation_delay_enabled: get_revocation_delay(env) > 0,
+        allow_issuer_self_revoke: get_allow_issuer_self_revoke(env),
+        dedupe_recipients: get_dedupe_recipients(env),
+        strict_issuer_invalidates: get_strict_issuer_invalidates(env),
+        sanitize_text: get_sanitize_text(env),
+        require_recipient_auth: get_require_recipient_auth(env),
+    }
+}
+
+/// Replace every switch in `FeatureFlags` at once, typically a modified `get_feature_flags`.
+/// `revocation_delay_enabled` can't be flipped here; a value that disagrees with the configured
+/// delay is refused with `InvalidConfig`.
+pub fn set_feature_flags(env: &Env, admin: Address, flags: FeatureFlags) {
+    require_admin(env, &admin);
+    if flags.revocation_delay_enabled != (get_revocation_delay(env) > 0) {
+        panic_with_error!(env, CredentialError::InvalidConfig);
+    }
+
+    let instance = env.storage().instance();
+    instance.set(&CredentialKey::Paused, &flags.paused);
+    instance.set(&CredentialKey::RequireCoSignatures, &flags.require_co_signatures);
+    instance.set(&CredentialKey::AllowIssuerSelfRevoke, &flags.allow_issuer_self_revoke);
+    instance.set(&CredentialKey::DedupeRecipients, &flags.dedupe_recipients);
+    instance.set(&CredentialKeyExt::StrictIssuerInvalidates, &flags.strict_issuer_invalidates);
+    instance.set(&CredentialKeyExt::SanitizeText, &flags.sanitize_text);
+    instance.set(&CredentialKeyExt::RequireRecipientAuth, &flags.require_recipient_auth);
+}
+
+pub fn get_config(env: &Env) -> Config {
+    let (max_title_len, max_description_len) = get_field_limits(env);
+    let (min_completion_date, max_future_skew) = get_completion_date_bounds(env);
+    Config {
+        max_title_len,
+        max_description_len,
+        max_batch_size: get_max_batch_size(env),
+        max_credentials_per_user: get_max_credentials_per_user(env),
+        min_completion_date,
+        max_future_skew,
+        revocation_delay: get_revocation_delay(env),
+        grace_period: get_grace_period(env),
+        cascade_mode: get_cascade_mode(env),
+        hook_failure_mode: get_hook_failure_mode(env),
+        verification_log_limit: get_verification_log_limit(env),
+        event_namespace: get_event_namespace(env),
+        ipfs_gateway: get_ipfs_gateway(env),
+        bulk_undo_window: get_bulk_undo_window(env),
+        appeal_timeout: get_appeal_timeout(env),
+        appeal_auto_reinstate: get_appeal_auto_reinstate(env),
+    }
+}
+
+/// Replace every setting in `Config` at once. Nothing is written unless the whole bundle is
+/// sane: non-zero field and batch limits, a completion-date window that admits today, and an
+/// IPFS gateway no longer than `MAX_GATEWAY_LEN`.
+pub fn set_config(env: &Env, admin: Address, config: Config) {
+    require_admin(env, &admin);
+
+    let issuance_possible = config.min_completion_date
+        <= env.ledger().timestamp().saturating_add(config.max_future_skew);
+    if config.max_title_len == 0
+        || config.max_description_len == 0
+        || config.max_batch_size == 0
+        || !issuance_possible
+        || config.ipfs_gateway.len() > MAX_GATEWAY_LEN
+    {
+        panic_with_error!(env, CredentialError::InvalidConfig);
+    }
+
+    let instance = env.storage().instance();
+    instance.set(&CredentialKey::MaxTitleLength, &config.max_title_len);
+    instance.set(&CredentialKey::MaxDescriptionLength, &config.max_description_len);
+    instance.set(&CredentialKey::MaxBatchSize, &config.max_batch_size);
+    instance.set(&CredentialKey::MaxCredentialsPerUser, &config.max_credentials_per_user);
+    instance.set(&CredentialKey::MinCompletionDate, &config.min_completion_date);
+    instance.set(&CredentialKey::MaxFutureSkew, &config.max_future_skew);
+    instance.set(&CredentialKey::RevocationDelay, &config.revocation_delay);
+    instance.set(&CredentialKey::GracePeriod, &config.grace_period);
+    instance.set(&CredentialKey::CascadeMode, &config.cascade_mode);
+    instance.set(&CredentialKey::HookFailureMode, &config.hook_failure_mode);
+    instance.set(&CredentialKeyExt::VerificationLogLimit, &config.verification_log_limit);
+    instance.set(&CredentialKeyExt::EventNamespace, &config.event_namespace);
+    instance.set(&CredentialKeyExt::IpfsGateway, &config.ipfs_gateway);
+    instance.set(&CredentialKeyExt::BulkUndoWindow, &config.bulk_undo_window);
+    instance.set(&CredentialKeyExt::AppealTimeout, &config.appeal_timeout);
+    instance.set(&CredentialKeyExt::AppealAutoReinstate, &config.appeal_auto_reinstate);
+}
+
+fn get_require_recipient_auth(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKeyExt::RequireRecipientAuth)
+        .unwrap_or(false)
+}
+
+/// Set the HTTP gateway document URLs are built on, e.g. `https://ipfs.io/ipfs/`; an empty
+/// gateway turns composition off
+pub fn set_ipfs_gateway(env: &Env, admin: Address, gateway: String) {
+    require_admin(env, &admin);
+    if gateway.len() > MAX_GATEWAY_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    env.storage().instance().set(&CredentialKeyExt::IpfsGateway, &gateway);
+}
+
+pub fn get_ipfs_gateway(env: &Env) -> String {
+    env.storage().instance()
+        .get(&CredentialKeyExt::IpfsGateway)
+        .unwrap_or(String::from_str(env, ""))
+}
+
+/// Clickable link to a credential's document: the gateway followed by the CID, with any
+/// `ipfs://` scheme dropped. Without a gateway the stored hash comes back unchanged; a
+/// credential with no document yields an empty string.
+pub fn get_credential_document_url(env: &Env, credential_id: u64) -> String {
+    let ipfs_hash = get_credential(env, credential_id).ipfs_hash;
+    let gateway = get_ipfs_gateway(env);
+    if gateway.is_empty() || ipfs_hash.is_empty() {
+        return ipfs_hash;
+    }
+
+    let scheme = Bytes::from_slice(env, b"ipfs://");
+    let mut cid = string_bytes(env, &ipfs_hash);
+    if cid.len() >= scheme.len() && cid.slice(..scheme.len()) == scheme {
+        cid = cid.slice(scheme.len()..);
+    }
+
+    let mut url = string_bytes(env, &gateway);
+    url.append(&cid);
+    let len = url.len() as usize;
+    if len > MAX_DOCUMENT_URL_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    let mut buf = [0u8; MAX_DOCUMENT_URL_LEN];
+    url.copy_into_slice(&mut buf[..len]);
+    String::from_bytes(env, &buf[..len])
+}
+
+fn get_sanitize_text(env: &Env) -> bool {
+    env.storage().instance()
+        .get(&CredentialKeyExt::SanitizeText)
+        .unwrap_or(true)
+}
+
+/// Set the maximum byte lengths accepted for credential titles and descriptions; neither may be 0
+pub fn set_field_limits(env: &Env, admin: Address, max_title_len: u32, max_description_len: u32) {
+    require_admin(env, &admin);
+    if max_title_len == 0 || max_description_len == 0 {
+        panic_with_error!(env, CredentialError::InvalidConfig);
+    }
+    env.storage().instance().set(&CredentialKey::MaxTitleLength, &max_title_len);
+    env.storage().instance().set(&CredentialKey::MaxDescriptionLength, &max_description_len);
+}
+
+/// Returns `(max_title_len, max_description_len)`
+pub fn get_field_limits(env: &Env) -> (u32, u32) {
+    let max_title_len = env.storage().instance()
+        .get(&CredentialKey::MaxTitleLength)
+        .unwrap_or(DEFAULT_MAX_TITLE_LEN);
+    let max_description_len = env.storage().instance()
+        .get(&CredentialKey::MaxDescriptionLength)
+        .unwrap_or(DEFAULT_MAX_DESCRIPTION_LEN);
+    (max_title_len, max_description_len)
+}
+
+/// Top up an issuer's prepaid issuance allowance. Issuers that have never been given a quota
+/// are unmetered; once one exists, each issuance consumes a unit and zero blocks issuance.
+pub fn add_quota(env: &Env, admin: Address, issuer: Address, amount: u32) -> u32 {
+    require_admin(env, &admin);
+
+    let balance = get_quota(env, issuer.clone()).unwrap_or(0).saturating_add(amount);
+    env.storage().persistent().set(&CredentialKeyExt::Quota(issuer.clone()), &balance);
+
+    publish_event(env, (symbol_short!("quota"), symbol_short!("added")), (issuer, amount, balance));
+    balance
+}
+
+/// Remaining issuance allowance; `None` if the issuer is unmetered
+pub fn get_quota(env: &Env, issuer: Address) -> Option<u32> {
+    env.storage().persistent().get(&CredentialKeyExt::Quota(issuer))
+}
+
+/// Refuse issuance to an address known to be dead weight (burn addresses, retired contracts)
+pub fn block_recipient(env: &Env, admin: Address, recipient: Address) {
+    require_admin(env, &admin);
+    env.storage().persistent().set(&CredentialKey::BlockedRecipient(recipient), &true);
+}
+
+pub fn unblock_recipient(env: &Env, admin: Address, recipient: Address) {
+    require_admin(env, &admin);
+    env.storage().persistent().remove(&CredentialKey::BlockedRecipient(recipient));
+}
+
+pub fn is_recipient_blocked(env: &Env, recipient: Address) -> bool {
+    env.storage().persistent().has(&CredentialKey::BlockedRecipient(recipient))
+}
+
+/// Cap how many credentials one recipient can hold, against storage griefing; 0 = unlimited
+pub fn set_max_credentials_per_user(env: &Env, admin: Address, max_credentials: u32) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::MaxCredentialsPerUser, &max_credentials);
+}
+
+pub fn get_max_credentials_per_user(env: &Env) -> u32 {
+    env.storage().instance()
+        .get(&CredentialKey::MaxCredentialsPerUser)
+        .unwrap_or(0)
+}
+
+/// Reject completion dates before `min_completion_date` or more than `max_future_skew`
+/// seconds ahead of the ledger
+pub fn set_completion_date_bounds(env: &Env, admin: Address, min_completion_date: u64, max_future_skew: u64) {
+    require_admin(env, &admin);
+    env.storage().instance().set(&CredentialKey::MinCompletionDate, &min_completion_date);
+    env.storage().instance().set(&CredentialKey::MaxFutureSkew, &max_future_skew);
+}
+
+/// Returns `(min_completion_date, max_future_skew)`
+pub fn get_completion_date_bounds(env: &Env) -> (u64, u64) {
+    let min_completion_date = env.storage().instance()
+        .get(&CredentialKey::MinCompletionDate)
+        .unwrap_or(0);
+    let max_future_skew = env.storage().instance()
+        .get(&CredentialKey::MaxFutureSkew)
+        .unwrap_or(DEFAULT_MAX_FUTURE_SKEW);
+    (min_completion_date, max_future_skew)
+}
+
+/// Root of the merkle tree over all credential fingerprints, in issuance order
+pub fn get_credential_root(env: &Env) -> BytesN<32> {
+    env.storage().instance()
+        .get(&CredentialKey::MerkleRoot)
+        .unwrap_or_else(|| compute_merkle_root(env, &Vec::new(env), 0))
+}
+
+/// Check a light-client inclusion proof: `proof` holds the sibling hashes from the
+/// leaf (the credential's fingerprint at index `credential_id - 1`) up to the root.
+pub fn verify_inclusion(env: &Env, credential_id: u64, proof: Vec<BytesN<32>>) -> bool {
+    if proof.len() != MERKLE_TREE_DEPTH || credential_id == 0 || credential_id > get_credential_count(env) {
+        return false;
+    }
+    // Ids an import skipped over hold a zero leaf, not a credential
+    let stored = env.storage().persistent().has(&CredentialKey::Credential(credential_id))
+        || env.storage().persistent().has(&CredentialKey::PurgedIndex(credential_id));
+    if !stored {
+        return false;
+    }
+
+    // The tree commits to the fingerprint at issuance, before any deferred document was attached
+    let mut node = env.storage().persistent()
+        .get(&CredentialKey::IssuedLeaf(credential_id))
+        .unwrap_or_else(|| credential_fingerprint(env, credential_id));
+    let index = credential_id - 1;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if (index >> level) & 1 == 1 {
+            hash_pair(env, &sibling, &node)
+        } else {
+            hash_pair(env, &node, &sibling)
+        };
+    }
+
+    node == get_credential_root(env)
+}
+
+// Authorization and duplicate checks shared by every revocation entry point
+fn check_revocable(env: &Env, credential_id: u64, revoker: &Address) -> Credential {
+    revoker.require_auth();
+    require_not_paused(env);
+
+    let credential = get_credential(env, credential_id);
+    if *revoker != get_admin(env) {
+        if !get_allow_issuer_self_revoke(env) {
+            panic!("Only admin can revoke");
+        }
+        if credential.issuer != *revoker {
+            panic_with_error!(env, CredentialError::NotYourCredential);
+        }
+    }
+
+    // Only one revocation (and one event) per cycle, even if moderators race or a delayed
+    // revocation is already scheduled
+    if credential.is_revoked
+        || env.storage().persistent().has(&CredentialKey::PendingRevocation(credential_id))
+    {
+        panic_with_error!(env, CredentialError::AlreadyRevoked);
+    }
+
+    credential
+}
+
+fn revoke(env: &Env, credential_id: u64, revoker: Address, reason: RevocationReason, note: String) {
+    let credential = check_revocable(env, credential_id, &revoker);
+
+    let executable_at = revocation_executable_at(env, &credential.issuer);
+    if executable_at == env.ledger().timestamp() {
+        apply_revocation(env, credential_id, revoker, reason, note);
+        return;
+    }
+
+    schedule_revocation(env, credential_id, PendingRevocation { revoker, reason, note, executable_at });
+}
+
+// Earliest time a revocation requested now may be applied; an issuer-specific policy takes
+// precedence over the global delay
+fn revocation_executable_at(env: &Env, issuer: &Address) -> u64 {
+    let delay = match get_issuer_policy(env, issuer.clone()) {
+        Some(policy) => policy.revocation_delay,
+        None => get_revocation_delay(env),
+    };
+    checked_sum(env, env.ledger().timestamp(), delay)
+}
+
+fn schedule_revocation(env: &Env, credential_id: u64, pending: PendingRevocation) {
+    let executable_at = pending.executable_at;
+    env.storage().persistent().set(&CredentialKey::PendingRevocation(credential_id), &pending);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("rev_req")), (credential_id, executable_at));
+}
+
+// Returns `credential_id` followed by any dependents the cascade revoked
+fn apply_revocation(env: &Env, credential_id: u64, revoker: Address, reason: RevocationReason, note: String) -> Vec<u64> {
+    let mut credential = get_credential(env, credential_id);
+
+    if !credential.is_revoked {
+        let mut stats = get_issuer_stats(env, credential.issuer.clone());
+        stats.valid_count = stats.valid_count.saturating_sub(1);
+        stats.revoked_count = next_count(env, stats.revoked_count);
+        env.storage().persistent().set(&CredentialKey::IssuerStats(credential.issuer.clone()), &stats);
+        adjust_user_xp(env, credential.recipient.clone(), -(credential.xp as i64));
+    }
+
+    // Applied ahead of a scheduled effective date (e.g. an emergency override), it takes
+    // effect now
+    credential.is_revoked = true;
+    credential.revoked_from = credential.revoked_from.min(env.ledger().timestamp());
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    env.storage().persistent().remove(&CredentialKey::PendingRevocation(credential_id));
+    record_status_change(env, credential_id, VerificationStatus::Revoked);
+
+    let mut by_reason = get_revocations_by_reason(env, reason);
+    by_reason.push_back(credential_id);
+    env.storage().persistent().set(&CredentialKey::RevocationsByReason(reason), &by_reason);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("revoked")), (credential_id, revoker.clone(), reason, note));
+    notify_revocation_listener(env, credential_id);
+
+    let mut revoked = Vec::from_array(env, [credential_id]);
+    revoked.append(&cascade_to_dependents(env, credential_id, revoker, reason));
+    revoked
+}
+
+// A listener that traps or returns an error is reported with an event and the revocation
+// stands; budget exhaustion can't be caught and aborts the whole invocation
+fn notify_revocation_listener(env: &Env, credential_id: u64) {
+    let Some(listener) = get_revocation_listener(env) else {
+        return;
+    };
+
+    let args: Vec<Val> = Vec::from_array(env, [credential_id.into_val(env)]);
+    if !matches!(
+        env.try_invoke_contract::<Val, soroban_sdk::Error>(&listener, &Symbol::new(env, "on_revoked"), args),
+        Ok(Ok(_))
+    ) {
+        publish_event(env, (symbol_short!("listener"), symbol_short!("failed")), (credential_id, listener));
+    }
+}
+
+// Dependents of a revoked prerequisite are revoked (recursively) or queued for review,
+// per `CascadeMode`; returns the ids revoked
+fn cascade_to_dependents(env: &Env, credential_id: u64, revoker: Address, reason: RevocationReason) -> Vec<u64> {
+    let mut revoked = Vec::new(env);
+    let mode = get_cascade_mode(env);
+    if mode == CascadeMode::Off {
+        return revoked;
+    }
+
+    for dependent_id in get_dependents(env, credential_id).iter() {
+        let Some(dependent) = env.storage().persistent().get::<_, Credential>(&CredentialKey::Credential(dependent_id)) else {
+            continue;
+        };
+        if dependent.is_revoked {
+            continue;
+        }
+
+        match mode {
+            CascadeMode::Revoke => revoked.append(&apply_revocation(
+                env,
+                dependent_id,
+                revoker.clone(),
+                reason,
+                String::from_str(env, "Prerequisite revoked"),
+            )),
+            _ => {
+                env.storage().persistent().set(&CredentialKey::NeedsReview(dependent_id), &credential_id);
+                publish_event(env, (symbol_short!("cred"), symbol_short!("review")), (dependent_id, credential_id));
+            }
+        }
+    }
+    revoked
+}
+
+// Non-panicking form of `require_authorized_issuer`
+fn can_issue_for(env: &Env, issuer: &Address, course_id: &String) -> bool {
+    if is_issuer_expired(env, issuer) {
+        return false;
+    }
+    let course_issuers = get_course_issuers(env, course_id.clone());
+    if !course_issuers.is_empty() {
+        course_issuers.contains(issuer)
+    } else {
+        *issuer == get_admin(env) || is_issuer(env, issuer.clone())
+    }
+}
+
+fn require_authorized_issuer(env: &Env, issuer: &Address, course_id: &String) {
+    if is_issuer_expired(env, issuer) {
+        panic_with_error!(env, CredentialError::IssuerExpired);
+    }
+
+    // A course allowlist replaces the global issuer check rather than adding to it
+    let course_issuers = get_course_issuers(env, course_id.clone());
+    if !course_issuers.is_empty() {
+        if !course_issuers.contains(issuer) {
+            panic_with_error!(env, CredentialError::NotCourseIssuer);
+        }
+    } else if *issuer != get_admin(env) && !is_issuer(env, issuer.clone()) {
+        panic!("Unauthorized issuer");
+    }
+}
+
+// Authorizes the issuer and builds an unsaved credential with the next id and default options
+fn new_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> Credential {
+    issuer.require_auth();
+    build_credential(env, issuer, recipient, title, description, course_id, ipfs_hash)
+}
+
+// Like `new_credential`, for callers that have already authorized on the issuer's behalf
+fn build_credential(
+    env: &Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> Credential {
+    require_not_paused(env);
+
+    if recipient == env.current_contract_address() || is_recipient_blocked(env, recipient.clone()) {
+        panic_with_error!(env, CredentialError::InvalidRecipient);
+    }
+
+    require_authorized_issuer(env, &issuer, &course_id);
+    if get_require_recipient_auth(env) {
+        recipient.require_auth();
+    }
+
+    Credential {
+        id: next_id(env, get_credential_count(env)),
+        issuer,
+        recipient,
+        title,
+        description,
+        course_id,
+        completion_date: env.ledger().timestamp(),
+        ipfs_hash,
+        is_revoked: false,
+        co_issuers: Vec::new(env),
+        co_signers: Vec::new(env),
+        active_from: 0,
+        expires_at: 0,
+        credential_type: CredentialType::Certificate,
+        translations: Map::new(env),
+        supersedes: 0,
+        superseded_by: 0,
+        metadata: Map::new(env),
+        renewal_fee: 0,
+        cohort_id: String::from_str(env, ""),
+        xp: 0,
+        template_id: 0,
+        template_version: 0,
+        encrypted: false,
+        encryption_pubkey_hint: String::from_str(env, ""),
+        display_id: String::from_str(env, ""),
+        revoked_from: 0,
+        is_private: false,
+        grade: 0,
+        hidden_by_recipient: false,
+        is_suspended: false,
+        credits: 0,
+    }
+}
+
+// Credentials in their grace period still count as valid
+fn counts_as_valid(env: &Env, credential: &Credential) -> bool {
+    matches!(credential_status(env, credential), VerificationStatus::Valid | VerificationStatus::GracePeriod)
+}
+
+fn is_purgeable(env: &Env, credential: &Credential) -> bool {
+    credential.expires_at != 0
+        && env.ledger().timestamp() >= credential.expires_at.saturating_add(get_grace_period(env))
+}
+
+// Revoked, or scheduled by `revoke_credential_effective`, and past the effective date
+fn is_revoked_now(env: &Env, credential: &Credential) -> bool {
+    (credential.is_revoked || credential.revoked_from != 0)
+        && env.ledger().timestamp() >= credential.revoked_from
+}
+
+// Steps 2-11 of the `resolve_status` precedence, for an already-loaded credential
+fn credential_status(env: &Env, credential: &Credential) -> VerificationStatus {
+    if env.storage().persistent().has(&CredentialKey::Migrated(credential.id)) {
+        return VerificationStatus::Migrated;
+    }
+
+    if is_revoked_now(env, credential) {
+        return VerificationStatus::Revoked;
+    }
+
+    if credential.is_suspended {
+        return VerificationStatus::Suspended;
+    }
+
+    if credential.superseded_by != 0 {
+        return VerificationStatus::Superseded;
+    }
+
+    if env.ledger().timestamp() < credential.active_from {
+        return VerificationStatus::NotYetActive;
+    }
+
+    if get_require_co_signatures(env) && !is_fully_signed(credential) {
+        return VerificationStatus::AwaitingCoSignatures;
+    }
+
+    if credential.ipfs_hash.is_empty() {
+        return VerificationStatus::DocumentPending;
+    }
+
+    if credential.expires_at != 0 && env.ledger().timestamp() >= credential.expires_at {
+        if is_purgeable(env, credential) {
+            return VerificationStatus::Expired;
+        }
+        return VerificationStatus::GracePeriod;
+    }
+
+    if !passes_verifier_hook(env, credential) {
+        return VerificationStatus::Invalid;
+    }
+
+    VerificationStatus::Valid
+}
+
+// A hook that traps or returns a non-bool is handled per `HookFailureMode`
+fn passes_verifier_hook(env: &Env, credential: &Credential) -> bool {
+    if credential.template_id == 0 {
+        return true;
+    }
+    let Some(hook) = get_verifier_hook(env, credential.template_id) else {
+        return true;
+    };
+
+    let args: Vec<Val> = Vec::from_array(env, [credential.id.into_val(env), credential.recipient.into_val(env)]);
+    match env.try_invoke_contract::<bool, soroban_sdk::Error>(&hook, &Symbol::new(env, "check_credential"), args) {
+        Ok(Ok(passed)) => passed,
+        _ => get_hook_failure_mode(env) == HookFailureMode::Skip,
+    }
+}
+
+// Raw bytes of a string of any length. Its XDR form is a 4-byte ScVal tag and a 4-byte
+// length followed by the (padded) bytes, so those are sliced back out.
+fn string_bytes(env: &Env, value: &String) -> Bytes {
+    value.clone().to_xdr(env).slice(8..8 + value.len())
+}
+
+// Render the template for credentials issued without a display id, then claim the id in the
+// uniqueness index
+fn assign_display_id(env: &Env, credential: &mut Credential) {
+    if credential.display_id.is_empty() {
+        let Some(template) = get_display_id_template(env) else {
+            return;
+        };
+        let seq = next_count(env, env.storage().persistent()
+            .get(&CredentialKeyExt::CourseSequence(credential.course_id.clone()))
+            .unwrap_or(0));
+        env.storage().persistent().set(&CredentialKeyExt::CourseSequence(credential.course_id.clone()), &seq);
+        credential.display_id = render_display_id(env, &template, &credential.course_id, credential.completion_date, seq);
+    }
+
+    if credential.display_id.len() > MAX_DISPLAY_ID_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    let index_key = CredentialKeyExt::DisplayIdIndex(credential.display_id.clone());
+    if env.storage().persistent().has(&index_key) {
+        panic_with_error!(env, CredentialError::DuplicateDisplayId);
+    }
+    env.storage().persistent().set(&index_key, &credential.id);
+}
+
+fn render_display_id(env: &Env, template: &String, course_id: &String, timestamp: u64, seq: u32) -> String {
+    let template = string_bytes(env, template);
+    let placeholders = [
+        (Bytes::from_slice(env, b"{year}"), 0u8),
+        (Bytes::from_slice(env, b"{course}"), 1u8),
+        (Bytes::from_slice(env, b"{seq}"), 2u8),
+    ];
+
+    let mut rendered = Bytes::new(env);
+    let mut i = 0;
+    'outer: while i < template.len() {
+        for (token, kind) in placeholders.iter() {
+            let end = i + token.len();
+            if end <= template.len() && template.slice(i..end) == *token {
+                match kind {
+                    0 => push_decimal(&mut rendered, utc_year(timestamp), 4),
+                    1 => rendered.append(&string_bytes(env, course_id)),
+                    _ => push_decimal(&mut rendered, seq, 4),
+                }
+                i = end;
+                continue 'outer;
+            }
+        }
+        rendered.push_back(template.get(i).unwrap());
+        i += 1;
+    }
+
+    if rendered.len() > MAX_DISPLAY_ID_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    let mut buf = [0u8; MAX_DISPLAY_ID_LEN as usize];
+    let len = rendered.len() as usize;
+    rendered.copy_into_slice(&mut buf[..len]);
+    String::from_bytes(env, &buf[..len])
+}
+
+// Decimal digits of `value`, left-padded with zeros to `min_width`
+fn push_decimal(out: &mut Bytes, value: u32, min_width: u32) {
+    let mut digits = [0u8; 10];
+    let mut len = 0;
+    let mut rest = value;
+    while rest > 0 || len < min_width as usize {
+        digits[len] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        len += 1;
+    }
+    for digit in digits[..len].iter().rev() {
+        out.push_back(*digit);
+    }
+}
+
+// Gregorian year of a unix timestamp (days-to-civil, proleptic calendar)
+fn utc_year(timestamp: u64) -> u32 {
+    let z = timestamp / SECONDS_PER_DAY + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let month_index = (5 * doy + 2) / 153; // 0 = March
+    let year = yoe + era * 400 + u64::from(month_index >= 10);
+    year as u32
+}
+
+// Distinct course ids of a user's valid credentials, in issuance order
+fn valid_courses(env: &Env, user: Address) -> Vec<String> {
+    let mut courses = Vec::new(env);
+
+    for credential_id in get_user_credentials(env, user).iter() {
+        let credential = get_credential(env, credential_id);
+        if counts_as_valid(env, &credential) && !courses.contains(&credential.course_id) {
+            courses.push_back(credential.course_id);
+        }
+    }
+
+    courses
+}
+
+fn summarize(env: &Env, credential_ids: Vec<u64>) -> Vec<VerificationSummary> {
+    let mut summaries = Vec::new(env);
+
+    for credential_id in credential_ids.iter() {
+        let credential = get_credential(env, credential_id);
+        if credential.hidden_by_recipient {
+            continue;
+        }
+        let status = credential_status(env, &credential);
+        let credential = mask_issuer(env, credential);
+        summaries.push_back(VerificationSummary {
+            credential_id,
+            status,
+            issuer: credential.issuer,
+            credential_type: credential.credential_type,
+            completion_date: credential.completion_date,
+            active_from: credential.active_from,
+            expires_at: credential.expires_at,
+        });
+    }
+
+    summaries
+}
+
+// Sequential id after `current`; refuses to wrap back to an id that's already taken
+fn next_id(env: &Env, current: u64) -> u64 {
+    current.checked_add(1)
+        .unwrap_or_else(|| panic_with_error!(env, CredentialError::CounterOverflow))
+}
+
+// `next_id` for the u32 tallies (stats, per-day and per-course counters)
+fn next_count(env: &Env, current: u32) -> u32 {
+    current.checked_add(1)
+        .unwrap_or_else(|| panic_with_error!(env, CredentialError::CounterOverflow))
+}
+
+// Timestamps plus admin- or issuer-supplied durations, and running totals
+fn checked_sum(env: &Env, a: u64, b: u64) -> u64 {
+    a.checked_add(b)
+        .unwrap_or_else(|| panic_with_error!(env, CredentialError::CounterOverflow))
+}
+
+// Persists a freshly built credential and maintains every index that references it
+fn record_issuance(env: &Env, mut credential: Credential) -> Credential {
+    credential.title = sanitize_text_fields(env, credential.title, &credential.description);
+    validate_credential(env, &credential);
+    consume_quota(env, &credential.issuer);
+    assign_display_id(env, &mut credential);
+    store_credential(env, &credential);
+
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let issued_today: u32 = env.storage().persistent()
+        .get(&CredentialKey::IssuanceByDay(day))
+        .unwrap_or(0);
+    env.storage().persistent().set(&CredentialKey::IssuanceByDay(day), &next_count(env, issued_today));
+
+    credential
+}
+
+// Writes a credential under its own id, which must lie past the current count, and every index
+// shared by issuance and migration import. Ids skipped on the way become empty merkle leaves so
+// a credential's leaf stays at `id - 1`.
+fn store_credential(env: &Env, credential: &Credential) {
+    let count = credential.id;
+    let previous = get_credential_count(env);
+    if count <= previous {
+        panic_with_error!(env, CredentialError::IdTaken);
+    }
+
+    env.storage().persistent().set(&CredentialKey::Credential(count), credential);
+
+    // Index the content fingerprint so holders of a QR code can reference it
+    let fingerprint = compute_fingerprint(env, credential);
+    env.storage().persistent().set(&CredentialKey::FingerprintIndex(fingerprint.clone()), &count);
+    for skipped in (previous + 1)..count {
+        append_merkle_leaf(env, BytesN::from_array(env, &[0u8; 32]), skipped);
+    }
+    append_merkle_leaf(env, fingerprint, count);
+    reindex_expiry(env, count, 0, credential.expires_at);
+
+    let recipient = credential.recipient.clone();
+    let mut user_creds = get_user_credentials(env, recipient.clone());
+    let max_per_user = get_max_credentials_per_user(env);
+    if max_per_user != 0 && user_creds.len() >= max_per_user {
+        panic_with_error!(env, CredentialError::UserLimitReached);
+    }
+    user_creds.push_back(count);
+    env.storage().persistent().set(&CredentialKey::UserCredentials(recipient.clone()), &user_creds);
+
+    // Integrate with user profile
+    user_profile::add_credential(env, recipient.clone(), count);
+
+    let mut stats = get_issuer_stats(env, credential.issuer.clone());
+    if credential.is_revoked {
+        stats.revoked_count = next_count(env, stats.revoked_count);
+    } else {
+        stats.valid_count = next_count(env, stats.valid_count);
+        adjust_user_xp(env, recipient, credential.xp as i64);
+    }
+    env.storage().persistent().set(&CredentialKey::IssuerStats(credential.issuer.clone()), &stats);
+
+    let mut issued = issuer_credential_ids(env, credential.issuer.clone());
+    issued.push_back(count);
+    env.storage().persistent().set(&CredentialKey::IssuerCredentials(credential.issuer.clone()), &issued);
+
+    let mut in_course = get_course_credentials(env, credential.course_id.clone());
+    in_course.push_back(count);
+    env.storage().persistent().set(&CredentialKeyExt::CourseCredentials(credential.course_id.clone()), &in_course);
+
+    env.storage().instance().set(&CredentialKey::CredentialCount, &count);
+    let status = if credential.is_revoked { VerificationStatus::Revoked } else { VerificationStatus::Valid };
+    record_status_change(env, count, status);
+}
+
+fn is_issuer_expired(env: &Env, issuer: &Address) -> bool {
+    get_issuer_expiry(env, issuer.clone()).is_some_and(|expiry| env.ledger().timestamp() >= expiry)
+}
+
+fn consume_quota(env: &Env, issuer: &Address) {
+    if let Some(balance) = get_quota(env, issuer.clone()) {
+        if balance == 0 {
+            panic_with_error!(env, CredentialError::QuotaExhausted);
+        }
+        env.storage().persistent().set(&CredentialKeyExt::Quota(issuer.clone()), &(balance - 1));
+    }
+}
+
+// Text sanitization for issuance and every later edit of a title/description pair (translations,
+// templates), while `FeatureFlags::sanitize_text` is on: titles come back trimmed with runs of
+// spaces collapsed, and the description is only checked
+fn sanitize_text_fields(env: &Env, title: String, description: &String) -> String {
+    if !get_sanitize_text(env) {
+        return title;
+    }
+    reject_control_bytes(env, description, true);
+    sanitize_title(env, &title)
+}
+
+// Trim and collapse spaces, rejecting control bytes with `InvalidCharacters`: 0x00-0x1F and 0x7F
+// in titles; the same in descriptions except tab, newline and carriage return
+fn sanitize_title(env: &Env, title: &String) -> String {
+    reject_control_bytes(env, title, false);
+
+    let raw = string_bytes(env, title);
+    let mut cleaned = Bytes::new(env);
+    let mut pending_space = false;
+    for byte in raw.iter() {
+        if byte == b' ' {
+            pending_space = !cleaned.is_empty();
+            continue;
+        }
+        if pending_space {
+            cleaned.push_back(b' ');
+            pending_space = false;
+        }
+        cleaned.push_back(byte);
+    }
+
+    if cleaned == raw {
+        return title.clone();
+    }
+    if cleaned.len() as usize > MAX_SANITIZED_TITLE_LEN {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+    let mut buf = [0u8; MAX_SANITIZED_TITLE_LEN];
+    let len = cleaned.len() as usize;
+    cleaned.copy_into_slice(&mut buf[..len]);
+    String::from_bytes(env, &buf[..len])
+}
+
+fn reject_control_bytes(env: &Env, value: &String, allow_line_breaks: bool) {
+    let disallowed = string_bytes(env, value).iter().any(|byte| {
+        let line_break = matches!(byte, b'\t' | b'\n' | b'\r');
+        (byte < 0x20 || byte == 0x7F) && !(allow_line_breaks && line_break)
+    });
+    if disallowed {
+        panic_with_error!(env, CredentialError::InvalidCharacters);
+    }
+}
+
+// Content checks applied to every credential before it's stored
+fn validate_credential(env: &Env, credential: &Credential) {
+    validate_text_fields(env, &credential.title, &credential.description);
+
+    let (min_completion_date, max_future_skew) = get_completion_date_bounds(env);
+    if credential.completion_date < min_completion_date
+        || credential.completion_date > env.ledger().timestamp().saturating_add(max_future_skew)
+    {
+        panic_with_error!(env, CredentialError::InvalidCompletionDate);
+    }
+}
+
+fn validate_text_fields(env: &Env, title: &String, description: &String) {
+    let (max_title_len, max_description_len) = get_field_limits(env);
+    if title.len() > max_title_len || description.len() > max_description_len {
+        panic_with_error!(env, CredentialError::FieldTooLong);
+    }
+}
+
+// Incremental (deposit-contract style) merkle insert: only the left-hand branch of the
+// frontier is kept, so each leaf costs MERKLE_TREE_DEPTH hashes rather than a full rebuild.
+fn append_merkle_leaf(env: &Env, leaf: BytesN<32>, leaf_count: u64) {
+    let mut branch: Vec<BytesN<32>> = env.storage().instance()
+        .get(&CredentialKey::MerkleBranch)
+        .unwrap_or(Vec::new(env));
+
+    let mut node = leaf;
+    let mut size = leaf_count;
+    for level in 0..MERKLE_TREE_DEPTH {
+        if size & 1 == 1 {
+            if level < branch.len() {
+                branch.set(level, node);
+            } else {
+                branch.push_back(node);
+            }
+            break;
+        }
+        node = hash_pair(env, &branch.get(level).unwrap(), &node);
+        size >>= 1;
+    }
+
+    let root = compute_merkle_root(env, &branch, leaf_count);
+    env.storage().instance().set(&CredentialKey::MerkleBranch, &branch);
+    env.storage().instance().set(&CredentialKey::MerkleRoot, &root);
+}
+
+fn compute_merkle_root(env: &Env, branch: &Vec<BytesN<32>>, leaf_count: u64) -> BytesN<32> {
+    let mut zero = BytesN::from_array(env, &[0u8; 32]);
+    let mut node = zero.clone();
+    let mut size = leaf_count;
+
+    for level in 0..MERKLE_TREE_DEPTH {
+        node = if size & 1 == 1 {
+            hash_pair(env, &branch.get(level).unwrap(), &node)
+        } else {
+            hash_pair(env, &node, &zero)
+        };
+        zero = hash_pair(env, &zero, &zero);
+        size >>= 1;
+    }
+
+    node
+}
+
+fn hash_pair(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from(left);
+    data.append(&Bytes::from(right));
+    env.crypto().sha256(&data).into()
+}
+
+fn is_fully_signed(credential: &Credential) -> bool {
+    credential.co_issuers.iter().all(|co_issuer| credential.co_signers.contains(&co_issuer))
+}
+
+// Store an edited credential and move its fingerprint index entry. Same rule as
+// attach_document: the first recorded leaf stays the inclusion-proof target.
+fn store_with_fingerprint(env: &Env, credential: &Credential, old_fingerprint: BytesN<32>) {
+    let persistent = env.storage().persistent();
+    let new_fingerprint = compute_fingerprint(env, credential);
+    if new_fingerprint != old_fingerprint {
+        persistent.remove(&CredentialKey::FingerprintIndex(old_fingerprint.clone()));
+        if !persistent.has(&CredentialKey::IssuedLeaf(credential.id)) {
+            persistent.set(&CredentialKey::IssuedLeaf(credential.id), &old_fingerprint);
+        }
+        persistent.set(&CredentialKey::FingerprintIndex(new_fingerprint), &credential.id);
+    }
+    persistent.set(&CredentialKey::Credential(credential.id), credential);
+}
+
+// Saturates at zero, so a total can't underflow if it was ever recorded short
+fn adjust_user_xp(env: &Env, user: Address, delta: i64) {
+    if delta == 0 {
+        return;
+    }
+    let total = get_user_xp(env, user.clone()).saturating_add_signed(delta);
+    env.storage().persistent().set(&CredentialKeyExt::UserXp(user.clone()), &total);
+    update_leaderboard(env, user, total);
+}
+
+fn get_leaderboard(env: &Env) -> Vec<(Address, u64)> {
+    env.storage().instance()
+        .get(&CredentialKeyExt::Leaderboard)
+        .unwrap_or(Vec::new(env))
+}
+
+// Re-place `user` in the capped board; on equal XP the earlier entrant stays ahead
+fn update_leaderboard(env: &Env, user: Address, total: u64) {
+    let mut board = get_leaderboard(env);
+    if let Some(index) = board.iter().position(|(address, _)| address == user) {
+        board.remove(index as u32);
+    }
+
+    if total > 0 {
+        let position = board.iter().position(|(_, xp)| xp < total).map_or(board.len(), |i| i as u32);
+        if position < LEADERBOARD_SIZE {
+            board.insert(position, (user, total));
+        }
+        while board.len() > LEADERBOARD_SIZE {
+            board.pop_back();
+        }
+    }
+
+    env.storage().instance().set(&CredentialKeyExt::Leaderboard, &board);
+}
+
+// Revoke whichever of `ids` still exist and aren't revoked, skipping any delay, and log them
+fn revoke_bulk(env: &Env, admin: &Address, ids: Vec<u64>, reason: RevocationReason, note: String) -> u32 {
+    let mut revoked = Vec::new(env);
+    for credential_id in ids.iter() {
+        if try_get_credential(env, credential_id).is_some_and(|credential| !credential.is_revoked) {
+            revoked.append(&apply_revocation(env, credential_id, admin.clone(), reason, note.clone()));
+        }
+    }
+
+    let count = revoked.len();
+    log_bulk_action(env, admin.clone(), revoked);
+    count
+}
+
+// Record a bulk revocation; nothing is logged when it revoked nothing
+fn log_bulk_action(env: &Env, admin: Address, credential_ids: Vec<u64>) {
+    if credential_ids.is_empty() {
+        return;
+    }
+
+    let action_id = next_id(env, get_bulk_action_count(env));
+    let action = BulkAction { admin, credential_ids, performed_at: env.ledger().timestamp(), undone: false };
+    env.storage().persistent().set(&CredentialKeyExt::BulkActionLog(action_id), &action);
+    env.storage().instance().set(&CredentialKeyExt::BulkActionCount, &action_id);
+
+    publish_event(env, (symbol_short!("bulk"), symbol_short!("logged")), (action_id, action.credential_ids.len()));
+}
+
+/// Move a credential's entry in the expiry index; an expiry of 0 means "not indexed". The index
+/// is one (expires_at, id)-sorted bucket per `EXPIRY_BUCKET_SECS` window plus a sorted list of the
+/// non-empty windows. Buckets whose whole window has lapsed are dropped on every write.
+fn reindex_expiry(env: &Env, credential_id: u64, old_expiry: u64, new_expiry: u64) {
+    let mut weeks = get_expiry_weeks(env);
+
+    if old_expiry != 0 {
+        let week = old_expiry / EXPIRY_BUCKET_SECS;
+        let mut bucket = get_expiry_bucket(env, week);
+        if let Ok(position) = bucket.binary_search((old_expiry, credential_id)) {
+            bucket.remove(position);
+            store_expiry_bucket(env, &mut weeks, week, &bucket);
+        }
+    }
+    if new_expiry != 0 {
+        let week = new_expiry / EXPIRY_BUCKET_SECS;
+        let mut bucket = get_expiry_bucket(env, week);
+        if let Err(position) = bucket.binary_search((new_expiry, credential_id)) {
+            bucket.insert(position, (new_expiry, credential_id));
+            store_expiry_bucket(env, &mut weeks, week, &bucket);
+        }
+    }
+
+    let now = env.ledger().timestamp();
+    while let Some(week) = weeks.first() {
+        if week.saturating_add(1).saturating_mul(EXPIRY_BUCKET_SECS) > now {
+            break;
+        }
+        env.storage().persistent().remove(&CredentialKeyExt::ExpiryBucket(week));
+        weeks.pop_front();
+    }
+
+    env.storage().persistent().set(&CredentialKeyExt::ExpiryWeeks, &weeks);
+}
+
+fn get_expiry_weeks(env: &Env) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::ExpiryWeeks)
+        .unwrap_or(Vec::new(env))
+}
+
+fn get_expiry_bucket(env: &Env, week: u64) -> Vec<(u64, u64)> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::ExpiryBucket(week))
+        .unwrap_or(Vec::new(env))
+}
+
+// Write a bucket back, keeping `weeks` in step with which buckets are non-empty
+fn store_expiry_bucket(env: &Env, weeks: &mut Vec<u64>, week: u64, bucket: &Vec<(u64, u64)>) {
+    let key = CredentialKeyExt::ExpiryBucket(week);
+    if bucket.is_empty() {
+        env.storage().persistent().remove(&key);
+        if let Ok(position) = weeks.binary_search(week) {
+            weeks.remove(position);
+        }
+    } else {
+        env.storage().persistent().set(&key, bucket);
+        if let Err(position) = weeks.binary_search(week) {
+            weeks.insert(position, week);
+        }
+    }
+}
+
+fn record_status_change(env: &Env, credential_id: u64, status: VerificationStatus) {
+    let mut history = get_history(env, credential_id);
+    history.push_back(StatusChange { status, changed_at: env.ledger().timestamp() });
+    env.storage().persistent().set(&CredentialKeyExt::History(credential_id), &history);
+}
+
+// Whether the issuer's policy lets `apply_reinstatement` go ahead
+fn reinstatement_allowed(env: &Env, credential_id: u64) -> bool {
+    get_issuer_policy(env, get_credential(env, credential_id).issuer).is_none_or(|policy| policy.allow_reinstate)
+}
+
+// Record an appeal's final status; the caller has already applied any reinstatement
+fn close_appeal(env: &Env, mut appeal: Appeal, uphold: bool, note: String) {
+    appeal.status = if uphold { AppealStatus::Upheld } else { AppealStatus::Reinstated };
+    appeal.resolved_at = env.ledger().timestamp();
+    appeal.resolution_note = note;
+    env.storage().persistent().set(&CredentialKey::Appeal(appeal.credential_id), &appeal);
+
+    publish_event(env, (symbol_short!("appeal"), symbol_short!("resolved")), (appeal.credential_id, uphold));
+}
+
+fn apply_reinstatement(env: &Env, credential_id: u64) {
+    let mut credential = get_credential(env, credential_id);
+
+    if let Some(policy) = get_issuer_policy(env, credential.issuer.clone()) {
+        if !policy.allow_reinstate {
+            panic_with_error!(env, CredentialError::ReinstatementNotAllowed);
+        }
+    }
+
+    if credential.is_revoked {
+        let mut stats = get_issuer_stats(env, credential.issuer.clone());
+        stats.valid_count = next_count(env, stats.valid_count);
+        stats.revoked_count = stats.revoked_count.saturating_sub(1);
+        env.storage().persistent().set(&CredentialKey::IssuerStats(credential.issuer.clone()), &stats);
+        adjust_user_xp(env, credential.recipient.clone(), credential.xp as i64);
+    }
+
+    credential.is_revoked = false;
+    credential.revoked_from = 0;
+    env.storage().persistent().set(&CredentialKey::Credential(credential_id), &credential);
+    record_status_change(env, credential_id, VerificationStatus::Valid);
+
+    publish_event(env, (symbol_short!("cred"), symbol_short!("reinstate")), credential_id);
+}
+
+fn require_not_paused(env: &Env) {
+    if is_paused(env) {
+        panic!("Contract is paused");
+    }
+}
+
+fn require_admin(env: &Env, caller: &Address) {
+    caller.require_auth();
+
+    if *caller != get_admin(env) {
+        panic!("Only admin can perform this action");
+    }
+}
+
+fn get_verification_log(env: &Env, credential_id: u64) -> Vec<VerificationLogEntry> {
+    env.storage().persistent()
+        .get(&CredentialKeyExt::VerificationLog(credential_id))
+        .unwrap_or(Vec::new(env))
+}
+
+fn get_verification_notes(env: &Env, credential_id: u64) -> Vec<VerificationNote> {
+    env.storage().persistent()
+        .get(&CredentialKey::VerificationNotes(credential_id))
+        .unwrap_or(Vec::new(env))
+}
+
+// Approvals from signers since removed from the set don't count towards the threshold
+fn consume_approvals(env: &Env, action_hash: BytesN<32>, threshold: u32) {
+    let (signers, _) = get_admin_signers(env);
+    let approvals = get_action_approvals(env, action_hash.clone());
+
+    let valid_approvals = approvals.iter().filter(|signer| signers.contains(signer)).count() as u32;
+    if valid_approvals < threshold {
+        panic!("Not enough approvals");
+    }
+
+    env.storage().persistent().remove(&CredentialKey::ActionApprovals(action_hash));
+}
+
+/// Set the admin once, at deployment
+pub fn initialize(env: &Env, admin: Address) {
+    if try_get_admin(env).is_some() {
+        panic!("Contract already initialized");
+    }
+    env.storage().instance().set(&Symbol::new(env, "admin"), &admin);
+}
+
+pub(crate) fn get_admin(env: &Env) -> Address {
+    try_get_admin(env).unwrap_or_else(|| panic!("Admin not found"))
+}
+
+fn try_get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&Symbol::new(env, "admin"))
+}
+
+// SHA-256 over the immutable content fields, so revocation state doesn't change it
+fn compute_fingerprint(env: &Env, credential: &Credential) -> BytesN<32> {
+    let content = (
+        credential.id,
+        credential.issuer.clone(),
+        credential.recipient.clone(),
+        credential.title.clone(),
+        credential.description.clone(),
+        credential.course_id.clone(),
+        credential.completion_date,
+        credential.ipfs_hash.clone(),
+    );
+    env.crypto().sha256(&content.to_xdr(env)).into()
+}
+
+/// Publish with the deployment's event namespace as the leading topic
+fn publish_event<T, D>(env: &Env, topics: T, data: D)
+where
+    T: IntoVal<Env, Vec<Val>>,
+    D: IntoVal<Env, Val>,
+{
+    let mut namespaced: Vec<Val> = Vec::from_array(env, [get_event_namespace(env).into_val(env)]);
+    namespaced.append(&topics.into_val(env));
+    env.events().publish(namespaced, data);
+}
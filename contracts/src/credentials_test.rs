@@ -0,0 +1,3664 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, token, vec, xdr::FromXdr, Env, Error, Address, Bytes, BytesN, IntoVal, InvokeError, String, Symbol, Vec};
+use crate::{StarkEdContract, StarkEdContractClient};
+use crate::credentials::{AppealStatus, CascadeMode, Config, Credential, CredentialError, CredentialKey, CredentialKeyExt, CredentialType, CredentialUpdate, ExportEnvelope, FeatureFlags, HookFailureMode, IssuerInfo, IssuerPolicy, MemberSpec, RenewalConfig, RevocationReason, StatusChange, VerificationStatus, CONTRACT_VERSION, DEFAULT_EVENT_NAMESPACE, EXPORT_FORMAT_VERSION, LEADERBOARD_SIZE, MAX_HISTORY_BATCH, MAX_PINNED_CREDENTIALS, MERKLE_TREE_DEPTH, NEVER_EXPIRES};
+
+fn setup(env: &Env) -> (StarkEdContractClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StarkEdContract);
+    let admin = Address::generate(env);
+
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(&Symbol::new(env, "admin"), &admin);
+    });
+
+    (StarkEdContractClient::new(env, &contract_id), admin)
+}
+
+fn issue(env: &Env, client: &StarkEdContractClient, issuer: &Address, recipient: &Address, course_id: &str) -> u64 {
+    client.issue_credential(
+        issuer,
+        recipient,
+        &String::from_str(env, "Rust on Stellar"),
+        &String::from_str(env, "Completed Soroban basics"),
+        &String::from_str(env, course_id),
+        &String::from_str(env, "ipfs://Qm..."),
+    )
+}
+
+#[test]
+fn test_issue_and_verify_credential() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    assert_eq!(cred_id, 1);
+    assert_eq!(client.get_credential_count(), 1);
+
+    let cred = client.get_credential(&cred_id);
+    assert_eq!(cred.recipient, recipient);
+    assert!(!cred.is_revoked);
+
+    assert!(client.verify_credential(&cred_id));
+
+    // Revoke
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    let revoked_cred = client.get_credential(&cred_id);
+    assert!(revoked_cred.is_revoked);
+
+    // Verify should now return false
+    assert!(!client.verify_credential(&cred_id));
+
+    // User credential list
+    let user_creds: Vec<u64> = client.get_user_credentials(&recipient);
+    assert_eq!(user_creds.len(), 1);
+    assert_eq!(user_creds.get(0).unwrap(), 1);
+}
+
+#[test]
+fn test_revoke_by_fingerprint() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let first = issue(&env, &client, &admin, &recipient, "course-001");
+    let second = issue(&env, &client, &admin, &recipient, "course-002");
+
+    // Distinct credentials have distinct fingerprints
+    let fingerprint = client.credential_fingerprint(&second);
+    assert_ne!(fingerprint, client.credential_fingerprint(&first));
+
+    let note = Some(String::from_str(&env, "Reported forgery"));
+    client.revoke_by_fingerprint(&fingerprint, &admin, &RevocationReason::Fraud, &note);
+
+    assert!(client.verify_credential(&first));
+    assert!(!client.verify_credential(&second));
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::Fraud), vec![&env, second]);
+
+    // Revocation doesn't change the content fingerprint
+    assert_eq!(client.credential_fingerprint(&second), fingerprint);
+}
+
+#[test]
+#[should_panic(expected = "Credential not found")]
+fn test_revoke_by_unknown_fingerprint() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let unknown = BytesN::from_array(&env, &[7u8; 32]);
+    client.revoke_by_fingerprint(&unknown, &admin, &RevocationReason::Fraud, &None);
+}
+
+#[test]
+fn test_delayed_revocation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.set_revocation_delay(&admin, &3600);
+
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+
+    // Scheduled, but still valid until the delay elapses
+    assert!(client.verify_credential(&cred_id));
+    let pending = client.get_pending_revocation(&cred_id).unwrap();
+    assert_eq!(pending.executable_at, env.ledger().timestamp() + 3600);
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    client.execute_revocation(&cred_id);
+
+    assert!(!client.verify_credential(&cred_id));
+    assert!(client.get_pending_revocation(&cred_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Revocation delay not elapsed")]
+fn test_execute_revocation_before_delay() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.set_revocation_delay(&admin, &3600);
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+
+    client.execute_revocation(&cred_id);
+}
+
+#[test]
+fn test_emergency_revoke_is_audited() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.set_revocation_delay(&admin, &3600);
+    assert_eq!(client.get_emergency_revoke_count(), 0);
+
+    let note = String::from_str(&env, "Leaked exam answers");
+    client.emergency_revoke(&cred_id, &admin, &RevocationReason::PolicyViolation, &Some(note.clone()));
+
+    // Takes effect immediately despite the configured delay
+    assert!(!client.verify_credential(&cred_id));
+    assert_eq!(client.get_emergency_revoke_count(), 1);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (DEFAULT_EVENT_NAMESPACE, Symbol::new(&env, "emergency_revoke"), cred_id).into_val(&env));
+    let (logged_admin, logged_reason, logged_note): (Address, RevocationReason, String) = data.into_val(&env);
+    assert_eq!(logged_admin, admin);
+    assert_eq!(logged_reason, RevocationReason::PolicyViolation);
+    assert_eq!(logged_note, note);
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::PolicyViolation), vec![&env, cred_id]);
+}
+
+#[test]
+fn test_get_user_credentials_after_cursor() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    issue(&env, &client, &admin, &recipient, "course-001");
+    issue(&env, &client, &admin, &other, "course-001");
+    issue(&env, &client, &admin, &recipient, "course-002");
+    issue(&env, &client, &admin, &recipient, "course-003");
+
+    let first_page = client.get_user_credentials_after(&recipient, &0, &2);
+    assert_eq!(first_page, vec![&env, 1, 3]);
+
+    // A credential issued mid-iteration lands after the cursor
+    issue(&env, &client, &admin, &recipient, "course-004");
+
+    let cursor = first_page.last().unwrap();
+    let second_page = client.get_user_credentials_after(&recipient, &cursor, &2);
+    assert_eq!(second_page, vec![&env, 4, 5]);
+
+    let last_page = client.get_user_credentials_after(&recipient, &5, &2);
+    assert_eq!(last_page.len(), 0);
+}
+
+#[test]
+fn test_cosigned_credential() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let university_a = Address::generate(&env);
+    let university_b = Address::generate(&env);
+
+    let cred_id = client.issue_cosigned_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Joint MSc"),
+        &String::from_str(&env, "Dual-institution program"),
+        &String::from_str(&env, "course-joint"),
+        &String::from_str(&env, "ipfs://Qm..."),
+        &vec![&env, university_a.clone(), university_b.clone()],
+    );
+    client.set_require_co_signatures(&admin, &true);
+
+    assert!(!client.fully_signed(&cred_id));
+    assert!(!client.verify_credential(&cred_id));
+
+    client.cosign_credential(&cred_id, &university_a);
+    assert!(!client.fully_signed(&cred_id));
+    assert!(!client.verify_credential(&cred_id));
+
+    client.cosign_credential(&cred_id, &university_b);
+    assert!(client.fully_signed(&cred_id));
+    assert!(client.verify_credential(&cred_id));
+}
+
+#[test]
+#[should_panic(expected = "Not a co-issuer of this credential")]
+fn test_cosign_by_unlisted_issuer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.cosign_credential(&cred_id, &Address::generate(&env));
+}
+
+#[test]
+fn test_credential_storage_size() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let minimal = client.issue_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "A"),
+        &String::from_str(&env, ""),
+        &String::from_str(&env, "c1"),
+        &String::from_str(&env, ""),
+    );
+    let heavy = client.issue_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Advanced Smart Contract Engineering on Stellar"),
+        &String::from_str(&env, "Covers Soroban storage, auth, events, cross-contract calls, testing and deployment in depth"),
+        &String::from_str(&env, "course-advanced-soroban"),
+        &String::from_str(&env, "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let minimal_size = client.credential_storage_size(&minimal);
+    assert!(minimal_size > 0);
+    assert!(client.credential_storage_size(&heavy) > minimal_size);
+}
+
+#[test]
+fn test_appeal_reinstates_credential() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+
+    client.file_appeal(&cred_id, &recipient, &String::from_str(&env, "Revoked in error"));
+    let appeal = client.get_appeal(&cred_id).unwrap();
+    assert_eq!(appeal.status, AppealStatus::Pending);
+    assert_eq!(appeal.recipient, recipient);
+
+    client.resolve_appeal(&cred_id, &admin, &false, &String::from_str(&env, "Registrar confirmed completion"));
+
+    let appeal = client.get_appeal(&cred_id).unwrap();
+    assert_eq!(appeal.status, AppealStatus::Reinstated);
+    assert!(client.verify_credential(&cred_id));
+}
+
+#[test]
+fn test_appeal_upheld_keeps_revocation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+
+    client.file_appeal(&cred_id, &recipient, &String::from_str(&env, "Revoked in error"));
+    client.resolve_appeal(&cred_id, &admin, &true, &String::from_str(&env, "Plagiarism confirmed"));
+
+    let appeal = client.get_appeal(&cred_id).unwrap();
+    assert_eq!(appeal.status, AppealStatus::Upheld);
+    assert_eq!(appeal.resolution_note, String::from_str(&env, "Plagiarism confirmed"));
+    assert!(!client.verify_credential(&cred_id));
+}
+
+#[test]
+fn test_reinstating_directly_closes_the_appeal() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    client.file_appeal(&cred_id, &recipient, &String::from_str(&env, "Revoked in error"));
+
+    client.reinstate_credential(&cred_id, &admin);
+    assert_eq!(client.get_appeal(&cred_id).unwrap().status, AppealStatus::Reinstated);
+    assert!(client.get_pending_appeals().is_empty());
+    assert!(client.try_resolve_appeal(&cred_id, &admin, &false, &String::from_str(&env, "Late")).is_err());
+    assert_eq!(client.get_history(&cred_id).len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Credential is not revoked")]
+fn test_appeal_requires_revocation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.file_appeal(&cred_id, &recipient, &String::from_str(&env, "Nothing to contest"));
+}
+
+#[test]
+fn test_feature_flags() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    let defaults = FeatureFlags {
+        paused: false,
+        require_co_signatures: false,
+        revocation_delay_enabled: false,
+        allow_issuer_self_revoke: false,
+        dedupe_recipients: false,
+        strict_issuer_invalidates: true,
+        sanitize_text: true,
+        require_recipient_auth: false,
+    };
+    assert_eq!(client.get_feature_flags(), defaults);
+
+    client.set_revocation_delay(&admin, &600);
+    let flags = FeatureFlags {
+        paused: true,
+        dedupe_recipients: true,
+        sanitize_text: false,
+        revocation_delay_enabled: true,
+        ..defaults.clone()
+    };
+    client.set_feature_flags(&admin, &flags);
+    assert_eq!(client.get_feature_flags(), flags);
+
+    // The delay flag only reports `set_revocation_delay`
+    let no_delay = FeatureFlags { revocation_delay_enabled: false, ..flags.clone() };
+    assert_eq!(client.try_set_feature_flags(&admin, &no_delay), Err(Ok(CredentialError::InvalidConfig.into())));
+}
+
+#[test]
+#[should_panic(expected = "Contract is paused")]
+fn test_paused_blocks_issuance() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    client.set_paused(&admin, &true);
+    issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+}
+
+fn try_issue_sized(
+    env: &Env,
+    client: &StarkEdContractClient,
+    admin: &Address,
+    title_len: usize,
+    description_len: usize,
+) -> Result<Result<u64, Error>, Result<Error, InvokeError>> {
+    let title = [b't'; 2048];
+    let description = [b'd'; 2048];
+
+    client.try_issue_credential(
+        admin,
+        &Address::generate(env),
+        &String::from_bytes(env, &title[..title_len]),
+        &String::from_bytes(env, &description[..description_len]),
+        &String::from_str(env, "course-001"),
+        &String::from_str(env, "ipfs://Qm..."),
+    )
+}
+
+#[test]
+fn test_field_length_limits() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    assert_eq!(client.get_field_limits(), (128, 1024));
+
+    assert!(try_issue_sized(&env, &client, &admin, 128, 1024).is_ok());
+    assert_eq!(try_issue_sized(&env, &client, &admin, 129, 10), Err(Ok(CredentialError::FieldTooLong.into())));
+    assert_eq!(try_issue_sized(&env, &client, &admin, 10, 1025), Err(Ok(CredentialError::FieldTooLong.into())));
+    assert_eq!(client.get_credential_count(), 1);
+
+    // Limits are adjustable by the admin
+    client.set_field_limits(&admin, &256, &2048);
+    assert!(try_issue_sized(&env, &client, &admin, 129, 1025).is_ok());
+    // ...but never to limits that would block all issuance
+    assert_eq!(client.try_set_field_limits(&admin, &0, &2048), Err(Ok(CredentialError::InvalidConfig.into())));
+    assert_eq!(client.try_set_field_limits(&admin, &256, &0), Err(Ok(CredentialError::InvalidConfig.into())));
+}
+
+fn hash_pair(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from(left);
+    data.append(&Bytes::from(right));
+    env.crypto().sha256(&data).into()
+}
+
+// Builds the tree off-chain the way a light client would, returning (root, proof for `index`)
+fn merkle_proof(env: &Env, leaves: &Vec<BytesN<32>>, index: u32) -> (BytesN<32>, Vec<BytesN<32>>) {
+    let mut level = leaves.clone();
+    let mut zero = BytesN::from_array(env, &[0u8; 32]);
+    let mut index = index;
+    let mut proof = Vec::new(env);
+
+    for _ in 0..MERKLE_TREE_DEPTH {
+        proof.push_back(level.get(index ^ 1).unwrap_or(zero.clone()));
+
+        let mut next = Vec::new(env);
+        let mut i = 0;
+        while i < level.len() {
+            let right = level.get(i + 1).unwrap_or(zero.clone());
+            next.push_back(hash_pair(env, &level.get(i).unwrap(), &right));
+            i += 2;
+        }
+        if next.is_empty() {
+            next.push_back(hash_pair(env, &zero, &zero));
+        }
+
+        level = next;
+        zero = hash_pair(env, &zero, &zero);
+        index /= 2;
+    }
+
+    (level.get(0).unwrap(), proof)
+}
+
+#[test]
+fn test_merkle_inclusion_proofs() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let empty_root = client.get_credential_root();
+
+    let mut leaves = Vec::new(&env);
+    for course in ["course-001", "course-002", "course-003", "course-004", "course-005"] {
+        let cred_id = issue(&env, &client, &admin, &recipient, course);
+        leaves.push_back(client.credential_fingerprint(&cred_id));
+    }
+
+    let root = client.get_credential_root();
+    assert_ne!(root, empty_root);
+
+    for cred_id in 1..=5u64 {
+        let (expected_root, proof) = merkle_proof(&env, &leaves, (cred_id - 1) as u32);
+        assert_eq!(expected_root, root);
+        assert!(client.verify_inclusion(&cred_id, &proof));
+    }
+
+    // A proof for one member doesn't prove another, and unknown ids are rejected
+    let (_, proof_for_first) = merkle_proof(&env, &leaves, 0);
+    assert!(!client.verify_inclusion(&2, &proof_for_first));
+    assert!(!client.verify_inclusion(&99, &proof_for_first));
+}
+
+#[test]
+fn test_scheduled_credential_activation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let active_from = env.ledger().timestamp() + 30 * 24 * 60 * 60;
+    let cred_id = client.issue_scheduled_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Practitioner License"),
+        &String::from_str(&env, "Effective next month"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "ipfs://Qm..."),
+        &active_from,
+    );
+
+    // Recorded immediately, but not yet valid
+    assert_eq!(client.get_user_credentials(&recipient), vec![&env, cred_id]);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::NotYetActive);
+    assert!(!client.verify_credential(&cred_id));
+
+    env.ledger().with_mut(|li| li.timestamp = active_from);
+
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Valid);
+    assert!(client.verify_credential(&cred_id));
+}
+
+#[test]
+fn test_verify_credential_detailed_statuses() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Valid);
+
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Revoked);
+
+    assert_eq!(client.verify_credential_detailed(&42), VerificationStatus::NotFound);
+}
+
+fn issue_expiring(env: &Env, client: &StarkEdContractClient, issuer: &Address, recipient: &Address, expires_at: u64) -> u64 {
+    client.issue_expiring_credential(
+        issuer,
+        recipient,
+        &String::from_str(env, "Safety Certification"),
+        &String::from_str(env, "Annual renewal required"),
+        &String::from_str(env, "course-001"),
+        &String::from_str(env, "ipfs://Qm..."),
+        &expires_at,
+    )
+}
+
+#[test]
+fn test_purge_expired_credential() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_grace_period(&admin, &100);
+
+    client.set_display_id_template(&admin, &String::from_str(&env, "{course}-{seq}"));
+    let expiring = issue_expiring(&env, &client, &admin, &recipient, 2_000);
+    let valid = issue(&env, &client, &admin, &recipient, "course-002");
+    let fingerprint = client.credential_fingerprint(&expiring);
+    let display_id = client.get_credential(&expiring).display_id;
+    let fall = String::from_str(&env, "Fall 2024");
+    client.assign_cohort(&admin, &expiring, &fall);
+    client.pin_credential(&recipient, &expiring);
+
+    // Within grace it still verifies and can't be purged yet
+    env.ledger().with_mut(|li| li.timestamp = 2_050);
+    assert_eq!(client.verify_credential_detailed(&expiring), VerificationStatus::GracePeriod);
+    assert_eq!(client.try_purge_credential(&expiring, &admin), Err(Ok(CredentialError::NotPurgeable.into())));
+
+    env.ledger().with_mut(|li| li.timestamp = 2_100);
+    assert_eq!(client.verify_credential_detailed(&expiring), VerificationStatus::Expired);
+
+    client.purge_credential(&expiring, &admin);
+
+    assert_eq!(client.verify_credential_detailed(&expiring), VerificationStatus::Purged);
+    assert!(client.try_get_credential(&expiring).is_err());
+    assert_eq!(client.get_user_credentials(&recipient), vec![&env, valid]);
+    assert_eq!(client.get_issuer_credentials(&admin, &None), vec![&env, valid]);
+    assert!(client.get_cohort_credentials(&fall).is_empty());
+    assert!(client.get_pinned_credentials(&recipient).is_empty());
+    assert!(!display_id.is_empty());
+    assert_eq!(client.get_by_display_id(&display_id), None);
+    assert_eq!(client.get_issuer_stats(&admin).valid_count, 1);
+    // The archived fingerprint survives the purge
+    assert_eq!(client.credential_fingerprint(&expiring), fingerprint);
+
+    // A credential without expiry is never purgeable
+    assert_eq!(client.try_purge_credential(&valid, &admin), Err(Ok(CredentialError::NotPurgeable.into())));
+    assert!(client.verify_credential(&valid));
+}
+
+fn issue_typed(env: &Env, client: &StarkEdContractClient, issuer: &Address, recipient: &Address, credential_type: CredentialType) -> u64 {
+    client.issue_typed_credential(
+        issuer,
+        recipient,
+        &String::from_str(env, "Rust on Stellar"),
+        &String::from_str(env, "Completed Soroban basics"),
+        &String::from_str(env, "course-001"),
+        &String::from_str(env, "ipfs://Qm..."),
+        &credential_type,
+    )
+}
+
+#[test]
+fn test_user_type_counts() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    issue_typed(&env, &client, &admin, &recipient, CredentialType::Certificate);
+    issue_typed(&env, &client, &admin, &recipient, CredentialType::Certificate);
+    issue_typed(&env, &client, &admin, &recipient, CredentialType::Degree);
+    let revoked = issue_typed(&env, &client, &admin, &recipient, CredentialType::Badge);
+    issue(&env, &client, &admin, &recipient, "course-002");
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+
+    let counts = client.get_user_type_counts(&recipient);
+    assert_eq!(counts.get(CredentialType::Certificate), Some(3));
+    assert_eq!(counts.get(CredentialType::Degree), Some(1));
+    // Revoked credentials aren't counted
+    assert_eq!(counts.get(CredentialType::Badge), None);
+    assert_eq!(counts.len(), 2);
+}
+
+#[test]
+fn test_issuer_policies() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let lenient = Address::generate(&env);
+    let strict = Address::generate(&env);
+
+    client.add_issuer(&admin, &lenient);
+    client.add_issuer(&admin, &strict);
+    assert!(client.is_issuer(&lenient));
+
+    client.set_issuer_policy(&admin, &lenient, &IssuerPolicy { allow_reinstate: true, revocation_delay: 0 });
+    client.set_issuer_policy(&admin, &strict, &IssuerPolicy { allow_reinstate: false, revocation_delay: 0 });
+
+    let lenient_cred = issue(&env, &client, &lenient, &recipient, "course-001");
+    let strict_cred = issue(&env, &client, &strict, &recipient, "course-002");
+
+    client.revoke_credential(&lenient_cred, &admin, &RevocationReason::Other, &None);
+    client.revoke_credential(&strict_cred, &admin, &RevocationReason::Other, &None);
+
+    client.reinstate_credential(&lenient_cred, &admin);
+    assert!(client.verify_credential(&lenient_cred));
+
+    assert_eq!(
+        client.try_reinstate_credential(&strict_cred, &admin),
+        Err(Ok(CredentialError::ReinstatementNotAllowed.into()))
+    );
+    assert!(!client.verify_credential(&strict_cred));
+}
+
+#[test]
+fn test_issuer_policy_revocation_delay() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    client.add_issuer(&admin, &issuer);
+    client.set_issuer_policy(&admin, &issuer, &IssuerPolicy { allow_reinstate: true, revocation_delay: 600 });
+
+    let cred_id = issue(&env, &client, &issuer, &recipient, "course-001");
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+
+    // The issuer's own delay applies even though no global delay is set
+    assert!(client.verify_credential(&cred_id));
+    assert_eq!(client.get_pending_revocation(&cred_id).unwrap().executable_at, env.ledger().timestamp() + 600);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized issuer")]
+fn test_removed_issuer_cannot_issue() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+
+    client.add_issuer(&admin, &issuer);
+    client.remove_issuer(&admin, &issuer);
+    issue(&env, &client, &issuer, &Address::generate(&env), "course-001");
+}
+
+#[test]
+fn test_share_token_expires() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    let expires_at = env.ledger().timestamp() + 7 * 24 * 60 * 60;
+
+    let token = client.create_share_token(&cred_id, &recipient, &expires_at);
+    let other_token = client.create_share_token(&cred_id, &recipient, &expires_at);
+    assert_ne!(token, other_token);
+
+    assert_eq!(client.verify_by_token(&token), VerificationStatus::Valid);
+
+    env.ledger().with_mut(|li| li.timestamp = expires_at);
+    assert_eq!(client.verify_by_token(&token), VerificationStatus::TokenExpired);
+
+    // Unknown tokens don't verify
+    assert_eq!(client.verify_by_token(&BytesN::from_array(&env, &[1u8; 32])), VerificationStatus::NotFound);
+}
+
+#[test]
+#[should_panic(expected = "Only the recipient can share a credential")]
+fn test_share_token_requires_recipient() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.create_share_token(&cred_id, &Address::generate(&env), &(env.ledger().timestamp() + 60));
+}
+
+// A course-enrollment contract that gates on a credential held in this one
+mod gated_course {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+    use crate::StarkEdContractClient;
+
+    #[contract]
+    pub struct GatedCourseContract;
+
+    #[contractimpl]
+    impl GatedCourseContract {
+        pub fn can_enroll(env: Env, credentials: Address, student: Address, prerequisite_id: u64) -> bool {
+            StarkEdContractClient::new(&env, &credentials).verify_credential_for(&prerequisite_id, &student)
+        }
+    }
+}
+
+#[test]
+fn test_cross_contract_credential_gate() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let student = Address::generate(&env);
+
+    let consumer_id = env.register_contract(None, gated_course::GatedCourseContract);
+    let consumer = gated_course::GatedCourseContractClient::new(&env, &consumer_id);
+
+    let cred_id = issue(&env, &client, &admin, &student, "course-001");
+
+    assert!(client.is_valid(&cred_id));
+    assert!(!client.is_valid(&99));
+
+    assert!(consumer.can_enroll(&client.address, &student, &cred_id));
+    // Someone else's credential doesn't open the gate
+    assert!(!consumer.can_enroll(&client.address, &Address::generate(&env), &cred_id));
+
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    assert!(!consumer.can_enroll(&client.address, &student, &cred_id));
+}
+
+#[test]
+fn test_issuer_reputation_penalizes_revocations() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let careful = Address::generate(&env);
+    let careless = Address::generate(&env);
+    client.add_issuer(&admin, &careful);
+    client.add_issuer(&admin, &careless);
+
+    assert_eq!(client.get_issuer_reputation(&careful), 100);
+
+    for _ in 0..4 {
+        issue(&env, &client, &careful, &Address::generate(&env), "course-001");
+    }
+    let mut careless_ids = Vec::new(&env);
+    for _ in 0..4 {
+        careless_ids.push_back(issue(&env, &client, &careless, &Address::generate(&env), "course-001"));
+    }
+    client.revoke_credential(&careless_ids.get(0).unwrap(), &admin, &RevocationReason::Other, &None);
+    client.revoke_credential(&careless_ids.get(1).unwrap(), &admin, &RevocationReason::Other, &None);
+
+    let stats = client.get_issuer_stats(&careless);
+    assert_eq!(stats.valid_count, 2);
+    assert_eq!(stats.revoked_count, 2);
+
+    // 2 valid / (2 + 2 * 2 revoked) = 33
+    assert_eq!(client.get_issuer_reputation(&careful), 100);
+    assert_eq!(client.get_issuer_reputation(&careless), 33);
+}
+
+#[test]
+fn test_double_revocation_errors_without_event() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    let events_after_first = env.events().all().len();
+
+    assert_eq!(client.try_revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None), Err(Ok(CredentialError::AlreadyRevoked.into())));
+    assert_eq!(env.events().all().len(), events_after_first);
+    assert_eq!(client.get_issuer_stats(&admin).revoked_count, 1);
+}
+
+#[test]
+fn test_localized_title_and_description() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    let es = String::from_str(&env, "es");
+    let title = String::from_str(&env, "Rust en Stellar");
+    let description = String::from_str(&env, "Fundamentos de Soroban completados");
+    client.add_translation(&cred_id, &es, &title, &description, &admin);
+
+    assert_eq!(client.get_localized(&cred_id, &es), (title, description));
+
+    // Unknown languages fall back to the default fields
+    let (fallback_title, fallback_description) = client.get_localized(&cred_id, &String::from_str(&env, "fr"));
+    assert_eq!(fallback_title, String::from_str(&env, "Rust on Stellar"));
+    assert_eq!(fallback_description, String::from_str(&env, "Completed Soroban basics"));
+}
+
+#[test]
+fn test_deferred_document() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let cred_id = client.issue_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, ""),
+    );
+    let issued_fingerprint = client.credential_fingerprint(&cred_id);
+
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::DocumentPending);
+    assert!(!client.verify_credential(&cred_id));
+
+    client.attach_document(&cred_id, &String::from_str(&env, "ipfs://Qm..."), &admin);
+
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Valid);
+    assert_eq!(client.get_credential(&cred_id).ipfs_hash, String::from_str(&env, "ipfs://Qm..."));
+
+    // The tree still proves the issuance-time leaf
+    let (_, proof) = merkle_proof(&env, &vec![&env, issued_fingerprint], 0);
+    assert!(client.verify_inclusion(&cred_id, &proof));
+}
+
+#[test]
+fn test_user_profile_summary() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let degree = issue_typed(&env, &client, &admin, &recipient, CredentialType::Degree);
+    let revoked = issue(&env, &client, &admin, &recipient, "course-002");
+    let expiring = issue_expiring(&env, &client, &admin, &recipient, 2_000);
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+    env.ledger().with_mut(|li| li.timestamp = 3_000);
+
+    let summaries = client.get_user_profile_summary(&recipient);
+    assert_eq!(summaries.len(), 3);
+
+    let first = summaries.get(0).unwrap();
+    assert_eq!(first.credential_id, degree);
+    assert_eq!(first.status, VerificationStatus::Valid);
+    assert_eq!(first.issuer, admin);
+    assert_eq!(first.credential_type, CredentialType::Degree);
+    assert_eq!(first.completion_date, 1_000);
+
+    assert_eq!(summaries.get(1).unwrap().status, VerificationStatus::Revoked);
+
+    let last = summaries.get(2).unwrap();
+    assert_eq!(last.credential_id, expiring);
+    assert_eq!(last.status, VerificationStatus::Expired);
+    assert_eq!(last.expires_at, 2_000);
+
+    // The paged variant follows the credential-id cursor
+    let page = client.get_user_profile_summary_after(&recipient, &degree, &1);
+    assert_eq!(page, vec![&env, summaries.get(1).unwrap()]);
+
+    // Hidden credentials don't use up a page
+    client.recipient_hide_credential(&revoked, &recipient);
+    let page = client.get_user_profile_summary_after(&recipient, &degree, &1);
+    assert_eq!(page, vec![&env, last]);
+}
+
+#[test]
+fn test_course_issuer_allowlist() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let medical_school = Address::generate(&env);
+    let bootcamp = Address::generate(&env);
+    client.add_issuer(&admin, &medical_school);
+    client.add_issuer(&admin, &bootcamp);
+
+    let course_id = String::from_str(&env, "surgery-101");
+    client.set_course_issuers(&admin, &course_id, &vec![&env, medical_school.clone()]);
+
+    assert_eq!(issue(&env, &client, &medical_school, &Address::generate(&env), "surgery-101"), 1);
+
+    let rejected = client.try_issue_credential(
+        &bootcamp,
+        &Address::generate(&env),
+        &String::from_str(&env, "Surgery"),
+        &String::from_str(&env, "Unaccredited"),
+        &course_id,
+        &String::from_str(&env, "ipfs://Qm..."),
+    );
+    assert_eq!(rejected, Err(Ok(CredentialError::NotCourseIssuer.into())));
+
+    // Other courses still use the global issuer set
+    issue(&env, &client, &bootcamp, &Address::generate(&env), "course-001");
+}
+
+#[test]
+fn test_reissue_and_lineage() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let original = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    let renewed = client.reissue_credential(&admin, &original, &2_000, &String::from_str(&env, "ipfs://renewed"));
+    let renewed_again = client.reissue_credential(&admin, &renewed, &3_000, &String::from_str(&env, "ipfs://renewed-2"));
+
+    let latest = client.get_credential(&renewed_again);
+    assert_eq!(latest.supersedes, renewed);
+    assert_eq!(latest.recipient, recipient);
+    assert_eq!(latest.title, String::from_str(&env, "Safety Certification"));
+    assert_eq!(latest.expires_at, 3_000);
+
+    assert_eq!(client.verify_credential_detailed(&original), VerificationStatus::Superseded);
+    assert_eq!(client.verify_credential_detailed(&renewed), VerificationStatus::Superseded);
+    assert_eq!(client.verify_credential_detailed(&renewed_again), VerificationStatus::Valid);
+
+    // The walk covers the whole chain from any member
+    let lineage = vec![&env, original, renewed, renewed_again];
+    assert_eq!(client.get_credential_lineage(&renewed_again), lineage);
+    assert_eq!(client.get_credential_lineage(&original), lineage);
+}
+
+#[test]
+fn test_reissue_carries_credential_settings() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let text = |s: &str| String::from_str(&env, s);
+    let fall = text("Fall 2024");
+
+    let original = client.issue_credential_with_xp(
+        &admin, &recipient, &text("Rust on Stellar"), &text("Completed Soroban basics"), &text("course-001"), &text("QmHash"), &50,
+    );
+    client.assign_cohort(&admin, &original, &fall);
+    client.set_metadata(&admin, &original, &text("campus"), &text("north"));
+    client.set_credential_private(&original, &recipient, &true);
+
+    let renewed = client.reissue_credential(&admin, &original, &0, &text("ipfs://renewed"));
+    let copy = client.get_credential(&renewed);
+    assert_eq!(copy.cohort_id, fall);
+    assert_eq!(copy.metadata.get(text("campus")), Some(text("north")));
+    assert!(copy.is_private);
+    assert_eq!(client.get_cohort_credentials(&fall), vec![&env, original, renewed]);
+    // XP moves to the copy rather than counting twice
+    assert_eq!((copy.xp, client.get_credential(&original).xp), (50, 0));
+    assert_eq!(client.get_user_xp(&recipient), 50);
+
+    let sealed = client.issue_encrypted_credential(
+        &admin, &recipient, &text("Transcript"), &text("Sealed"), &text("course-002"), &text("QmSealed"), &text("registrar"),
+    );
+    let resealed = client.get_credential(&client.reissue_credential(&admin, &sealed, &0, &text("ipfs://resealed")));
+    assert!(resealed.encrypted);
+    assert_eq!(resealed.encryption_pubkey_hint, text("registrar"));
+    assert!(resealed.display_id.is_empty());
+}
+
+#[test]
+fn test_peek_next_id() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    assert_eq!(client.peek_next_id(), 1);
+    issue(&env, &client, &admin, &recipient, "course-001");
+
+    let peeked = client.peek_next_id();
+    assert_eq!(client.peek_next_id(), peeked);
+    assert_eq!(issue(&env, &client, &admin, &recipient, "course-002"), peeked);
+}
+
+#[test]
+fn test_revocations_by_reason() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let forged = issue(&env, &client, &admin, &recipient, "course-001");
+    let typo = issue(&env, &client, &admin, &recipient, "course-002");
+    let also_forged = issue(&env, &client, &admin, &recipient, "course-003");
+
+    client.revoke_credential(&forged, &admin, &RevocationReason::Fraud, &Some(String::from_str(&env, "Fake transcript")));
+    client.revoke_credential(&typo, &admin, &RevocationReason::IssuanceError, &None);
+    client.revoke_credential(&also_forged, &admin, &RevocationReason::Fraud, &None);
+
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::Fraud), vec![&env, forged, also_forged]);
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::IssuanceError), vec![&env, typo]);
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::PolicyViolation).len(), 0);
+
+    let (_, _, data) = env.events().all().last().unwrap();
+    let (logged_id, _, logged_reason, _): (u64, Address, RevocationReason, String) = data.into_val(&env);
+    assert_eq!(logged_id, also_forged);
+    assert_eq!(logged_reason, RevocationReason::Fraud);
+}
+
+#[test]
+fn test_admin_transfer_needs_two_of_three_approvals() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let signers = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    client.set_admin_signers(&admin, &signers, &2);
+
+    let new_admin = Address::generate(&env);
+    let action_hash = client.transfer_admin_action_hash(&new_admin);
+
+    assert_eq!(client.approve_action(&signers.get(0).unwrap(), &action_hash), 1);
+    assert!(client.try_transfer_admin(&new_admin).is_err());
+
+    assert_eq!(client.approve_action(&signers.get(2).unwrap(), &action_hash), 2);
+    client.transfer_admin(&new_admin);
+
+    // The new admin holds the single-admin powers; the old one lost them
+    client.set_grace_period(&new_admin, &60);
+    assert!(client.try_set_grace_period(&admin, &60).is_err());
+    assert_eq!(client.get_action_approvals(&action_hash).len(), 0);
+}
+
+#[test]
+fn test_replacing_admin_signers_needs_current_threshold() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let signers = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    client.set_admin_signers(&admin, &signers, &2);
+
+    // The admin alone can no longer swap in a set of its own choosing
+    let puppet = vec![&env, Address::generate(&env)];
+    assert!(client.try_set_admin_signers(&admin, &puppet, &1).is_err());
+
+    let action_hash = client.set_admin_signers_action_hash(&puppet, &1);
+    client.approve_action(&signers.get(0).unwrap(), &action_hash);
+    client.approve_action(&signers.get(1).unwrap(), &action_hash);
+    client.set_admin_signers(&admin, &puppet, &1);
+    assert_eq!(client.get_admin_signers(), (puppet, 1));
+}
+
+#[test]
+fn test_promote_anchor_keeps_original_timestamp() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let content = BytesN::from_array(&env, &[9u8; 32]);
+    let anchor_id = client.anchor_document(&admin, &content);
+    assert_eq!(client.get_anchor(&anchor_id).anchored_at, 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 50_000);
+    let cred_id = client.promote_anchor_to_credential(
+        &anchor_id,
+        &recipient,
+        &String::from_str(&env, "Thesis Defense"),
+        &String::from_str(&env, "Submitted before review"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "ipfs://Qm..."),
+    );
+
+    assert_eq!(client.get_credential(&cred_id).completion_date, 1_000);
+    assert_eq!(client.get_anchor(&anchor_id).credential_id, cred_id);
+    assert!(client.verify_credential(&cred_id));
+}
+
+#[test]
+fn test_get_purgeable_credentials() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    client.set_grace_period(&admin, &500);
+
+    let long_expired = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    let valid = issue(&env, &client, &admin, &recipient, "course-002");
+    let in_grace = issue_expiring(&env, &client, &admin, &recipient, 1_800);
+    let also_long_expired = issue_expiring(&env, &client, &admin, &recipient, 1_200);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+    assert_eq!(client.get_purgeable_credentials(&10), vec![&env, long_expired, also_long_expired]);
+    assert_eq!(client.get_purgeable_credentials(&1), vec![&env, long_expired]);
+
+    // Purged ones drop out of the preview
+    client.purge_credential(&long_expired, &admin);
+    assert_eq!(client.get_purgeable_credentials(&10), vec![&env, also_long_expired]);
+    assert!(client.verify_credential(&valid));
+    assert!(client.verify_credential(&in_grace));
+}
+
+#[test]
+fn test_operator_issues_for_institution() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let university = Address::generate(&env);
+    let registrar = Address::generate(&env);
+    client.add_issuer(&admin, &university);
+    client.add_operator(&university, &registrar);
+
+    let issue_as = || {
+        client.try_issue_as(
+            &registrar,
+            &university,
+            &Address::generate(&env),
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+        )
+    };
+
+    let cred_id = issue_as().unwrap().unwrap();
+    assert_eq!(client.get_credential(&cred_id).issuer, university);
+
+    client.remove_operator(&university, &registrar);
+    assert!(issue_as().is_err());
+}
+
+#[test]
+fn test_verification_notes() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let cred_id = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+    let employer = Address::generate(&env);
+    let agency = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let confirmed = String::from_str(&env, "Confirmed with registrar");
+    client.add_verification_note(&cred_id, &employer, &confirmed);
+    client.add_verification_note(&cred_id, &agency, &String::from_str(&env, "Background check passed"));
+
+    let notes = client.get_verification_notes_paged(&cred_id, &0, &10);
+    assert_eq!(notes.len(), 2);
+    let first = notes.get(0).unwrap();
+    assert_eq!((first.verifier, first.note, first.created_at), (employer.clone(), confirmed, 1_000));
+
+    let second_page = client.get_verification_notes_paged(&cred_id, &1, &10);
+    assert_eq!(second_page.get(0).unwrap().verifier, agency);
+    assert_eq!(client.get_verification_notes_paged(&cred_id, &2, &10).len(), 0);
+
+    // One note per verifier
+    assert!(client.try_add_verification_note(&cred_id, &employer, &String::from_str(&env, "Again")).is_err());
+}
+
+#[test]
+fn test_export_issuer_credentials_round_trips() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let university = Address::generate(&env);
+    client.add_issuer(&admin, &university);
+
+    let mut issued = Vec::new(&env);
+    for _ in 0..3 {
+        issued.push_back(issue(&env, &client, &university, &Address::generate(&env), "course-001"));
+    }
+    issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+
+    let blob = client.export_issuer_credentials(&university, &None);
+    let envelope = ExportEnvelope::from_xdr(&env, &blob).unwrap();
+
+    assert_eq!(envelope.version, EXPORT_FORMAT_VERSION);
+    assert_eq!(envelope.issuer, university);
+    assert_eq!(envelope.next_after, 0);
+    assert_eq!(envelope.credentials.len(), 3);
+    for (exported, credential_id) in envelope.credentials.iter().zip(issued.iter()) {
+        assert_eq!(exported, client.get_credential(&credential_id));
+    }
+}
+
+#[test]
+fn test_get_active_user_credentials() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let active = issue(&env, &client, &admin, &recipient, "course-001");
+    let revoked = issue(&env, &client, &admin, &recipient, "course-002");
+    let expired = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+    assert_eq!(client.get_active_user_credentials(&recipient, &false), vec![&env, active, expired]);
+    assert_eq!(client.get_active_user_credentials(&recipient, &true), vec![&env, active]);
+}
+
+#[test]
+fn test_batch_duplicate_recipients() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let recipients = vec![&env, alice.clone(), bob.clone(), alice.clone()];
+
+    let issue_batch = || {
+        client.try_issue_credentials_batch(
+            &admin,
+            &recipients,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+        )
+    };
+
+    // Off by default: duplicates are issued as given
+    assert_eq!(issue_batch(), Ok(Ok(vec![&env, 1, 2, 3])));
+    assert_eq!(client.get_user_credentials(&alice), vec![&env, 1, 3]);
+
+    client.set_feature_flags(&admin, &FeatureFlags { dedupe_recipients: true, ..client.get_feature_flags() });
+    assert_eq!(issue_batch(), Err(Ok(CredentialError::DuplicateRecipient.into())));
+    assert_eq!(client.get_credential_count(), 3);
+}
+
+#[test]
+fn test_completion_date_bounds() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    client.set_completion_date_bounds(&admin, &5_000, &60);
+
+    let issue_dated = |completion_date: u64| {
+        client.try_issue_credential_with_date(
+            &admin,
+            &Address::generate(&env),
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+            &completion_date,
+        )
+    };
+
+    assert_eq!(issue_dated(4_999), Err(Ok(CredentialError::InvalidCompletionDate.into())));
+    assert_eq!(issue_dated(10_061), Err(Ok(CredentialError::InvalidCompletionDate.into())));
+
+    let cred_id = issue_dated(7_500).unwrap().unwrap();
+    assert_eq!(client.get_credential(&cred_id).completion_date, 7_500);
+    assert!(issue_dated(10_060).is_ok());
+}
+
+#[test]
+fn test_contract_status() {
+    let env = Env::default();
+    let fresh = StarkEdContractClient::new(&env, &env.register_contract(None, StarkEdContract));
+
+    let status = fresh.get_contract_status();
+    assert!(!status.initialized);
+    assert_eq!(status.admin, None);
+    assert_eq!(status.credential_count, 0);
+    assert_eq!(status.version, CONTRACT_VERSION);
+
+    let (client, admin) = setup(&env);
+    issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+    client.set_paused(&admin, &true);
+
+    let status = client.get_contract_status();
+    assert!(status.initialized);
+    assert!(status.paused);
+    assert_eq!(status.admin, Some(admin));
+    assert_eq!(status.credential_count, 1);
+}
+
+#[test]
+fn test_issuer_self_revoke() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let university = Address::generate(&env);
+    let bootcamp = Address::generate(&env);
+    client.add_issuer(&admin, &university);
+    client.add_issuer(&admin, &bootcamp);
+
+    let own = issue(&env, &client, &university, &Address::generate(&env), "course-001");
+    let other = issue(&env, &client, &bootcamp, &Address::generate(&env), "course-001");
+
+    // Disabled by default
+    assert!(client.try_revoke_credential(&own, &university, &RevocationReason::IssuanceError, &None).is_err());
+
+    client.set_feature_flags(&admin, &FeatureFlags { allow_issuer_self_revoke: true, ..client.get_feature_flags() });
+    client.revoke_credential(&own, &university, &RevocationReason::IssuanceError, &None);
+    assert!(!client.verify_credential(&own));
+
+    assert_eq!(
+        client.try_revoke_credential(&other, &university, &RevocationReason::Fraud, &None),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+    client.revoke_credential(&other, &admin, &RevocationReason::Fraud, &None);
+    assert!(!client.verify_credential(&other));
+}
+
+#[test]
+fn test_max_credentials_per_user() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let victim = Address::generate(&env);
+    client.set_max_credentials_per_user(&admin, &2);
+
+    issue(&env, &client, &admin, &victim, "course-001");
+    issue(&env, &client, &admin, &victim, "course-002");
+
+    let over_cap = client.try_issue_credential(
+        &admin,
+        &victim,
+        &String::from_str(&env, "Junk"),
+        &String::from_str(&env, "Spam"),
+        &String::from_str(&env, "course-003"),
+        &String::from_str(&env, "ipfs://Qm..."),
+    );
+    assert_eq!(over_cap, Err(Ok(CredentialError::UserLimitReached.into())));
+
+    // The cap is per recipient
+    issue(&env, &client, &admin, &Address::generate(&env), "course-003");
+}
+
+#[test]
+fn test_get_common_courses() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    issue(&env, &client, &admin, &alice, "course-001");
+    issue(&env, &client, &admin, &alice, "course-002");
+    let revoked = issue(&env, &client, &admin, &alice, "course-003");
+    issue(&env, &client, &admin, &bob, "course-002");
+    issue(&env, &client, &admin, &bob, "course-003");
+    issue(&env, &client, &admin, &bob, "course-001");
+    issue(&env, &client, &admin, &bob, "course-004");
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+
+    let common = client.get_common_courses(&alice, &bob);
+    assert_eq!(common, vec![&env, String::from_str(&env, "course-001"), String::from_str(&env, "course-002")]);
+}
+
+#[test]
+fn test_set_metadata_batch() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let university = Address::generate(&env);
+    client.add_issuer(&admin, &university);
+
+    let mut cohort = Vec::new(&env);
+    for _ in 0..3 {
+        cohort.push_back(issue(&env, &client, &university, &Address::generate(&env), "course-001"));
+    }
+    let foreign = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+
+    let key = String::from_str(&env, "semester");
+    let value = String::from_str(&env, "2024-fall");
+    client.set_metadata_batch(&university, &cohort, &key, &value);
+    for credential_id in cohort.iter() {
+        assert_eq!(client.get_metadata(&credential_id, &key), Some(value.clone()));
+    }
+
+    // One credential the caller didn't issue rejects the whole batch
+    let mut mixed = cohort.clone();
+    mixed.push_back(foreign);
+    let other_value = String::from_str(&env, "2025-spring");
+    assert_eq!(
+        client.try_set_metadata_batch(&university, &mixed, &key, &other_value),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+    assert_eq!(client.get_metadata(&cohort.get(0).unwrap(), &key), Some(value));
+    assert_eq!(client.get_metadata(&foreign, &key), None);
+}
+
+#[test]
+fn test_sweep_expired() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let expired = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    let valid = issue(&env, &client, &admin, &recipient, "course-002");
+    let not_yet = issue_expiring(&env, &client, &admin, &recipient, 5_000);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+    assert_eq!(client.sweep_expired(&vec![&env, expired, valid, not_yet, 99]), 1);
+    assert_eq!(client.get_expired_count(), 1);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (DEFAULT_EVENT_NAMESPACE, Symbol::new(&env, "cred"), Symbol::new(&env, "expired")).into_val(&env));
+    let swept_id: u64 = data.into_val(&env);
+    assert_eq!(swept_id, expired);
+
+    // Sweeping again doesn't double count
+    assert_eq!(client.sweep_expired(&vec![&env, expired]), 0);
+    assert_eq!(client.get_expired_count(), 1);
+}
+
+#[test]
+fn test_export_for_migration() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let next_version = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    let fingerprint = client.credential_fingerprint(&cred_id);
+    client.set_migration_target(&admin, &next_version);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    let record = client.export_for_migration(&cred_id, &admin);
+
+    assert_eq!(record.credential.id, cred_id);
+    assert_eq!(record.credential.completion_date, 1_000);
+    assert_eq!(record.fingerprint, fingerprint);
+    assert_eq!(record.source_contract, client.address);
+    assert_eq!(record.target_contract, next_version);
+    assert_eq!(record.exported_at, 2_000);
+
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Migrated);
+    assert!(!client.verify_credential(&cred_id));
+    assert!(client.try_export_for_migration(&cred_id, &admin).is_err());
+}
+
+#[test]
+fn test_get_user_credentials_by_status() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let valid = issue(&env, &client, &admin, &recipient, "course-001");
+    let revoked = issue(&env, &client, &admin, &recipient, "course-002");
+    let expired = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    let also_valid = issue(&env, &client, &admin, &recipient, "course-003");
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+    assert_eq!(client.get_user_credentials_by_status(&recipient, &VerificationStatus::Valid), vec![&env, valid, also_valid]);
+    assert_eq!(client.get_user_credentials_by_status(&recipient, &VerificationStatus::Revoked), vec![&env, revoked]);
+    assert_eq!(client.get_user_credentials_by_status(&recipient, &VerificationStatus::Expired), vec![&env, expired]);
+}
+
+mod verifier_hook {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    /// Answers with whatever `set_answer` stored; no answer at all makes it trap
+    #[contract]
+    pub struct MockHook;
+
+    #[contractimpl]
+    impl MockHook {
+        pub fn set_answer(env: Env, answer: bool) {
+            env.storage().instance().set(&symbol_short!("answer"), &answer);
+        }
+
+        pub fn check_credential(env: Env, _credential_id: u64, _recipient: Address) -> bool {
+            env.storage().instance()
+                .get(&symbol_short!("answer"))
+                .unwrap_or_else(|| panic!("registry unavailable"))
+        }
+    }
+}
+
+// A credential issued from a fresh "Nursing License" template; returns (template id, credential id)
+fn issue_licensed(env: &Env, client: &StarkEdContractClient, issuer: &Address) -> (u64, u64) {
+    let text = |s: &str| String::from_str(env, s);
+    let template_id = client.create_template(issuer, &text("Nursing License"), &text("State board"), &text("course-001"), &0);
+    let credential_id = client.issue_from_template(issuer, &template_id, &Address::generate(env), &text("ipfs://Qm..."));
+    (template_id, credential_id)
+}
+
+#[test]
+fn test_verifier_hook() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let (template_id, license) = issue_licensed(&env, &client, &admin);
+    let badge = issue_typed(&env, &client, &admin, &Address::generate(&env), CredentialType::Badge);
+
+    let hook_id = env.register_contract(None, verifier_hook::MockHook);
+    let hook = verifier_hook::MockHookClient::new(&env, &hook_id);
+    client.set_verifier_hook(&admin, &template_id, &hook_id);
+    assert_eq!(client.get_verifier_hook(&template_id), Some(hook_id.clone()));
+
+    hook.set_answer(&true);
+    assert_eq!(client.verify_credential_detailed(&license), VerificationStatus::Valid);
+
+    hook.set_answer(&false);
+    assert_eq!(client.verify_credential_detailed(&license), VerificationStatus::Invalid);
+    assert!(!client.verify_credential(&license));
+
+    // Hooks are scoped to the template, and only its issuer may set one
+    assert_eq!(client.verify_credential_detailed(&badge), VerificationStatus::Valid);
+    let (_, other_license) = issue_licensed(&env, &client, &admin);
+    assert_eq!(client.verify_credential_detailed(&other_license), VerificationStatus::Valid);
+    let stranger = Address::generate(&env);
+    client.add_issuer(&admin, &stranger);
+    assert_eq!(
+        client.try_set_verifier_hook(&stranger, &template_id, &hook_id),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+
+    client.remove_verifier_hook(&admin, &template_id);
+    assert_eq!(client.verify_credential_detailed(&license), VerificationStatus::Valid);
+}
+
+#[test]
+fn test_verifier_hook_failure_mode() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let (template_id, license) = issue_licensed(&env, &client, &admin);
+
+    // Never given an answer, so every check traps
+    let hook_id = env.register_contract(None, verifier_hook::MockHook);
+    client.set_verifier_hook(&admin, &template_id, &hook_id);
+
+    assert_eq!(client.verify_credential_detailed(&license), VerificationStatus::Invalid);
+    client.set_hook_failure_mode(&admin, &HookFailureMode::Skip);
+    assert_eq!(client.verify_credential_detailed(&license), VerificationStatus::Valid);
+}
+
+#[test]
+fn test_get_user_credentials_sorted() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    for course_id in ["course-001", "course-002", "course-003"] {
+        issue(&env, &client, &admin, &recipient, course_id);
+    }
+
+    // Simulate a list that was appended out of order (e.g. by a transfer)
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(&CredentialKey::UserCredentials(recipient.clone()), &vec![&env, 3u64, 1, 2]);
+    });
+
+    assert_eq!(client.get_user_credentials(&recipient), vec![&env, 3, 1, 2]);
+    assert_eq!(client.get_user_credentials_sorted(&recipient), vec![&env, 1, 2, 3]);
+}
+
+#[test]
+fn test_blocked_recipients() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let burn_address = Address::generate(&env);
+
+    let issue_to = |recipient: &Address| {
+        client.try_issue_credential(
+            &admin,
+            recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+        )
+    };
+
+    assert_eq!(issue_to(&client.address), Err(Ok(CredentialError::InvalidRecipient.into())));
+
+    client.block_recipient(&admin, &burn_address);
+    assert_eq!(issue_to(&burn_address), Err(Ok(CredentialError::InvalidRecipient.into())));
+
+    client.unblock_recipient(&admin, &burn_address);
+    assert!(issue_to(&burn_address).is_ok());
+}
+
+#[test]
+fn test_search_by_title_prefix() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let issue_titled = |title: &str| {
+        client.issue_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, title),
+            &String::from_str(&env, "Completed"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+        )
+    };
+    let basics = issue_titled("Rust on Stellar");
+    let advanced = issue_titled("RUST Advanced");
+    issue_titled("Intro to Rust");
+    issue_titled("Ru");
+
+    let prefix = String::from_str(&env, "rust");
+    assert_eq!(client.search_by_title_prefix(&recipient, &prefix), vec![&env, basics, advanced]);
+    assert_eq!(client.search_by_title_prefix(&recipient, &String::from_str(&env, "Go")).len(), 0);
+}
+
+#[test]
+fn test_issue_credential_full() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    client.set_feature_flags(&admin, &FeatureFlags { sanitize_text: true, ..client.get_feature_flags() });
+    client.set_display_id_template(&admin, &String::from_str(&env, "{course}-{seq}"));
+
+    let credential = client.issue_credential_full(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "  Rust   on Stellar "),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "ipfs://Qm..."),
+    );
+
+    // The returned value is what was stored, after sanitizing and display-id assignment
+    assert_eq!(credential.id, 1);
+    assert_eq!(credential.title, String::from_str(&env, "Rust on Stellar"));
+    assert!(!credential.display_id.is_empty());
+    assert_eq!(credential, client.get_credential(&credential.id));
+}
+
+#[test]
+fn test_max_batch_size() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.get_max_batch_size(), 100);
+    client.set_max_batch_size(&admin, &3);
+
+    let issue_batch = |size: u32| {
+        let mut recipients = Vec::new(&env);
+        for _ in 0..size {
+            recipients.push_back(Address::generate(&env));
+        }
+        client.try_issue_credentials_batch(
+            &admin,
+            &recipients,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+        )
+    };
+
+    assert_eq!(issue_batch(4), Err(Ok(CredentialError::BatchTooLarge.into())));
+    assert_eq!(client.get_credential_count(), 0);
+
+    assert!(issue_batch(3).is_ok());
+    assert_eq!(client.get_credential_count(), 3);
+}
+
+#[test]
+fn test_pinned_credentials() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..(MAX_PINNED_CREDENTIALS + 1) {
+        ids.push_back(issue(&env, &client, &admin, &recipient, "course-001"));
+    }
+    let someone_elses = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+
+    client.pin_credential(&recipient, &ids.get(3).unwrap());
+    client.pin_credential(&recipient, &ids.get(1).unwrap());
+    assert_eq!(client.get_pinned_credentials(&recipient), vec![&env, ids.get(3).unwrap(), ids.get(1).unwrap()]);
+
+    assert_eq!(
+        client.try_pin_credential(&recipient, &someone_elses),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+
+    for index in [0, 2, 4] {
+        client.pin_credential(&recipient, &ids.get(index).unwrap());
+    }
+    assert!(client.try_pin_credential(&recipient, &ids.get(5).unwrap()).is_err());
+
+    client.unpin_credential(&recipient, &ids.get(3).unwrap());
+    assert_eq!(client.get_pinned_credentials(&recipient).len(), MAX_PINNED_CREDENTIALS - 1);
+    assert_eq!(client.get_pinned_credentials(&recipient).get(0).unwrap(), ids.get(1).unwrap());
+}
+
+fn issue_dependent(env: &Env, client: &StarkEdContractClient, admin: &Address, recipient: &Address, prerequisite_id: u64) -> u64 {
+    client.issue_dependent_credential(
+        admin,
+        recipient,
+        &String::from_str(env, "Rust Advanced"),
+        &String::from_str(env, "Builds on Rust on Stellar"),
+        &String::from_str(env, "course-002"),
+        &String::from_str(env, "ipfs://Qm..."),
+        &prerequisite_id,
+        &None,
+    )
+}
+
+#[test]
+fn test_cascade_revoke_to_dependents() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let prerequisite = issue(&env, &client, &admin, &recipient, "course-001");
+    let dependent = issue_dependent(&env, &client, &admin, &recipient, prerequisite);
+    let second_level = issue_dependent(&env, &client, &admin, &recipient, dependent);
+    assert_eq!(client.get_dependents(&prerequisite), vec![&env, dependent]);
+
+    client.set_cascade_mode(&admin, &CascadeMode::Revoke);
+    client.revoke_credential(&prerequisite, &admin, &RevocationReason::Fraud, &None);
+
+    assert!(!client.verify_credential(&dependent));
+    assert!(!client.verify_credential(&second_level));
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::Fraud), vec![&env, prerequisite, dependent, second_level]);
+}
+
+#[test]
+fn test_cascade_flags_dependents_for_review() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let prerequisite = issue(&env, &client, &admin, &recipient, "course-001");
+    let dependent = issue_dependent(&env, &client, &admin, &recipient, prerequisite);
+
+    client.set_cascade_mode(&admin, &CascadeMode::FlagForReview);
+    client.revoke_credential(&prerequisite, &admin, &RevocationReason::Fraud, &None);
+
+    assert!(client.verify_credential(&dependent));
+    assert_eq!(client.get_review_flag(&dependent), Some(prerequisite));
+
+    client.clear_review_flag(&admin, &dependent);
+    assert_eq!(client.get_review_flag(&dependent), None);
+}
+
+#[test]
+fn test_issuance_stats_by_day() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let day = 86_400;
+
+    env.ledger().with_mut(|li| li.timestamp = 10 * day + 5);
+    issue(&env, &client, &admin, &recipient, "course-001");
+    issue(&env, &client, &admin, &recipient, "course-002");
+
+    env.ledger().with_mut(|li| li.timestamp = 12 * day + 100);
+    issue(&env, &client, &admin, &recipient, "course-003");
+
+    assert_eq!(client.get_issuance_stats(&10, &12), vec![&env, (10, 2), (11, 0), (12, 1)]);
+    assert!(client.try_get_issuance_stats(&12, &10).is_err());
+}
+
+#[test]
+fn test_paid_renewal() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&recipient, &1_000);
+    let token = token::Client::new(&env, &token_id);
+
+    client.set_renewal_config(&admin, &RenewalConfig { token: token_id.clone(), renewal_period: 10_000, allow_lapsed: false });
+    let soon_to_expire = issue_expiring(&env, &client, &admin, &recipient, 5_000);
+    let lapsed = issue_expiring(&env, &client, &admin, &recipient, 1_000);
+    client.set_renewal_fee(&admin, &soon_to_expire, &250);
+    client.set_renewal_fee(&admin, &lapsed, &250);
+
+    env.ledger().with_mut(|li| li.timestamp = 4_000);
+    assert_eq!(client.renew(&soon_to_expire, &recipient), 15_000);
+    assert_eq!(client.get_credential(&soon_to_expire).expires_at, 15_000);
+    assert_eq!(token.balance(&recipient), 750);
+    assert_eq!(token.balance(&admin), 250);
+
+    // Lapsed beyond grace: forbidden unless the config allows it
+    assert!(client.try_renew(&lapsed, &recipient).is_err());
+    client.set_renewal_config(&admin, &RenewalConfig { token: token_id, renewal_period: 10_000, allow_lapsed: true });
+    assert_eq!(client.renew(&lapsed, &recipient), 14_000);
+    assert_eq!(client.verify_credential_detailed(&lapsed), VerificationStatus::Valid);
+}
+
+#[test]
+fn test_find_credential_returns_option() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    assert_eq!(client.find_credential(&id), Some(client.get_credential(&id)));
+    assert_eq!(client.find_credential(&(id + 100)), None);
+}
+
+#[test]
+fn test_extend_expiry_signed() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use soroban_sdk::xdr::ToXdr;
+
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue_expiring(&env, &client, &admin, &recipient, 5_000);
+
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let pubkey = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    client.set_issuer_signing_key(&admin, &pubkey);
+
+    let sign = |contract: &Address, new_expiry: u64, nonce: u64| {
+        let message = (contract.clone(), id, new_expiry, nonce).to_xdr(&env);
+        let mut buf = [0u8; 256];
+        let len = message.len() as usize;
+        message.copy_into_slice(&mut buf[..len]);
+        BytesN::from_array(&env, &key.sign(&buf[..len]).to_bytes())
+    };
+
+    // Signature over a different expiry, or for another contract, does not authorize this one
+    assert!(client.try_extend_expiry_signed(&id, &20_000, &0, &sign(&client.address, 30_000, 0), &pubkey).is_err());
+    let elsewhere = Address::generate(&env);
+    assert!(client.try_extend_expiry_signed(&id, &20_000, &0, &sign(&elsewhere, 20_000, 0), &pubkey).is_err());
+    assert_eq!(client.get_credential(&id).expires_at, 5_000);
+
+    assert_eq!(client.get_extension_nonce(&id), 0);
+    client.extend_expiry_signed(&id, &20_000, &0, &sign(&client.address, 20_000, 0), &pubkey);
+    assert_eq!(client.get_credential(&id).expires_at, 20_000);
+    assert_eq!(client.get_extension_nonce(&id), 1);
+
+    // Shortening the expiry again doesn't let the old signature be replayed
+    let updates = CredentialUpdate { title: None, description: None, ipfs_hash: None, expires_at: Some(6_000) };
+    client.update_credential_fields(&id, &updates, &admin);
+    assert!(client.try_extend_expiry_signed(&id, &20_000, &0, &sign(&client.address, 20_000, 0), &pubkey).is_err());
+    client.extend_expiry_signed(&id, &20_000, &1, &sign(&client.address, 20_000, 1), &pubkey);
+    assert_eq!(client.get_credential(&id).expires_at, 20_000);
+}
+
+#[test]
+fn test_cohort_spans_courses_and_revokes_together() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let fall = String::from_str(&env, "Fall 2024");
+
+    let a = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+    let b = issue(&env, &client, &admin, &Address::generate(&env), "course-002");
+    let outsider = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+    client.assign_cohort(&admin, &a, &fall);
+    client.assign_cohort(&admin, &b, &fall);
+
+    assert_eq!(client.get_cohort_credentials(&fall), vec![&env, a, b]);
+    assert_eq!(client.get_credential(&b).cohort_id, fall);
+
+    assert_eq!(client.revoke_cohort(&fall, &admin, &RevocationReason::PolicyViolation), 2);
+    assert!(client.get_credential(&a).is_revoked);
+    assert!(client.get_credential(&b).is_revoked);
+    assert!(!client.get_credential(&outsider).is_revoked);
+    // Already-revoked members are skipped on a repeat call
+    assert_eq!(client.revoke_cohort(&fall, &admin, &RevocationReason::PolicyViolation), 0);
+}
+
+#[test]
+fn test_check_revoked_batch_positions() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let a = issue(&env, &client, &admin, &recipient, "course-001");
+    let b = issue(&env, &client, &admin, &recipient, "course-002");
+    let c = issue(&env, &client, &admin, &recipient, "course-003");
+    client.revoke_credential(&b, &admin, &RevocationReason::Other, &None);
+
+    assert_eq!(
+        client.check_revoked_batch(&vec![&env, a, b, c, 999]),
+        vec![&env, false, true, false, false]
+    );
+}
+
+#[test]
+fn test_event_namespace_leads_topics() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+    assert_eq!(client.get_event_namespace(), DEFAULT_EVENT_NAMESPACE);
+
+    let campus = Symbol::new(&env, "campus_a");
+    client.set_event_namespace(&admin, &campus);
+    client.revoke_credential(&id, &admin, &RevocationReason::Other, &None);
+
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(topics, (campus, Symbol::new(&env, "cred"), Symbol::new(&env, "revoked")).into_val(&env));
+}
+
+#[test]
+fn test_update_credential_fields_partial() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+    let before = client.get_credential(&id);
+
+    let updates = CredentialUpdate {
+        title: None,
+        description: Some(String::from_str(&env, "Revised syllabus")),
+        ipfs_hash: None,
+        expires_at: None,
+    };
+    client.update_credential_fields(&id, &updates, &admin);
+
+    let after = client.get_credential(&id);
+    assert_eq!(after.description, String::from_str(&env, "Revised syllabus"));
+    assert_eq!(Credential { description: before.description.clone(), ..after.clone() }, before);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_update_credential_fields(&id, &updates, &stranger),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+}
+
+#[test]
+fn test_strict_verification_flags_removed_issuer() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+    let id = issue(&env, &client, &issuer, &recipient, "course-001");
+    assert_eq!(client.verify_credential_strict(&id), VerificationStatus::Valid);
+
+    client.remove_issuer(&admin, &issuer);
+    assert_eq!(client.verify_credential_strict(&id), VerificationStatus::IssuerRevoked);
+    assert_eq!(client.verify_credential_detailed(&id), VerificationStatus::Valid);
+    assert!(client.verify_credential(&id));
+
+    client.set_feature_flags(&admin, &FeatureFlags { strict_issuer_invalidates: false, ..client.get_feature_flags() });
+    assert_eq!(client.verify_credential_strict(&id), VerificationStatus::Valid);
+}
+
+#[test]
+fn test_get_all_credential_ids_pages_without_gaps() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let mut issued = Vec::new(&env);
+    for _ in 0..4 {
+        issued.push_back(issue(&env, &client, &admin, &recipient, "course-001"));
+    }
+    let purged = issue_expiring(&env, &client, &admin, &recipient, 100);
+    issued.push_back(issue(&env, &client, &admin, &recipient, "course-001"));
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.purge_credential(&purged, &admin);
+
+    let mut walked = Vec::new(&env);
+    let mut start = 0;
+    loop {
+        let page = client.get_all_credential_ids(&start, &2);
+        if page.is_empty() {
+            break;
+        }
+        start = page.last().unwrap() + 1;
+        walked.append(&page);
+    }
+    assert_eq!(walked, issued);
+}
+
+#[test]
+fn test_user_xp_tracks_issuance_and_revocation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let issue_xp = |course_id: &str, xp: u32| {
+        client.issue_credential_with_xp(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed the course"),
+            &String::from_str(&env, course_id),
+            &String::from_str(&env, "QmHash"),
+            &xp,
+        )
+    };
+
+    let first = issue_xp("course-001", 150);
+    issue_xp("course-002", 50);
+    issue(&env, &client, &admin, &recipient, "course-003");
+    assert_eq!(client.get_user_xp(&recipient), 200);
+
+    client.revoke_credential(&first, &admin, &RevocationReason::Other, &None);
+    assert_eq!(client.get_user_xp(&recipient), 50);
+
+    client.reinstate_credential(&first, &admin);
+    assert_eq!(client.get_user_xp(&recipient), 200);
+    assert_eq!(client.get_user_xp(&Address::generate(&env)), 0);
+
+    // Purging takes the XP back too, but only once for a revoked credential
+    let second = issue_xp("course-004", 30);
+    let updates = CredentialUpdate { title: None, description: None, ipfs_hash: None, expires_at: Some(1_000) };
+    client.update_credential_fields(&first, &updates, &admin);
+    client.update_credential_fields(&second, &updates, &admin);
+    client.revoke_credential(&second, &admin, &RevocationReason::Other, &None);
+    assert_eq!(client.get_user_xp(&recipient), 200);
+    env.ledger().set_timestamp(2_000);
+    client.purge_credential(&first, &admin);
+    client.purge_credential(&second, &admin);
+    assert_eq!(client.get_user_xp(&recipient), 50);
+}
+
+#[test]
+fn test_top_users_ordering_and_cap() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let award = |recipient: &Address, xp: u32| {
+        client.issue_credential_with_xp(
+            &admin,
+            recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed the course"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "QmHash"),
+            &xp,
+        )
+    };
+
+    let low = Address::generate(&env);
+    let mid = Address::generate(&env);
+    let high = Address::generate(&env);
+    award(&low, 10);
+    award(&mid, 50);
+    let high_cred = award(&high, 40);
+    award(&high, 40);
+
+    assert_eq!(
+        client.get_top_users(&10),
+        vec![&env, (high.clone(), 80), (mid.clone(), 50), (low.clone(), 10)]
+    );
+    assert_eq!(client.get_top_users(&2), vec![&env, (high.clone(), 80), (mid.clone(), 50)]);
+
+    client.revoke_credential(&high_cred, &admin, &RevocationReason::Other, &None);
+    assert_eq!(client.get_top_users(&1), vec![&env, (mid, 50)]);
+
+    // The board never holds more than LEADERBOARD_SIZE users
+    for xp in 1..=LEADERBOARD_SIZE + 5 {
+        award(&Address::generate(&env), 100 + xp);
+    }
+    let board = client.get_top_users(&(LEADERBOARD_SIZE * 2));
+    assert_eq!(board.len(), LEADERBOARD_SIZE);
+    assert_eq!(board.get(0).unwrap().1, 100 + LEADERBOARD_SIZE as u64 + 5);
+}
+
+#[test]
+fn test_template_versions_recorded_on_issuance() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let doc = String::from_str(&env, "QmHash");
+
+    let template = client.create_template(
+        &admin,
+        &String::from_str(&env, "First Aid"),
+        &String::from_str(&env, "Basic first aid"),
+        &String::from_str(&env, "course-001"),
+        &0,
+    );
+    assert_eq!(client.get_template_version(&template), 1);
+    let v1 = client.issue_from_template(&admin, &template, &recipient, &doc);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let version = client.update_template(
+        &admin,
+        &template,
+        &String::from_str(&env, "First Aid"),
+        &String::from_str(&env, "First aid with CPR module"),
+        &5_000,
+    );
+    assert_eq!(version, 2);
+    let v2 = client.issue_from_template(&admin, &template, &recipient, &doc);
+
+    let first = client.get_credential(&v1);
+    let second = client.get_credential(&v2);
+    assert_eq!((first.template_id, first.template_version), (template, 1));
+    assert_eq!((second.template_id, second.template_version), (template, 2));
+    assert_eq!(first.description, String::from_str(&env, "Basic first aid"));
+    assert_eq!(second.description, String::from_str(&env, "First aid with CPR module"));
+    assert_eq!((first.expires_at, second.expires_at), (0, 6_000));
+}
+
+#[test]
+fn test_issuance_errors_instead_of_wrapping_counter() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&CredentialKey::CredentialCount, &u64::MAX);
+    });
+
+    assert_eq!(
+        try_issue_sized(&env, &client, &admin, 8, 8),
+        Err(Ok(CredentialError::CounterOverflow.into()))
+    );
+    assert_eq!(client.get_credential_count(), u64::MAX);
+}
+
+#[test]
+fn test_tallies_error_instead_of_wrapping() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&CredentialKey::EmergencyRevokeCount, &u32::MAX);
+        env.storage().instance().set(&CredentialKey::ShareTokenNonce, &u64::MAX);
+    });
+    assert_eq!(
+        client.try_emergency_revoke(&cred_id, &admin, &RevocationReason::Fraud, &None),
+        Err(Ok(CredentialError::CounterOverflow.into()))
+    );
+    assert_eq!(
+        client.try_create_share_token(&cred_id, &recipient, &(env.ledger().timestamp() + 60)),
+        Err(Ok(CredentialError::CounterOverflow.into()))
+    );
+
+    // Durations added to the clock error too
+    env.ledger().set_timestamp(1_000);
+    client.set_revocation_delay(&admin, &u64::MAX);
+    assert_eq!(
+        client.try_revoke_credential(&cred_id, &admin, &RevocationReason::Fraud, &None),
+        Err(Ok(CredentialError::CounterOverflow.into()))
+    );
+    let text = |s: &str| String::from_str(&env, s);
+    let template = client.create_template(&admin, &text("Rust 101"), &text("Intro"), &text("course-001"), &u64::MAX);
+    assert_eq!(
+        client.try_issue_from_template(&admin, &template, &recipient, &text("ipfs://Qm...")),
+        Err(Ok(CredentialError::CounterOverflow.into()))
+    );
+}
+
+#[test]
+fn test_encrypted_credential_flag_and_hint() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let hint = String::from_str(&env, "x25519:ab12cd34");
+
+    let id = client.issue_encrypted_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Medical Residency"),
+        &String::from_str(&env, "Clinical rotation records"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "QmEncrypted"),
+        &hint,
+    );
+    let credential = client.get_credential(&id);
+    assert!(credential.encrypted);
+    assert_eq!(credential.encryption_pubkey_hint, hint);
+    assert_eq!(client.verify_credential_detailed(&id), VerificationStatus::Valid);
+
+    let plain = client.get_credential(&issue(&env, &client, &admin, &recipient, "course-002"));
+    assert!(!plain.encrypted);
+    assert!(plain.encryption_pubkey_hint.is_empty());
+}
+
+#[test]
+fn test_remap_course_moves_index() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let old_course = String::from_str(&env, "course-old");
+    let new_course = String::from_str(&env, "course-new");
+
+    let existing = issue(&env, &client, &admin, &Address::generate(&env), "course-new");
+    let a = issue(&env, &client, &admin, &Address::generate(&env), "course-old");
+    let b = issue(&env, &client, &admin, &Address::generate(&env), "course-old");
+    assert_eq!(client.get_course_credentials(&old_course), vec![&env, a, b]);
+
+    assert_eq!(client.remap_course(&admin, &old_course, &new_course), 2);
+    assert_eq!(client.get_course_credentials(&new_course), vec![&env, existing, a, b]);
+    assert!(client.get_course_credentials(&old_course).is_empty());
+    assert_eq!(client.get_credential(&a).course_id, new_course);
+    assert_eq!(client.get_credential(&b).course_id, new_course);
+    assert_eq!(client.verify_credential_detailed(&a), VerificationStatus::Valid);
+}
+
+#[test]
+fn test_credentials_equal_compares_content() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let a = issue(&env, &client, &admin, &recipient, "course-001");
+    let other_course = issue(&env, &client, &admin, &recipient, "course-002");
+    // Distinct display ids and a hidden flag are bookkeeping, not content
+    client.set_display_id_template(&admin, &String::from_str(&env, "{course}-{seq}"));
+    let b = issue(&env, &client, &admin, &recipient, "course-001");
+    client.recipient_hide_credential(&b, &recipient);
+    assert_ne!(client.get_credential(&a).display_id, client.get_credential(&b).display_id);
+    assert!(client.credentials_equal(&a, &b, &false));
+    assert!(!client.credentials_equal(&a, &other_course, &true));
+
+    client.revoke_credential(&b, &admin, &RevocationReason::Other, &None);
+    assert!(!client.credentials_equal(&a, &b, &false));
+    assert!(client.credentials_equal(&a, &b, &true));
+}
+
+#[test]
+fn test_audited_verifications_are_logged() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let registrar = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    assert_eq!(client.verify_credential_audited(&id, &employer), VerificationStatus::Valid);
+    client.revoke_credential(&id, &admin, &RevocationReason::Fraud, &None);
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    assert_eq!(client.verify_credential_audited(&id, &registrar), VerificationStatus::Revoked);
+    // Anonymous verification leaves no trace
+    client.verify_credential_detailed(&id);
+
+    let log = client.get_verification_log_paged(&id, &0, &10);
+    assert_eq!(log.len(), 2);
+    let first = log.get(0).unwrap();
+    assert_eq!((first.verifier, first.verified_at, first.status), (employer, 100, VerificationStatus::Valid));
+    let second = client.get_verification_log_paged(&id, &1, &1).get(0).unwrap();
+    assert_eq!((second.verifier.clone(), second.status), (registrar.clone(), VerificationStatus::Revoked));
+
+    client.set_verification_log_limit(&admin, &2);
+    client.verify_credential_audited(&id, &registrar);
+    let log = client.get_verification_log_paged(&id, &0, &10);
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.get(0).unwrap(), second);
+}
+
+#[test]
+fn test_issue_to_email_and_claim() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use soroban_sdk::xdr::ToXdr;
+
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let learner = Address::generate(&env);
+    let email_hash: BytesN<32> = env.crypto().sha256(&Bytes::from_slice(&env, b"learner@example.edu")).into();
+
+    let verifier = SigningKey::from_bytes(&[9u8; 32]);
+    client.set_claim_verifier_key(&admin, &BytesN::from_array(&env, &verifier.verifying_key().to_bytes()));
+
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    let pending = client.issue_to_email(
+        &admin,
+        &email_hash,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "QmHash"),
+    );
+    assert_eq!(pending, 1);
+    assert!(client.get_user_credentials(&learner).is_empty());
+
+    let sign_for = |recipient: &Address| {
+        let message = (client.address.clone(), email_hash.clone(), recipient.clone()).to_xdr(&env);
+        let mut buf = [0u8; 256];
+        let len = message.len() as usize;
+        message.copy_into_slice(&mut buf[..len]);
+        BytesN::from_array(&env, &verifier.sign(&buf[..len]).to_bytes())
+    };
+
+    // A proof made out to someone else can't be used
+    let intruder = Address::generate(&env);
+    assert!(client.try_claim_credential(&email_hash, &sign_for(&learner), &intruder).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    let ids = client.claim_credential(&email_hash, &sign_for(&learner), &learner);
+    assert_eq!(ids.len(), 1);
+    let id = ids.get(0).unwrap();
+    assert_eq!(client.get_user_credentials(&learner), vec![&env, id]);
+    let credential = client.get_credential(&id);
+    assert_eq!(credential.recipient, learner);
+    assert_eq!(credential.completion_date, 500);
+    assert!(client.get_email_credentials(&email_hash).is_empty());
+}
+
+#[test]
+fn test_claim_skips_items_from_unauthorized_issuers() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use soroban_sdk::xdr::ToXdr;
+
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let learner = Address::generate(&env);
+    let school = Address::generate(&env);
+    client.add_issuer(&admin, &school);
+    let email_hash: BytesN<32> = env.crypto().sha256(&Bytes::from_slice(&env, b"learner@example.edu")).into();
+
+    let verifier = SigningKey::from_bytes(&[9u8; 32]);
+    client.set_claim_verifier_key(&admin, &BytesN::from_array(&env, &verifier.verifying_key().to_bytes()));
+    for issuer in [&school, &admin] {
+        client.issue_to_email(
+            issuer,
+            &email_hash,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "QmHash"),
+        );
+    }
+    client.remove_issuer(&admin, &school);
+
+    let message = (client.address.clone(), email_hash.clone(), learner.clone()).to_xdr(&env);
+    let mut buf = [0u8; 256];
+    let len = message.len() as usize;
+    message.copy_into_slice(&mut buf[..len]);
+    let proof = BytesN::from_array(&env, &verifier.sign(&buf[..len]).to_bytes());
+
+    // The admin's item mints; the removed school's item waits for re-authorization
+    let ids = client.claim_credential(&email_hash, &proof, &learner);
+    assert_eq!(ids.len(), 1);
+    assert_eq!(client.get_credential(&ids.get(0).unwrap()).issuer, admin);
+    let waiting = client.get_email_credentials(&email_hash);
+    assert_eq!(waiting.len(), 1);
+    assert_eq!(waiting.get(0).unwrap().issuer, school);
+
+    client.add_issuer(&admin, &school);
+    let ids = client.claim_credential(&email_hash, &proof, &learner);
+    assert_eq!(client.get_credential(&ids.get(0).unwrap()).issuer, school);
+    assert!(client.get_email_credentials(&email_hash).is_empty());
+}
+
+#[test]
+fn test_credentials_by_issuer_and_course() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let university = Address::generate(&env);
+    client.add_issuer(&admin, &university);
+
+    let wanted_a = issue(&env, &client, &university, &Address::generate(&env), "course-001");
+    issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+    issue(&env, &client, &university, &Address::generate(&env), "course-002");
+    let wanted_b = issue(&env, &client, &university, &Address::generate(&env), "course-001");
+
+    assert_eq!(
+        client.get_issuer_course_credentials(&university, &String::from_str(&env, "course-001")),
+        vec![&env, wanted_a, wanted_b]
+    );
+    assert!(client
+        .get_issuer_course_credentials(&university, &String::from_str(&env, "course-999"))
+        .is_empty());
+}
+
+#[test]
+fn test_reinstating_valid_credential_errors_without_event() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+    let events_before = env.events().all().len();
+
+    assert_eq!(client.try_reinstate_credential(&id, &admin), Err(Ok(CredentialError::AlreadyValid.into())));
+    assert_eq!(env.events().all().len(), events_before);
+}
+
+#[test]
+fn test_display_ids_from_template() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    // Before a template is set, credentials carry no display id
+    let plain = issue(&env, &client, &admin, &recipient, "CS");
+    assert!(client.get_credential(&plain).display_id.is_empty());
+
+    client.set_display_id_template(&admin, &String::from_str(&env, "{year}-{course}-{seq}"));
+    env.ledger().with_mut(|li| li.timestamp = 1_709_251_200); // 2024-03-01
+    let first = issue(&env, &client, &admin, &recipient, "CS");
+    let second = issue(&env, &client, &admin, &recipient, "CS");
+    let other = issue(&env, &client, &admin, &recipient, "MATH");
+
+    assert_eq!(client.get_credential(&first).display_id, String::from_str(&env, "2024-CS-0001"));
+    assert_eq!(client.get_credential(&second).display_id, String::from_str(&env, "2024-CS-0002"));
+    assert_eq!(client.get_credential(&other).display_id, String::from_str(&env, "2024-MATH-0001"));
+    assert_eq!(client.get_by_display_id(&String::from_str(&env, "2024-CS-0002")), Some(second));
+    assert_eq!(client.get_by_display_id(&String::from_str(&env, "2024-CS-0003")), None);
+
+    let supplied = String::from_str(&env, "HONORS-7");
+    let issue_supplied = || {
+        client.try_issue_credential_with_display_id(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "CS"),
+            &String::from_str(&env, "QmHash"),
+            &supplied,
+        )
+    };
+    let honors = issue_supplied().unwrap().unwrap();
+    assert_eq!(client.get_by_display_id(&supplied), Some(honors));
+    assert_eq!(issue_supplied(), Err(Ok(CredentialError::DuplicateDisplayId.into())));
+}
+
+#[test]
+fn test_revocation_with_future_effective_date() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    let retake = issue(&env, &client, &admin, &recipient, "course-001");
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.revoke_credential_effective(&id, &admin, &RevocationReason::PolicyViolation, &None, &5_000);
+    assert_eq!(client.get_credential(&id).revoked_from, 5_000);
+    assert_eq!(client.get_pending_revocation(&id).unwrap().executable_at, 5_000);
+    assert_eq!(client.verify_credential_detailed(&id), VerificationStatus::Valid);
+    assert_eq!(client.get_issuer_stats(&admin).revoked_count, 0);
+
+    // Until the effective date the credential reads as unrevoked everywhere
+    env.ledger().with_mut(|li| li.timestamp = 4_999);
+    assert!(client.verify_credential(&id));
+    assert_eq!(client.check_revoked_batch(&vec![&env, id]), vec![&env, false]);
+    assert_eq!(client.get_active_user_credentials(&recipient, &false), vec![&env, id, retake]);
+    assert_eq!(client.find_duplicate_credentials(&recipient), vec![&env, (id, retake)]);
+    let retitle = CredentialUpdate {
+        title: Some(String::from_str(&env, "Rust on Stellar II")),
+        description: None,
+        ipfs_hash: None,
+        expires_at: None,
+    };
+    client.update_credential_fields(&id, &retitle, &admin);
+    let appeal = String::from_str(&env, "Not yet");
+    assert!(client.try_file_appeal(&id, &recipient, &appeal).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert_eq!(client.verify_credential_detailed(&id), VerificationStatus::Revoked);
+    assert!(!client.verify_credential(&id));
+    assert_eq!(client.check_revoked_batch(&vec![&env, id]), vec![&env, true]);
+    assert_eq!(client.get_active_user_credentials(&recipient, &false), vec![&env, retake]);
+    assert!(client.find_duplicate_credentials(&recipient).is_empty());
+    assert!(client.try_update_credential_fields(&id, &retitle, &admin).is_err());
+    client.file_appeal(&id, &recipient, &appeal);
+
+    // Stats and history catch up once the scheduled revocation is executed
+    client.execute_revocation(&id);
+    assert_eq!(client.get_issuer_stats(&admin).revoked_count, 1);
+    assert_eq!(client.get_credential(&id).revoked_from, 5_000);
+    assert!(client.get_pending_revocation(&id).is_none());
+}
+
+#[test]
+fn test_future_effective_revocation_respects_delay_and_defers_cascade() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let prerequisite = issue(&env, &client, &admin, &recipient, "course-001");
+    let dependent = issue_dependent(&env, &client, &admin, &recipient, prerequisite);
+    client.set_cascade_mode(&admin, &CascadeMode::Revoke);
+    client.set_revocation_delay(&admin, &100_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.revoke_credential_effective(&prerequisite, &admin, &RevocationReason::Fraud, &None, &1_001);
+    assert_eq!(client.get_pending_revocation(&prerequisite).unwrap().executable_at, 101_000);
+    assert_eq!(client.get_credential(&prerequisite).revoked_from, 101_000);
+    assert!(client.verify_credential(&dependent));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+    assert!(client.verify_credential(&prerequisite));
+    assert!(client.try_execute_revocation(&prerequisite).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp = 101_000);
+    assert!(!client.verify_credential(&prerequisite));
+    assert!(client.verify_credential(&dependent));
+    assert!(client.try_suspend_credential(&prerequisite, &admin).is_err());
+    assert!(client.try_emergency_revoke(&prerequisite, &admin, &RevocationReason::Fraud, &None).is_err());
+
+    client.execute_revocation(&prerequisite);
+    assert!(!client.verify_credential(&dependent));
+    assert_eq!(client.get_credential(&dependent).revoked_from, 0);
+    assert_eq!(client.get_emergency_revoke_count(), 0);
+}
+
+#[test]
+fn test_config_bundle_round_trip_and_validation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let defaults = client.get_config();
+    assert_eq!(defaults.max_batch_size, client.get_max_batch_size());
+    assert_eq!(defaults.event_namespace, DEFAULT_EVENT_NAMESPACE);
+
+    let config = Config {
+        max_title_len: 80,
+        max_description_len: 400,
+        max_batch_size: 25,
+        max_credentials_per_user: 500,
+        min_completion_date: 5_000,
+        max_future_skew: 60,
+        revocation_delay: 3_600,
+        grace_period: 86_400,
+        cascade_mode: CascadeMode::FlagForReview,
+        hook_failure_mode: HookFailureMode::Skip,
+        verification_log_limit: 100,
+        event_namespace: Symbol::new(&env, "campus_b"),
+        ipfs_gateway: String::from_str(&env, "https://gateway.campus-b.edu/ipfs/"),
+        bulk_undo_window: 3_600,
+        appeal_timeout: 604_800,
+        appeal_auto_reinstate: true,
+    };
+    client.set_config(&admin, &config);
+    assert_eq!(client.get_config(), config);
+    assert_eq!(client.get_field_limits(), (80, 400));
+    assert_eq!(client.get_max_credentials_per_user(), 500);
+
+    // A completion-date floor beyond the allowed skew would make every issuance fail
+    let impossible = Config { min_completion_date: 20_000, ..config.clone() };
+    assert_eq!(client.try_set_config(&admin, &impossible), Err(Ok(CredentialError::InvalidConfig.into())));
+    let no_batches = Config { max_batch_size: 0, ..config.clone() };
+    assert_eq!(client.try_set_config(&admin, &no_batches), Err(Ok(CredentialError::InvalidConfig.into())));
+    assert_eq!(client.get_config(), config);
+}
+
+#[test]
+fn test_verifier_grants_on_private_credentials() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let id = issue(&env, &client, &admin, &recipient, "course-001");
+
+    // Public credentials answer anyone
+    assert_eq!(client.verify_credential_as(&id, &stranger), VerificationStatus::Valid);
+
+    client.set_credential_private(&id, &recipient, &true);
+    client.grant_verifier(&id, &recipient, &employer);
+    assert_eq!(client.verify_credential_as(&id, &employer), VerificationStatus::Valid);
+    assert_eq!(client.try_verify_credential_as(&id, &stranger), Err(Ok(CredentialError::VerifierNotGranted.into())));
+
+    client.revoke_verifier(&id, &recipient, &employer);
+    assert_eq!(client.try_verify_credential_as(&id, &employer), Err(Ok(CredentialError::VerifierNotGranted.into())));
+    assert_eq!(
+        client.try_grant_verifier(&id, &stranger, &stranger),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+}
+
+#[test]
+fn test_get_histories_batches_status_changes() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    let a = issue(&env, &client, &admin, &recipient, "course-001");
+    let b = issue(&env, &client, &admin, &recipient, "course-002");
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.revoke_credential(&a, &admin, &RevocationReason::IssuanceError, &None);
+    client.revoke_credential(&b, &admin, &RevocationReason::Fraud, &None);
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    client.reinstate_credential(&a, &admin);
+
+    let change = |status, changed_at| StatusChange { status, changed_at };
+    let histories = client.get_histories(&vec![&env, a, b]);
+    assert_eq!(histories.len(), 2);
+    assert_eq!(
+        histories.get(a).unwrap(),
+        vec![
+            &env,
+            change(VerificationStatus::Valid, 100),
+            change(VerificationStatus::Revoked, 200),
+            change(VerificationStatus::Valid, 300),
+        ]
+    );
+    assert_eq!(
+        histories.get(b).unwrap(),
+        vec![&env, change(VerificationStatus::Valid, 100), change(VerificationStatus::Revoked, 200)]
+    );
+
+    let mut too_many = Vec::new(&env);
+    for id in 0..=MAX_HISTORY_BATCH as u64 {
+        too_many.push_back(id);
+    }
+    assert_eq!(client.try_get_histories(&too_many), Err(Ok(CredentialError::BatchTooLarge.into())));
+}
+
+#[test]
+fn test_title_sanitization() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let try_issue_titled = |title: &str, description: &str| {
+        client.try_issue_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, title),
+            &String::from_str(&env, description),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "QmHash"),
+        )
+    };
+
+    let padded = try_issue_titled("   Rust    on  Stellar  ", "Line one\nLine two").unwrap().unwrap();
+    assert_eq!(client.get_credential(&padded).title, String::from_str(&env, "Rust on Stellar"));
+    assert_eq!(client.get_credential(&padded).description, String::from_str(&env, "Line one\nLine two"));
+
+    assert_eq!(try_issue_titled("Rust\u{0007}Bell", "ok"), Err(Ok(CredentialError::InvalidCharacters.into())));
+    assert_eq!(try_issue_titled("Two\nLines", "ok"), Err(Ok(CredentialError::InvalidCharacters.into())));
+    assert_eq!(try_issue_titled("Fine", "Nul\u{0000}byte"), Err(Ok(CredentialError::InvalidCharacters.into())));
+
+    client.set_feature_flags(&admin, &FeatureFlags { sanitize_text: false, ..client.get_feature_flags() });
+    let raw = try_issue_titled("  As typed  ", "ok").unwrap().unwrap();
+    assert_eq!(client.get_credential(&raw).title, String::from_str(&env, "  As typed  "));
+}
+
+#[test]
+fn test_edits_are_sanitized_like_issuance() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let text = |value: &str| String::from_str(&env, value);
+
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-001");
+    let updates = CredentialUpdate { title: Some(text(" Rust   on Stellar ")), description: None, ipfs_hash: None, expires_at: None };
+    client.update_credential_fields(&cred_id, &updates, &admin);
+    assert_eq!(client.get_credential(&cred_id).title, text("Rust on Stellar"));
+    let updates = CredentialUpdate { title: None, description: Some(text("Nul\u{0000}byte")), ipfs_hash: None, expires_at: None };
+    assert_eq!(
+        client.try_update_credential_fields(&cred_id, &updates, &admin),
+        Err(Ok(CredentialError::InvalidCharacters.into()))
+    );
+
+    client.add_translation(&cred_id, &text("es"), &text("  Rust en  Stellar"), &text("Linea uno\nLinea dos"), &admin);
+    assert_eq!(client.get_localized(&cred_id, &text("es")).0, text("Rust en Stellar"));
+    assert_eq!(
+        client.try_add_translation(&cred_id, &text("fr"), &text("Rust\u{0007}"), &text("ok"), &admin),
+        Err(Ok(CredentialError::InvalidCharacters.into()))
+    );
+
+    let template_id = client.create_template(&admin, &text("Rust  101 "), &text("Basics"), &text("course-001"), &0);
+    assert_eq!(client.get_template(&template_id).title, text("Rust 101"));
+    client.update_template(&admin, &template_id, &text(" Rust 102"), &text("Basics"), &0);
+    assert_eq!(client.get_template(&template_id).title, text("Rust 102"));
+    assert_eq!(
+        client.try_update_template(&admin, &template_id, &text("Rust 103"), &text("Tab\tok, bell\u{0007} not"), &0),
+        Err(Ok(CredentialError::InvalidCharacters.into()))
+    );
+}
+
+#[test]
+fn test_resolve_status_precedence() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let expire_at = |id: u64, expires_at: u64| {
+        let updates = CredentialUpdate { title: None, description: None, ipfs_hash: None, expires_at: Some(expires_at) };
+        client.update_credential_fields(&id, &updates, &admin);
+    };
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    // Revoked beats expired
+    let revoked_and_expired = issue_expiring(&env, &client, &admin, &recipient, 2_000);
+    client.revoke_credential(&revoked_and_expired, &admin, &RevocationReason::Other, &None);
+
+    // Not yet active beats expired
+    let scheduled_and_expired = client.issue_scheduled_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "QmHash"),
+        &9_000,
+    );
+    expire_at(scheduled_and_expired, 2_000);
+
+    // Document pending beats expired
+    let pending_and_expired = client.issue_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, ""),
+    );
+    expire_at(pending_and_expired, 2_000);
+
+    // Migrated beats revoked
+    let migrated_and_revoked = issue(&env, &client, &admin, &recipient, "course-001");
+    client.set_migration_target(&admin, &Address::generate(&env));
+    client.export_for_migration(&migrated_and_revoked, &admin);
+    client.revoke_credential(&migrated_and_revoked, &admin, &RevocationReason::Other, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert_eq!(client.resolve_status(&revoked_and_expired), VerificationStatus::Revoked);
+    assert_eq!(client.resolve_status(&scheduled_and_expired), VerificationStatus::NotYetActive);
+    assert_eq!(client.resolve_status(&pending_and_expired), VerificationStatus::DocumentPending);
+    assert_eq!(client.resolve_status(&migrated_and_revoked), VerificationStatus::Migrated);
+    assert_eq!(client.resolve_status(&999), VerificationStatus::NotFound);
+    assert_eq!(
+        client.verify_credential_detailed(&scheduled_and_expired),
+        client.resolve_status(&scheduled_and_expired)
+    );
+}
+
+#[test]
+fn test_issuance_quota_top_up_and_exhaustion() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+    let try_issue = || {
+        client.try_issue_credential(
+            &issuer,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "QmHash"),
+        )
+    };
+
+    // Unmetered until a quota is granted
+    assert_eq!(client.get_quota(&issuer), None);
+    assert!(try_issue().is_ok());
+
+    assert_eq!(client.add_quota(&admin, &issuer, &2), 2);
+    assert!(try_issue().is_ok());
+    assert!(try_issue().is_ok());
+    assert_eq!(client.get_quota(&issuer), Some(0));
+    assert_eq!(try_issue(), Err(Ok(CredentialError::QuotaExhausted.into())));
+
+    client.add_quota(&admin, &issuer, &1);
+    assert!(try_issue().is_ok());
+    assert_eq!(client.get_quota(&issuer), Some(0));
+}
+
+#[test]
+fn test_issuer_authorization_expiry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_issuer_expiry(&admin, &issuer, &2_000);
+    issue(&env, &client, &issuer, &recipient, "course-001");
+    assert!(client.is_issuer(&issuer));
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert!(!client.is_issuer(&issuer));
+    assert_eq!(
+        client.try_issue_credential(
+            &issuer,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-002"),
+            &String::from_str(&env, "QmHash"),
+        ),
+        Err(Ok(CredentialError::IssuerExpired.into()))
+    );
+
+    // Renewed accreditation
+    client.set_issuer_expiry(&admin, &issuer, &0);
+    issue(&env, &client, &issuer, &recipient, "course-002");
+}
+
+#[test]
+fn test_best_credential_per_course() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let graded = |course_id: &str, grade: u32| {
+        client.issue_graded_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, course_id),
+            &String::from_str(&env, "QmHash"),
+            &grade,
+        )
+    };
+
+    let strong = graded("course-001", 92);
+    graded("course-001", 71); // weaker retake
+    issue(&env, &client, &admin, &recipient, "course-002");
+    let latest_ungraded = issue(&env, &client, &admin, &recipient, "course-002");
+    let best_but_revoked = graded("course-003", 99);
+    let fallback = graded("course-003", 60);
+    client.revoke_credential(&best_but_revoked, &admin, &RevocationReason::Other, &None);
+
+    let best = client.get_best_per_course(&recipient);
+    assert_eq!(best.len(), 3);
+    assert_eq!(best.get(String::from_str(&env, "course-001")), Some(strong));
+    assert_eq!(best.get(String::from_str(&env, "course-002")), Some(latest_ungraded));
+    assert_eq!(best.get(String::from_str(&env, "course-003")), Some(fallback));
+}
+
+#[test]
+fn test_dependent_cannot_predate_prerequisite() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let prerequisite = issue(&env, &client, &admin, &recipient, "course-001");
+    let try_dependent = |completion_date: u64| {
+        client.try_issue_dependent_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust Advanced"),
+            &String::from_str(&env, "Builds on Rust on Stellar"),
+            &String::from_str(&env, "course-002"),
+            &String::from_str(&env, "ipfs://Qm..."),
+            &prerequisite,
+            &Some(completion_date),
+        )
+    };
+
+    assert_eq!(try_dependent(9_000), Err(Ok(CredentialError::TemporalInconsistency.into())));
+    let dependent = try_dependent(10_000).unwrap().unwrap();
+    assert_eq!(client.get_dependents(&prerequisite), vec![&env, dependent]);
+}
+
+#[test]
+fn test_recipient_hide_and_unhide() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let shown = issue(&env, &client, &admin, &recipient, "course-001");
+    let hidden = issue(&env, &client, &admin, &recipient, "course-002");
+    client.pin_credential(&recipient, &hidden);
+
+    client.recipient_hide_credential(&hidden, &recipient);
+    let profile = client.get_user_profile_summary(&recipient);
+    assert_eq!(profile.len(), 1);
+    assert_eq!(profile.get(0).unwrap().credential_id, shown);
+    assert!(client.get_pinned_credentials(&recipient).is_empty());
+    // Issuer-side validity and lookups by id are untouched
+    assert_eq!(client.verify_credential_detailed(&hidden), VerificationStatus::Valid);
+    assert!(client.get_issuer_credentials(&admin, &None).contains(hidden));
+
+    client.recipient_unhide(&hidden, &recipient);
+    assert_eq!(client.get_user_profile_summary(&recipient).len(), 2);
+    assert_eq!(
+        client.try_recipient_hide_credential(&shown, &admin),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+}
+
+#[test]
+fn test_import_validates_migrated_fingerprint() {
+    let env = Env::default();
+    let (source, source_admin) = setup(&env);
+    let (target, target_admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    issue(&env, &source, &source_admin, &recipient, "course-000");
+    let exported = issue(&env, &source, &source_admin, &recipient, "course-001");
+    let forged = issue(&env, &source, &source_admin, &recipient, "course-002");
+    source.set_migration_target(&source_admin, &target.address);
+    let record = source.export_for_migration(&exported, &source_admin);
+
+    let imported = target.import_migrated_credential(&target_admin, &record);
+    assert!(target.validate_imported(&imported));
+    assert_eq!(target.get_import_provenance(&imported).unwrap().source_id, exported);
+    assert_eq!(target.get_user_credentials(&recipient), vec![&env, imported]);
+
+    let mut tampered = source.export_for_migration(&forged, &source_admin);
+    tampered.credential.title = String::from_str(&env, "Rust on Stellar (Honors)");
+    assert_eq!(
+        target.try_import_migrated_credential(&target_admin, &tampered),
+        Err(Ok(CredentialError::TamperedImport.into()))
+    );
+
+    // Edits after import break the link to the signed content
+    let updates = CredentialUpdate { title: None, description: Some(String::from_str(&env, "Edited")), ipfs_hash: None, expires_at: None };
+    target.update_credential_fields(&imported, &updates, &target_admin);
+    assert!(!target.validate_imported(&imported));
+    assert!(!target.validate_imported(&999));
+}
+
+#[test]
+fn test_import_keeps_ids_links_and_revocations() {
+    let env = Env::default();
+    let (source, source_admin) = setup(&env);
+    let (target, target_admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let original = issue(&env, &source, &source_admin, &recipient, "course-001");
+    let renewed = source.reissue_credential(&source_admin, &original, &0, &String::from_str(&env, "ipfs://renewed"));
+    let revoked = issue(&env, &source, &source_admin, &recipient, "course-002");
+    source.revoke_credential(&revoked, &source_admin, &RevocationReason::Fraud, &None);
+    source.set_migration_target(&source_admin, &target.address);
+
+    // Neither a spent quota nor today's date bounds stop old credentials coming over
+    target.add_quota(&target_admin, &source_admin, &0);
+    target.set_completion_date_bounds(&target_admin, &1_000_000, &0);
+
+    let mut leaves = Vec::new(&env);
+    let mut records = Vec::new(&env);
+    for cred_id in [original, renewed, revoked] {
+        let record = source.export_for_migration(&cred_id, &source_admin);
+        leaves.push_back(record.fingerprint.clone());
+        assert_eq!(target.import_migrated_credential(&target_admin, &record), cred_id);
+        records.push_back(record);
+    }
+
+    assert_eq!(target.get_credential(&original).superseded_by, renewed);
+    assert_eq!(target.get_credential(&renewed).supersedes, original);
+    assert!(!target.verify_credential(&revoked));
+    let stats = target.get_issuer_stats(&source_admin);
+    assert_eq!((stats.valid_count, stats.revoked_count), (2, 1));
+    let (root, proof) = merkle_proof(&env, &leaves, (renewed - 1) as u32);
+    assert_eq!(target.get_credential_root(), root);
+    assert!(target.verify_inclusion(&renewed, &proof));
+
+    assert_eq!(
+        target.try_import_migrated_credential(&target_admin, &records.get(0).unwrap()),
+        Err(Ok(CredentialError::IdTaken.into()))
+    );
+}
+
+#[test]
+fn test_import_gaps_are_not_credentials() {
+    let env = Env::default();
+    let (source, source_admin) = setup(&env);
+    let (target, target_admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    issue(&env, &source, &source_admin, &recipient, "course-001");
+    let moved = issue(&env, &source, &source_admin, &recipient, "course-002");
+    source.set_migration_target(&source_admin, &target.address);
+    let record = source.export_for_migration(&moved, &source_admin);
+    target.import_migrated_credential(&target_admin, &record);
+
+    assert_eq!(target.get_all_credential_ids(&0, &10), vec![&env, moved]);
+    let leaves = vec![&env, BytesN::from_array(&env, &[0u8; 32]), record.fingerprint];
+    let (_, gap_proof) = merkle_proof(&env, &leaves, 0);
+    assert!(!target.verify_inclusion(&1, &gap_proof));
+    let (_, proof) = merkle_proof(&env, &leaves, 1);
+    assert!(target.verify_inclusion(&moved, &proof));
+}
+
+#[test]
+fn test_soonest_expiring_orders_by_expiry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+
+    let issue_expiring = |expires_at: u64| {
+        client.issue_expiring_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+            &expires_at,
+        )
+    };
+    let late = issue_expiring(9_000);
+    let never = issue(&env, &client, &admin, &recipient, "course-002");
+    let soonest = issue_expiring(2_000);
+    let revoked = issue_expiring(2_500);
+    let middle = issue_expiring(5_000);
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+
+    assert_eq!(client.get_soonest_expiring(&10), vec![&env, soonest, middle, late]);
+    assert_eq!(client.get_soonest_expiring(&2), vec![&env, soonest, middle]);
+    assert!(!client.get_soonest_expiring(&10).contains(never));
+
+    // Extending moves a credential back in the queue; lapsed ones drop out
+    let updates = CredentialUpdate { title: None, description: None, ipfs_hash: None, expires_at: Some(10_000) };
+    client.update_credential_fields(&soonest, &updates, &admin);
+    assert_eq!(client.get_soonest_expiring(&10), vec![&env, middle, late, soonest]);
+    env.ledger().set_timestamp(6_000);
+    assert_eq!(client.get_soonest_expiring(&10), vec![&env, late, soonest]);
+}
+
+#[test]
+fn test_soonest_expiring_spans_weekly_buckets() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let week = 7 * 86_400;
+    env.ledger().set_timestamp(1_000);
+
+    let issue_expiring = |expires_at: u64| {
+        client.issue_expiring_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, "course-001"),
+            &String::from_str(&env, "ipfs://Qm..."),
+            &expires_at,
+        )
+    };
+    let next_month = issue_expiring(4 * week + 10);
+    let this_week = issue_expiring(5_000);
+    let next_week = issue_expiring(week + 10);
+    assert_eq!(client.get_soonest_expiring(&10), vec![&env, this_week, next_week, next_month]);
+    assert_eq!(client.get_soonest_expiring(&2), vec![&env, this_week, next_week]);
+
+    // Once a whole week has lapsed, the next write drops its bucket
+    env.ledger().set_timestamp(week + 100);
+    let later = issue_expiring(3 * week);
+    let has_first_bucket = env.as_contract(&client.address, || {
+        env.storage().persistent().has(&CredentialKeyExt::ExpiryBucket(0))
+    });
+    assert!(!has_first_bucket);
+    assert_eq!(client.get_soonest_expiring(&10), vec![&env, later, next_month]);
+}
+
+#[test]
+fn test_private_issuer_is_masked_for_public_readers() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let board = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.add_issuer(&admin, &board);
+    let cred_id = issue(&env, &client, &board, &recipient, "course-001");
+
+    client.set_issuer_private(&board, &true);
+    assert!(client.is_issuer_private(&board));
+    assert_eq!(client.get_credential(&cred_id).issuer, client.address);
+    assert_eq!(client.find_credential(&cred_id).unwrap().issuer, client.address);
+    assert_eq!(client.get_credential_as(&cred_id, &stranger).issuer, client.address);
+    assert_eq!(client.get_credential_as(&cred_id, &admin).issuer, board);
+    assert_eq!(client.get_credential_as(&cred_id, &board).issuer, board);
+    assert_eq!(client.get_user_profile_summary(&recipient).get(0).unwrap().issuer, client.address);
+    assert!(client.verify_credential(&cred_id));
+
+    let text = |s: &str| String::from_str(&env, s);
+    let template_id = client.create_template(&board, &text("Rust 101"), &text("Intro"), &text("course-001"), &0);
+    assert_eq!(client.get_template(&template_id).issuer, client.address);
+    let anchor_id = client.anchor_document(&board, &BytesN::from_array(&env, &[7; 32]));
+    assert_eq!(client.get_anchor(&anchor_id).issuer, client.address);
+    let email_hash = BytesN::from_array(&env, &[9; 32]);
+    client.issue_to_email(&board, &email_hash, &text("Rust 101"), &text("Intro"), &text("course-001"), &text("ipfs"));
+    assert_eq!(client.get_email_credentials(&email_hash).get(0).unwrap().issuer, client.address);
+
+    // Listings by issuer are for the issuer itself and the admin
+    assert!(client.try_get_issuer_credentials(&board, &None).is_err());
+    assert!(client.try_get_issuer_credentials(&board, &Some(stranger.clone())).is_err());
+    assert!(client.try_export_issuer_credentials(&board, &None).is_err());
+    assert_eq!(client.get_issuer_credentials(&board, &Some(board.clone())), vec![&env, cred_id]);
+    assert_eq!(client.get_issuer_credentials(&board, &Some(admin.clone())), vec![&env, cred_id]);
+    client.export_issuer_credentials_after(&board, &0, &Some(admin.clone()));
+
+    client.set_issuer_private(&board, &false);
+    assert_eq!(client.get_credential(&cred_id).issuer, board);
+    assert_eq!(client.get_template(&template_id).issuer, board);
+    assert_eq!(client.get_issuer_credentials(&board, &None), vec![&env, cred_id]);
+    assert_eq!(client.get_user_profile_summary(&recipient).get(0).unwrap().issuer, board);
+}
+
+#[test]
+fn test_time_to_expiry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+
+    let expiring = client.issue_expiring_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "ipfs://Qm..."),
+        &(1_000 + 30 * 86_400),
+    );
+    let permanent = issue(&env, &client, &admin, &recipient, "course-002");
+
+    assert_eq!(client.time_to_expiry(&expiring), 30 * 86_400);
+    assert_eq!(client.time_to_expiry(&permanent), NEVER_EXPIRES);
+
+    env.ledger().set_timestamp(1_000 + 31 * 86_400);
+    assert_eq!(client.time_to_expiry(&expiring), -86_400);
+    assert_eq!(client.time_to_expiry(&permanent), NEVER_EXPIRES);
+}
+
+fn member(env: &Env, title: &str, course_id: &str) -> MemberSpec {
+    MemberSpec {
+        title: String::from_str(env, title),
+        description: String::from_str(env, "Degree module"),
+        course_id: String::from_str(env, course_id),
+        ipfs_hash: String::from_str(env, "ipfs://Qm..."),
+    }
+}
+
+#[test]
+fn test_issue_bundle_creates_members_and_bundle() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let members = vec![
+        &env,
+        member(&env, "Algorithms", "cs-101"),
+        member(&env, "Databases", "cs-201"),
+        member(&env, "Compilers", "cs-301"),
+    ];
+    let (bundle_id, member_ids) = client.issue_bundle(&admin, &recipient, &members, &String::from_str(&env, "BSc Computer Science"));
+
+    assert_eq!(member_ids, vec![&env, 1, 2, 3]);
+    assert_eq!(bundle_id, 4);
+    assert_eq!(client.get_bundle_members(&bundle_id), member_ids);
+    assert_eq!(client.get_credential(&2).title, String::from_str(&env, "Databases"));
+    assert_eq!(client.get_credential(&bundle_id).title, String::from_str(&env, "BSc Computer Science"));
+    assert_eq!(client.get_user_credentials(&recipient), vec![&env, 1, 2, 3, 4]);
+    assert!(client.get_bundle_members(&1).is_empty());
+
+    // Members count against the batch limit
+    client.set_max_batch_size(&admin, &2);
+    assert_eq!(
+        client.try_issue_bundle(&admin, &recipient, &members, &String::from_str(&env, "BSc Computer Science")),
+        Err(Ok(CredentialError::BatchTooLarge.into()))
+    );
+}
+
+#[test]
+fn test_issue_bundle_rolls_back_on_member_failure() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let too_long = [b't'; 200];
+    let mut broken = member(&env, "Databases", "cs-201");
+    broken.title = String::from_bytes(&env, &too_long);
+    let members = vec![&env, member(&env, "Algorithms", "cs-101"), broken, member(&env, "Compilers", "cs-301")];
+
+    assert_eq!(
+        client.try_issue_bundle(&admin, &recipient, &members, &String::from_str(&env, "BSc Computer Science")),
+        Err(Ok(CredentialError::FieldTooLong.into()))
+    );
+    assert_eq!(client.get_credential_count(), 0);
+    assert!(client.get_user_credentials(&recipient).is_empty());
+    assert_eq!(client.find_credential(&1), None);
+}
+
+#[test]
+fn test_find_duplicate_credentials() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let learner = Address::generate(&env);
+    let careful = Address::generate(&env);
+
+    let first = issue(&env, &client, &admin, &learner, "course-001");
+    issue(&env, &client, &admin, &learner, "course-002");
+    let second = issue(&env, &client, &admin, &learner, "course-001");
+    issue(&env, &client, &admin, &careful, "course-001");
+    issue(&env, &client, &admin, &careful, "course-002");
+
+    assert_eq!(client.find_duplicate_credentials(&learner), vec![&env, (first, second)]);
+    assert!(client.find_duplicate_credentials(&careful).is_empty());
+
+    // Revoking the extra copy resolves the duplicate
+    client.revoke_credential(&second, &admin, &RevocationReason::IssuanceError, &None);
+    assert!(client.find_duplicate_credentials(&learner).is_empty());
+}
+
+#[test]
+fn test_suspend_and_unsuspend_credential() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+    env.ledger().set_timestamp(100);
+    let cred_id = issue(&env, &client, &issuer, &recipient, "course-001");
+
+    env.ledger().set_timestamp(200);
+    client.suspend_credential(&cred_id, &issuer);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Suspended);
+    assert!(!client.verify_credential(&cred_id));
+    assert!(!client.get_credential(&cred_id).is_revoked);
+    assert_eq!(client.try_suspend_credential(&cred_id, &admin), Err(Ok(CredentialError::AlreadySuspended.into())));
+
+    env.ledger().set_timestamp(300);
+    client.unsuspend_credential(&cred_id, &admin);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Valid);
+    assert_eq!(client.try_unsuspend_credential(&cred_id, &admin), Err(Ok(CredentialError::NotSuspended.into())));
+
+    let change = |status, changed_at| StatusChange { status, changed_at };
+    assert_eq!(
+        client.get_history(&cred_id),
+        vec![
+            &env,
+            change(VerificationStatus::Valid, 100),
+            change(VerificationStatus::Suspended, 200),
+            change(VerificationStatus::Valid, 300),
+        ]
+    );
+
+    // Revocation outranks suspension and can't be suspended further
+    client.suspend_credential(&cred_id, &admin);
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Fraud, &None);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Revoked);
+    let other = issue(&env, &client, &issuer, &recipient, "course-002");
+    client.revoke_credential(&other, &admin, &RevocationReason::Fraud, &None);
+    assert_eq!(client.try_suspend_credential(&other, &admin), Err(Ok(CredentialError::AlreadyRevoked.into())));
+    assert_eq!(
+        client.try_suspend_credential(&other, &Address::generate(&env)),
+        Err(Ok(CredentialError::NotYourCredential.into()))
+    );
+}
+
+#[test]
+fn test_credential_document_url_uses_gateway() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let cred_id = client.issue_credential(
+        &admin,
+        &recipient,
+        &String::from_str(&env, "Rust on Stellar"),
+        &String::from_str(&env, "Completed Soroban basics"),
+        &String::from_str(&env, "course-001"),
+        &String::from_str(&env, "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"),
+    );
+    let bare = issue(&env, &client, &admin, &recipient, "course-002");
+
+    // No gateway: the stored hash is returned as is
+    assert_eq!(
+        client.get_credential_document_url(&cred_id),
+        String::from_str(&env, "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")
+    );
+
+    client.set_ipfs_gateway(&admin, &String::from_str(&env, "https://ipfs.io/ipfs/"));
+    assert_eq!(client.get_ipfs_gateway(), String::from_str(&env, "https://ipfs.io/ipfs/"));
+    assert_eq!(
+        client.get_credential_document_url(&cred_id),
+        String::from_str(&env, "https://ipfs.io/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi")
+    );
+    assert_eq!(client.get_credential_document_url(&bare), String::from_str(&env, "https://ipfs.io/ipfs/Qm..."));
+
+    let too_long = [b'g'; 129];
+    assert_eq!(
+        client.try_set_ipfs_gateway(&admin, &String::from_bytes(&env, &too_long)),
+        Err(Ok(CredentialError::FieldTooLong.into()))
+    );
+}
+
+#[test]
+fn test_revoke_by_fingerprints_applies_blocklist() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let forged_a = issue(&env, &client, &admin, &recipient, "course-001");
+    let genuine = issue(&env, &client, &admin, &recipient, "course-002");
+    let forged_b = issue(&env, &client, &admin, &recipient, "course-003");
+    let already = issue(&env, &client, &admin, &recipient, "course-004");
+    client.revoke_credential(&already, &admin, &RevocationReason::Other, &None);
+
+    let blocklist = vec![
+        &env,
+        client.credential_fingerprint(&forged_a),
+        BytesN::from_array(&env, &[7; 32]),
+        client.credential_fingerprint(&forged_b),
+        client.credential_fingerprint(&already),
+    ];
+    assert_eq!(client.revoke_by_fingerprints(&admin, &blocklist, &RevocationReason::Fraud), 2);
+
+    assert_eq!(client.verify_credential_detailed(&forged_a), VerificationStatus::Revoked);
+    assert_eq!(client.verify_credential_detailed(&forged_b), VerificationStatus::Revoked);
+    assert!(client.verify_credential(&genuine));
+    assert_eq!(client.get_revocations_by_reason(&RevocationReason::Fraud), vec![&env, forged_a, forged_b]);
+}
+
+#[test]
+fn test_require_recipient_auth_at_issuance() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let signed_by = |env: &Env, address: &Address| env.auths().iter().any(|(signer, _)| signer == address);
+
+    issue(&env, &client, &admin, &recipient, "course-001");
+    assert!(signed_by(&env, &admin));
+    assert!(!signed_by(&env, &recipient));
+
+    client.set_feature_flags(&admin, &FeatureFlags { require_recipient_auth: true, ..client.get_feature_flags() });
+    assert!(client.get_feature_flags().require_recipient_auth);
+    let cred_id = issue(&env, &client, &admin, &recipient, "course-002");
+    assert!(signed_by(&env, &admin));
+    assert!(signed_by(&env, &recipient));
+    assert!(client.verify_credential(&cred_id));
+}
+
+#[test]
+fn test_credential_storage_key_round_trips() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let cred_id = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+
+    let encoded = client.credential_storage_key(&cred_id);
+    let key = CredentialKey::from_xdr(&env, &encoded).unwrap();
+    assert!(matches!(key, CredentialKey::Credential(id) if id == cred_id));
+
+    let stored: Credential = env.as_contract(&client.address, || env.storage().persistent().get(&key).unwrap());
+    assert_eq!(stored, client.get_credential(&cred_id));
+}
+
+#[test]
+fn test_endorsements_weighted_by_reputation() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let learner = Address::generate(&env);
+    let professional = Address::generate(&env);
+    let anonymous = Address::generate(&env);
+    let cred_id = issue(&env, &client, &admin, &learner, "course-001");
+
+    for course in ["course-101", "course-102", "course-103"] {
+        issue(&env, &client, &admin, &professional, course);
+    }
+    let revoked = issue(&env, &client, &admin, &professional, "course-104");
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+
+    assert_eq!(client.get_endorsement_score(&cred_id), 0);
+    client.endorse_credential(&cred_id, &anonymous);
+    client.endorse_credential(&cred_id, &professional);
+
+    let endorsements = client.get_endorsements(&cred_id);
+    assert_eq!(endorsements.get(0).unwrap().weight, 1);
+    // Three valid credentials; the revoked one earns nothing
+    assert_eq!(endorsements.get(1).unwrap().weight, 4);
+    assert_eq!(client.get_endorsement_score(&cred_id), 5);
+
+    assert_eq!(
+        client.try_endorse_credential(&cred_id, &professional),
+        Err(Ok(CredentialError::AlreadyEndorsed.into()))
+    );
+    assert!(client.try_endorse_credential(&cred_id, &learner).is_err());
+}
+
+#[test]
+fn test_undo_bulk_revocation_within_window() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+    env.ledger().set_timestamp(1_000);
+
+    let a = issue(&env, &client, &issuer, &recipient, "course-001");
+    let b = issue(&env, &client, &issuer, &recipient, "course-001");
+    let other_course = issue(&env, &client, &issuer, &recipient, "course-002");
+    let already = issue(&env, &client, &issuer, &recipient, "course-001");
+    client.revoke_credential(&already, &admin, &RevocationReason::Other, &None);
+
+    assert_eq!(client.revoke_by_course(&admin, &String::from_str(&env, "course-001"), &RevocationReason::PolicyViolation), 2);
+    assert_eq!(client.get_bulk_action_count(), 1);
+    assert_eq!(client.get_bulk_action(&1).unwrap().credential_ids, vec![&env, a, b]);
+    assert!(!client.verify_credential(&a));
+    assert!(client.verify_credential(&other_course));
+
+    env.ledger().set_timestamp(1_000 + 86_400);
+    client.undo_bulk_action(&admin, &1);
+    assert!(client.verify_credential(&a));
+    assert!(client.verify_credential(&b));
+    // Revoked before the bulk action, so not the undo's to reverse
+    assert!(!client.verify_credential(&already));
+    assert!(client.get_bulk_action(&1).unwrap().undone);
+    assert!(client.try_undo_bulk_action(&admin, &1).is_err());
+
+    // Past the window the revocation stands
+    assert_eq!(client.revoke_all_by_issuer(&admin, &issuer, &RevocationReason::Fraud), 3);
+    env.ledger().set_timestamp(1_000 + 86_400 + client.get_bulk_undo_window() + 1);
+    assert_eq!(client.try_undo_bulk_action(&admin, &2), Err(Ok(CredentialError::UndoWindowClosed.into())));
+    assert!(!client.verify_credential(&other_course));
+}
+
+#[test]
+fn test_cohort_revocation_logs_cascades_and_undo_skips_strict_issuers() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let strict = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let fall = String::from_str(&env, "Fall 2024");
+    client.add_issuer(&admin, &strict);
+    client.set_issuer_policy(&admin, &strict, &IssuerPolicy { allow_reinstate: false, revocation_delay: 0 });
+    client.set_cascade_mode(&admin, &CascadeMode::Revoke);
+
+    let prerequisite = issue(&env, &client, &admin, &recipient, "course-001");
+    let dependent = issue_dependent(&env, &client, &admin, &recipient, prerequisite);
+    let locked = issue(&env, &client, &strict, &recipient, "course-001");
+    client.assign_cohort(&admin, &prerequisite, &fall);
+    client.assign_cohort(&strict, &locked, &fall);
+
+    let signers = vec![&env, Address::generate(&env)];
+    client.set_admin_signers(&admin, &signers, &1);
+    assert!(client.try_revoke_cohort(&fall, &admin, &RevocationReason::Fraud).is_err());
+    client.approve_action(&signers.get(0).unwrap(), &client.revoke_cohort_action_hash(&fall, &RevocationReason::Fraud));
+
+    assert_eq!(client.revoke_cohort(&fall, &admin, &RevocationReason::Fraud), 3);
+    assert_eq!(client.get_bulk_action(&1).unwrap().credential_ids, vec![&env, prerequisite, dependent, locked]);
+
+    client.undo_bulk_action(&admin, &1);
+    assert!(client.verify_credential(&prerequisite));
+    assert!(client.verify_credential(&dependent));
+    assert!(!client.verify_credential(&locked));
+
+    client.set_feature_flags(&admin, &FeatureFlags { paused: true, ..client.get_feature_flags() });
+    assert!(client.try_revoke_cohort(&fall, &admin, &RevocationReason::Fraud).is_err());
+}
+
+#[test]
+fn test_bulk_revocations_need_signer_approvals_and_no_pause() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let issuer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &issuer);
+    let cred_id = issue(&env, &client, &issuer, &recipient, "course-001");
+    let course_id = String::from_str(&env, "course-001");
+
+    client.set_paused(&admin, &true);
+    assert!(client.try_revoke_by_course(&admin, &course_id, &RevocationReason::Fraud).is_err());
+    assert!(client.try_revoke_all_by_issuer(&admin, &issuer, &RevocationReason::Fraud).is_err());
+    client.set_paused(&admin, &false);
+
+    let signers = vec![&env, Address::generate(&env), Address::generate(&env)];
+    client.set_admin_signers(&admin, &signers, &2);
+    assert!(client.try_revoke_by_course(&admin, &course_id, &RevocationReason::Fraud).is_err());
+    assert!(client.try_revoke_all_by_issuer(&admin, &issuer, &RevocationReason::Fraud).is_err());
+
+    // Approvals bind the reason as well as the target
+    let by_course = client.revoke_by_course_action_hash(&course_id, &RevocationReason::Fraud);
+    for signer in signers.iter() {
+        client.approve_action(&signer, &by_course);
+    }
+    assert!(client.try_revoke_by_course(&admin, &course_id, &RevocationReason::Other).is_err());
+    assert_eq!(client.revoke_by_course(&admin, &course_id, &RevocationReason::Fraud), 1);
+    assert!(!client.verify_credential(&cred_id));
+
+    let by_issuer = client.revoke_all_by_issuer_action_hash(&issuer, &RevocationReason::Fraud);
+    for signer in signers.iter() {
+        client.approve_action(&signer, &by_issuer);
+    }
+    assert_eq!(client.revoke_all_by_issuer(&admin, &issuer, &RevocationReason::Fraud), 0);
+    assert_eq!(client.get_action_approvals(&by_issuer).len(), 0);
+}
+
+#[test]
+fn test_capstone_template_auto_bundles_prerequisites() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+
+    let algorithms = issue(&env, &client, &admin, &recipient, "cs-101");
+    let databases = issue(&env, &client, &admin, &recipient, "cs-201");
+    let unrelated = issue(&env, &client, &admin, &recipient, "art-101");
+
+    let capstone = client.create_template(
+        &admin,
+        &String::from_str(&env, "Capstone Project"),
+        &String::from_str(&env, "Final-year project"),
+        &String::from_str(&env, "cs-499"),
+        &0,
+    );
+    let prerequisites = vec![
+        &env,
+        String::from_str(&env, "cs-101"),
+        String::from_str(&env, "cs-201"),
+        String::from_str(&env, "cs-301"),
+    ];
+    client.set_template_prerequisites(&admin, &capstone, &prerequisites, &true);
+    assert!(client.get_template(&capstone).auto_bundle_prereqs);
+
+    let cred_id = client.issue_from_template(&admin, &capstone, &recipient, &String::from_str(&env, "QmHash"));
+    // cs-301 isn't held, so it's simply left out
+    assert_eq!(client.get_bundle_members(&cred_id), vec![&env, algorithms, databases]);
+    assert!(!client.get_bundle_members(&cred_id).contains(unrelated));
+
+    // Without the flag the capstone stands alone
+    client.set_template_prerequisites(&admin, &capstone, &prerequisites, &false);
+    let plain = client.issue_from_template(&admin, &capstone, &Address::generate(&env), &String::from_str(&env, "QmHash"));
+    assert!(client.get_bundle_members(&plain).is_empty());
+}
+
+#[test]
+fn test_issuer_directory_with_stats() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let busy = Address::generate(&env);
+    let quiet = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_issuer(&admin, &busy);
+    client.add_issuer(&admin, &quiet);
+    client.set_issuer_name(&busy, &String::from_str(&env, "Lagos School of Engineering"));
+
+    for course in ["course-001", "course-002", "course-003"] {
+        issue(&env, &client, &busy, &recipient, course);
+    }
+    client.revoke_credential(&2, &admin, &RevocationReason::Other, &None);
+    issue(&env, &client, &quiet, &recipient, "course-004");
+    env.ledger().set_timestamp(500);
+    client.set_issuer_expiry(&admin, &quiet, &400);
+
+    let directory = client.get_issuer_directory(&0, &10);
+    assert_eq!(
+        directory,
+        vec![
+            &env,
+            IssuerInfo {
+                issuer: busy.clone(),
+                name: String::from_str(&env, "Lagos School of Engineering"),
+                issued_count: 3,
+                revoked_count: 1,
+                authorized: true,
+            },
+            IssuerInfo {
+                issuer: quiet.clone(),
+                name: String::from_str(&env, ""),
+                issued_count: 1,
+                revoked_count: 0,
+                authorized: false,
+            },
+        ]
+    );
+    assert_eq!(client.get_issuer_directory(&1, &10).get(0).unwrap().issuer, quiet);
+    assert!(client.get_issuer_directory(&2, &10).is_empty());
+
+    // Private issuers stay off the public listing
+    client.set_issuer_private(&busy, &true);
+    assert_eq!(client.get_issuer_directory(&0, &10), vec![&env, directory.get(1).unwrap()]);
+    assert!(client.get_issuer_directory(&0, &1).is_empty());
+}
+
+#[test]
+fn test_expired_appeals_auto_resolve() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let reason = String::from_str(&env, "Revoked in error");
+
+    let early = issue(&env, &client, &admin, &recipient, "course-001");
+    let late = issue(&env, &client, &admin, &recipient, "course-002");
+    let handled = issue(&env, &client, &admin, &recipient, "course-003");
+    for cred_id in [early, late, handled] {
+        client.revoke_credential(&cred_id, &admin, &RevocationReason::Other, &None);
+    }
+
+    env.ledger().set_timestamp(1_000);
+    client.file_appeal(&early, &recipient, &reason);
+    client.file_appeal(&handled, &recipient, &reason);
+    env.ledger().set_timestamp(5_000);
+    client.file_appeal(&late, &recipient, &reason);
+    client.resolve_appeal(&handled, &admin, &true, &String::from_str(&env, "Confirmed"));
+    assert_eq!(client.get_pending_appeals(), vec![&env, early, late]);
+
+    // No timeout configured: nothing is swept
+    env.ledger().set_timestamp(100_000);
+    assert_eq!(client.process_expired_appeals(), 0);
+
+    client.set_appeal_timeout(&admin, &10_000, &true);
+    env.ledger().set_timestamp(12_000);
+    assert_eq!(client.process_expired_appeals(), 1);
+    assert_eq!(client.get_appeal(&early).unwrap().status, AppealStatus::Reinstated);
+    assert!(client.verify_credential(&early));
+    assert_eq!(client.get_appeal(&late).unwrap().status, AppealStatus::Pending);
+    assert_eq!(client.get_pending_appeals(), vec![&env, late]);
+
+    client.set_appeal_timeout(&admin, &10_000, &false);
+    env.ledger().set_timestamp(15_000);
+    assert_eq!(client.process_expired_appeals(), 1);
+    assert_eq!(client.get_appeal(&late).unwrap().status, AppealStatus::Upheld);
+    assert!(!client.verify_credential(&late));
+    assert!(client.get_pending_appeals().is_empty());
+}
+
+#[test]
+fn test_compute_gpa_weights_grades_by_credits() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let graded = |course_id: &str, grade: u32, credits: u32| {
+        let cred_id = client.issue_graded_credential(
+            &admin,
+            &recipient,
+            &String::from_str(&env, "Rust on Stellar"),
+            &String::from_str(&env, "Completed Soroban basics"),
+            &String::from_str(&env, course_id),
+            &String::from_str(&env, "QmHash"),
+            &grade,
+        );
+        client.set_credential_credits(&cred_id, &credits, &admin);
+        cred_id
+    };
+
+    let cs = String::from_str(&env, "cs-");
+    assert_eq!(client.compute_gpa(&recipient, &cs), 0);
+
+    graded("cs-101", 4, 3);
+    graded("cs-201", 3, 4);
+    graded("cs-301", 2, 0); // no credits: not counted
+    let math = graded("math-101", 1, 3);
+    let revoked = graded("cs-401", 1, 10);
+    client.revoke_credential(&revoked, &admin, &RevocationReason::Other, &None);
+
+    // (4*3 + 3*4) / 7 = 3.428..., rounded to 343
+    assert_eq!(client.compute_gpa(&recipient, &cs), 343);
+    // (4*3 + 3*4 + 1*3) / 10 = 2.7
+    assert_eq!(client.compute_gpa(&recipient, &String::from_str(&env, "")), 270);
+    assert_eq!(client.compute_gpa(&recipient, &String::from_str(&env, "bio-")), 0);
+
+    // A reissue takes over the original's grade and credits, so the GPA holds
+    let renewed = client.reissue_credential(&admin, &math, &0, &String::from_str(&env, "ipfs://renewed"));
+    assert_eq!(client.get_credential(&renewed).credits, 3);
+    assert_eq!(client.compute_gpa(&recipient, &String::from_str(&env, "")), 270);
+}
+
+mod revocation_listener {
+    use soroban_sdk::{contract, contractimpl, symbol_short, vec, Env, Vec};
+
+    /// Records every credential id it's told about; traps instead once marked down
+    #[contract]
+    pub struct MockListener;
+
+    #[contractimpl]
+    impl MockListener {
+        pub fn set_down(env: Env, down: bool) {
+            env.storage().instance().set(&symbol_short!("down"), &down);
+        }
+
+        pub fn on_revoked(env: Env, credential_id: u64) {
+            if env.storage().instance().get(&symbol_short!("down")).unwrap_or(false) {
+                panic!("portal unavailable");
+            }
+            let mut seen = Self::seen(env.clone());
+            seen.push_back(credential_id);
+            env.storage().instance().set(&symbol_short!("seen"), &seen);
+        }
+
+        pub fn seen(env: Env) -> Vec<u64> {
+            env.storage().instance().get(&symbol_short!("seen")).unwrap_or(vec![&env])
+        }
+    }
+}
+
+#[test]
+fn test_revocation_listener_is_notified() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let recipient = Address::generate(&env);
+    let first = issue(&env, &client, &admin, &recipient, "course-001");
+    let second = issue(&env, &client, &admin, &recipient, "course-002");
+
+    let listener_id = env.register_contract(None, revocation_listener::MockListener);
+    let listener = revocation_listener::MockListenerClient::new(&env, &listener_id);
+    client.set_revocation_listener(&admin, &listener_id);
+    assert_eq!(client.get_revocation_listener(), Some(listener_id.clone()));
+
+    client.revoke_credential(&first, &admin, &RevocationReason::Fraud, &None);
+    assert_eq!(listener.seen(), vec![&env, first]);
+
+    client.remove_revocation_listener(&admin);
+    client.revoke_credential(&second, &admin, &RevocationReason::Fraud, &None);
+    assert_eq!(listener.seen(), vec![&env, first]);
+}
+
+#[test]
+fn test_failing_revocation_listener_does_not_block_revoke() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let cred_id = issue(&env, &client, &admin, &Address::generate(&env), "course-001");
+
+    let listener_id = env.register_contract(None, revocation_listener::MockListener);
+    let listener = revocation_listener::MockListenerClient::new(&env, &listener_id);
+    listener.set_down(&true);
+    client.set_revocation_listener(&admin, &listener_id);
+
+    client.revoke_credential(&cred_id, &admin, &RevocationReason::Fraud, &None);
+    assert_eq!(client.verify_credential_detailed(&cred_id), VerificationStatus::Revoked);
+
+    // The failure is reported rather than aborting the revocation
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (DEFAULT_EVENT_NAMESPACE, Symbol::new(&env, "listener"), Symbol::new(&env, "failed")).into_val(&env));
+    let (logged_id, logged_listener): (u64, Address) = data.into_val(&env);
+    assert_eq!(logged_id, cred_id);
+    assert_eq!(logged_listener, listener_id);
+    assert!(listener.seen().is_empty());
+}
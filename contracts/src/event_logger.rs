@@ -1,8 +1,7 @@
-#![no_std]
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, symbol_short};
 
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum EventType {
     CourseCompletion,
     CredentialIssuance,
@@ -140,7 +139,7 @@ impl EventLoggerContract {
     ) -> u64 {
         user.require_auth();
         
-        let event_id = Self::create_event(
+        Self::create_event(
             env.clone(),
             EventType::ProfileUpdate,
             user.clone(),
@@ -148,9 +147,7 @@ impl EventLoggerContract {
             None,
             None,
             metadata,
-        );
-        
-        event_id
+        )
     }
 
     /// Log a course enrollment event
@@ -162,7 +159,7 @@ impl EventLoggerContract {
     ) -> u64 {
         user.require_auth();
         
-        let event_id = Self::create_event(
+        Self::create_event(
             env.clone(),
             EventType::CourseEnrollment,
             user.clone(),
@@ -170,9 +167,7 @@ impl EventLoggerContract {
             None,
             None,
             metadata,
-        );
-        
-        event_id
+        )
     }
 
     /// Get event by ID
@@ -219,17 +214,9 @@ impl EventLoggerContract {
             .unwrap_or(0);
         
         let mut events = Vec::new(&env);
-        let start = if total_events > offset as u64 { 
-            total_events - offset as u64 
-        } else { 
-            0 
-        };
+        let start = total_events.saturating_sub(offset as u64);
         
-        let end = if start > limit as u64 { 
-            start - limit as u64 
-        } else { 
-            0 
-        };
+        let end = start.saturating_sub(limit as u64);
         
         for i in (end..start).rev() {
             if let Some(event) = Self::get_event(env.clone(), i + 1) {
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
-use crate::eventLogger::{EventLoggerContract, EventLoggerContractClient, EventType};
-use soroban_sdk::{Env, testutils::{Address as _, Ledger}, Address, String, Vec};
+use crate::event_logger::{EventLoggerContract, EventLoggerContractClient, EventType};
+use soroban_sdk::{Env, testutils::{Address as _, Ledger}, Address, String};
 
 #[test]
 fn test_initialize() {
@@ -32,6 +32,7 @@ fn test_double_initialize() {
 fn test_log_course_completion() {
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
     
     let contract_id = env.register_contract(None, EventLoggerContract);
     let client = EventLoggerContractClient::new(&env, &contract_id);
@@ -1,5 +1,8 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec};
+
+use credentials::Credential;
 
 pub mod credentials;
 #[cfg(test)]
@@ -14,80 +17,20 @@ mod progress_test;
 mod event_logger_test;
 #[cfg(test)]
 mod user_profile_test;
-pub mod eventLogger;
+#[allow(non_snake_case)]
 pub mod courseMetadata;
+#[allow(non_snake_case)]
 pub mod syncCoordination;
 #[cfg(test)]
-mod progress_test;
-#[cfg(test)]
+#[allow(non_snake_case)]
 mod courseMetadata_test;
 #[cfg(test)]
+#[allow(non_snake_case)]
 mod syncCoordination_test;
 
-
-#[contracttype]
-#[derive(Clone)]
-pub struct UserProfile {
-    pub owner: Address,
-    pub username: String,
-    pub email: Option<String>,
-    pub bio: Option<String>,
-    pub avatar_url: Option<String>,
-    pub created_at: u64,
-    pub updated_at: u64,
-    pub achievements: Vec<u64>,
-    pub credentials: Vec<u64>,      // ← ADD THIS
-    pub reputation: u64,            // ← optional, but good for future
-    pub privacy_level: PrivacyLevel,
-}
-
-#[contracttype]
-#[derive(Clone)]
-pub enum PrivacyLevel {
-    Public,
-    Private,
-    FriendsOnly,
-}
-
 #[contracttype]
-#[derive(Clone)]
-pub enum ProfileKey {
-    User(Address),
-    Achievement(u64),
-    Username(String),
-    AchievementByUser(Address, u64),
-    UserAchievements(Address),
-}
-
-#[contracttype]
-#[derive(Clone)]
-pub struct Achievement {
-    pub id: u64,
-    pub user: Address,
-    pub title: String,
-    pub description: String,
-    pub earned_at: u64,
-    pub badge_url: Option<String>,
-    pub verified: bool,
-}
-
 pub enum DataKey {
-    Credential(u64),
-    CredentialCount,
-    Admin,
-}
-
-#[contracttype]
-pub struct Credential {
-    pub id: u64,
-    pub issuer: Address,
-    pub recipient: Address,
-    pub title: String,
-    pub description: String,
-    pub course_id: String,
-    pub completion_date: u64,
-    pub ipfs_hash: String,
-    pub is_verified: bool,
+    Course(u64),
 }
 
 #[contracttype]
@@ -115,76 +58,7 @@ pub struct StarkEdContract;
 impl StarkEdContract {
     /// Initialize the contract with an admin address
     pub fn initialize(env: Env, admin: Address) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Contract already initialized");
-        }
-        
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::CredentialCount, &0u64);
-    }
-
-    /// Issue a new credential
-    pub fn issue_credential(
-        env: Env,
-        issuer: Address,
-        recipient: Address,
-        title: String,
-        description: String,
-        course_id: String,
-        ipfs_hash: String,
-    ) -> u64 {
-        let admin: Address = env.storage().instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Admin not found"));
-
-        if issuer != admin {
-            panic!("Only admin can issue credentials");
-        }
-
-        let count: u64 = env.storage().instance()
-            .get(&DataKey::CredentialCount)
-            .unwrap_or(0);
-        let credential_id = count + 1;
-
-        let credential = Credential {
-            id: credential_id,
-            issuer: issuer.clone(),
-            recipient: recipient.clone(),
-            title,
-            description,
-            course_id,
-            completion_date: env.ledger().timestamp(),
-            ipfs_hash,
-            is_verified: false,
-        };
-
-        env.storage().instance().set(&DataKey::Credential(credential_id), &credential);
-        env.storage().instance().set(&DataKey::CredentialCount, &credential_id);
-
-        credential_id
-    }
-
-    /// Verify a credential
-    pub fn verify_credential(env: Env, credential_id: u64) -> bool {
-        let _admin: Address = env.storage().instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Admin not found"));
-        
-        let mut credential: Credential = env.storage().instance()
-            .get(&DataKey::Credential(credential_id))
-            .unwrap_or_else(|| panic!("Credential not found"));
-
-        credential.is_verified = true;
-        env.storage().instance().set(&DataKey::Credential(credential_id), &credential);
-
-        true
-    }
-
-    /// Get credential details
-    pub fn get_credential(env: Env, credential_id: u64) -> Credential {
-        env.storage().instance()
-            .get(&DataKey::Credential(credential_id))
-            .unwrap_or_else(|| panic!("Credential not found"))
+        credentials::initialize(&env, admin)
     }
 
     /// Create a new course
@@ -195,11 +69,7 @@ impl StarkEdContract {
         description: String,
         price: u64,
     ) -> String {
-        let admin: Address = env.storage().instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Admin not found"));
-
-        if instructor != admin {
+        if instructor != credentials::get_admin(&env) {
             panic!("Only admin can create courses");
         }
 
@@ -213,11 +83,8 @@ impl StarkEdContract {
             is_active: true,
         };
 
-        // Store course in persistent storage
-        env.storage().instance().set(&DataKey::Credential(env.ledger().timestamp()), &course);
-
         // Store course (simplified - in production would use proper storage)
-
+        env.storage().instance().set(&DataKey::Course(env.ledger().timestamp()), &course);
 
         course_id
     }
@@ -234,13 +101,6 @@ impl StarkEdContract {
             })
     }
 
-    /// Get total credential count
-    pub fn get_credential_count(env: Env) -> u64 {
-        env.storage().instance()
-            .get(&DataKey::CredentialCount)
-            .unwrap_or(0)
-    }
-
     pub fn issue_credential(
     env: Env,
     issuer: Address,
@@ -257,8 +117,14 @@ pub fn verify_credential(env: Env, credential_id: u64) -> bool {
     credentials::verify_credential(&env, credential_id)
 }
 
-pub fn revoke_credential(env: Env, credential_id: u64, revoker: Address) {
-    credentials::revoke_credential(&env, credential_id, revoker)
+pub fn revoke_credential(
+    env: Env,
+    credential_id: u64,
+    revoker: Address,
+    reason: credentials::RevocationReason,
+    note: Option<String>,
+) {
+    credentials::revoke_credential(&env, credential_id, revoker, reason, note)
 }
 
 pub fn get_user_credentials(env: Env, user: Address) -> Vec<u64> {
@@ -266,10 +132,1118 @@ pub fn get_user_credentials(env: Env, user: Address) -> Vec<u64> {
 }
 
 pub fn get_credential(env: Env, credential_id: u64) -> Credential {
-    credentials::get_credential(&env, credential_id)
+    credentials::get_public_credential(&env, credential_id)
 }
 
 pub fn get_credential_count(env: Env) -> u64 {
     credentials::get_credential_count(&env)
 }
+
+pub fn revoke_by_fingerprint(
+    env: Env,
+    fingerprint: BytesN<32>,
+    revoker: Address,
+    reason: credentials::RevocationReason,
+    note: Option<String>,
+) {
+    credentials::revoke_by_fingerprint(&env, fingerprint, revoker, reason, note)
+}
+
+pub fn credential_fingerprint(env: Env, credential_id: u64) -> BytesN<32> {
+    credentials::credential_fingerprint(&env, credential_id)
+}
+
+pub fn set_revocation_delay(env: Env, admin: Address, delay: u64) {
+    credentials::set_revocation_delay(&env, admin, delay)
+}
+
+pub fn get_pending_revocation(env: Env, credential_id: u64) -> Option<credentials::PendingRevocation> {
+    credentials::get_pending_revocation(&env, credential_id)
+}
+
+pub fn execute_revocation(env: Env, credential_id: u64) {
+    credentials::execute_revocation(&env, credential_id)
+}
+
+pub fn emergency_revoke(
+    env: Env,
+    credential_id: u64,
+    admin: Address,
+    reason: credentials::RevocationReason,
+    note: Option<String>,
+) {
+    credentials::emergency_revoke(&env, credential_id, admin, reason, note)
+}
+
+pub fn get_emergency_revoke_count(env: Env) -> u32 {
+    credentials::get_emergency_revoke_count(&env)
+}
+
+pub fn get_user_credentials_after(env: Env, user: Address, after_id: u64, limit: u32) -> Vec<u64> {
+    credentials::get_user_credentials_after(&env, user, after_id, limit)
+}
+
+pub fn issue_cosigned_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    co_issuers: Vec<Address>,
+) -> u64 {
+    credentials::issue_cosigned_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, co_issuers)
+}
+
+pub fn cosign_credential(env: Env, credential_id: u64, co_issuer: Address) {
+    credentials::cosign_credential(&env, credential_id, co_issuer)
+}
+
+pub fn fully_signed(env: Env, credential_id: u64) -> bool {
+    credentials::fully_signed(&env, credential_id)
+}
+
+pub fn set_require_co_signatures(env: Env, admin: Address, required: bool) {
+    credentials::set_require_co_signatures(&env, admin, required)
+}
+
+pub fn credential_storage_size(env: Env, credential_id: u64) -> u32 {
+    credentials::credential_storage_size(&env, credential_id)
+}
+
+pub fn file_appeal(env: Env, credential_id: u64, recipient: Address, reason: String) {
+    credentials::file_appeal(&env, credential_id, recipient, reason)
+}
+
+pub fn resolve_appeal(env: Env, credential_id: u64, admin: Address, uphold: bool, note: String) {
+    credentials::resolve_appeal(&env, credential_id, admin, uphold, note)
+}
+
+pub fn get_appeal(env: Env, credential_id: u64) -> Option<credentials::Appeal> {
+    credentials::get_appeal(&env, credential_id)
+}
+
+pub fn set_paused(env: Env, admin: Address, paused: bool) {
+    credentials::set_paused(&env, admin, paused)
+}
+
+pub fn get_feature_flags(env: Env) -> credentials::FeatureFlags {
+    credentials::get_feature_flags(&env)
+}
+
+pub fn set_feature_flags(env: Env, admin: Address, flags: credentials::FeatureFlags) {
+    credentials::set_feature_flags(&env, admin, flags)
+}
+
+pub fn set_field_limits(env: Env, admin: Address, max_title_len: u32, max_description_len: u32) {
+    credentials::set_field_limits(&env, admin, max_title_len, max_description_len)
+}
+
+pub fn get_field_limits(env: Env) -> (u32, u32) {
+    credentials::get_field_limits(&env)
+}
+
+pub fn get_credential_root(env: Env) -> BytesN<32> {
+    credentials::get_credential_root(&env)
+}
+
+pub fn verify_inclusion(env: Env, credential_id: u64, proof: Vec<BytesN<32>>) -> bool {
+    credentials::verify_inclusion(&env, credential_id, proof)
+}
+
+pub fn issue_scheduled_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    active_from: u64,
+) -> u64 {
+    credentials::issue_scheduled_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, active_from)
+}
+
+pub fn verify_credential_detailed(env: Env, credential_id: u64) -> credentials::VerificationStatus {
+    credentials::verify_credential_detailed(&env, credential_id)
+}
+
+pub fn issue_expiring_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    expires_at: u64,
+) -> u64 {
+    credentials::issue_expiring_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, expires_at)
+}
+
+pub fn set_grace_period(env: Env, admin: Address, grace_period: u64) {
+    credentials::set_grace_period(&env, admin, grace_period)
+}
+
+pub fn purge_credential(env: Env, credential_id: u64, admin: Address) {
+    credentials::purge_credential(&env, credential_id, admin)
+}
+
+pub fn issue_typed_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    credential_type: credentials::CredentialType,
+) -> u64 {
+    credentials::issue_typed_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, credential_type)
+}
+
+pub fn get_user_type_counts(env: Env, user: Address) -> Map<credentials::CredentialType, u32> {
+    credentials::get_user_type_counts(&env, user)
+}
+
+pub fn reinstate_credential(env: Env, credential_id: u64, admin: Address) {
+    credentials::reinstate_credential(&env, credential_id, admin)
+}
+
+pub fn add_issuer(env: Env, admin: Address, issuer: Address) {
+    credentials::add_issuer(&env, admin, issuer)
+}
+
+pub fn remove_issuer(env: Env, admin: Address, issuer: Address) {
+    credentials::remove_issuer(&env, admin, issuer)
+}
+
+pub fn is_issuer(env: Env, issuer: Address) -> bool {
+    credentials::is_issuer(&env, issuer)
+}
+
+pub fn set_issuer_policy(env: Env, admin: Address, issuer: Address, policy: credentials::IssuerPolicy) {
+    credentials::set_issuer_policy(&env, admin, issuer, policy)
+}
+
+pub fn get_issuer_policy(env: Env, issuer: Address) -> Option<credentials::IssuerPolicy> {
+    credentials::get_issuer_policy(&env, issuer)
+}
+
+pub fn create_share_token(env: Env, credential_id: u64, recipient: Address, expires_at: u64) -> BytesN<32> {
+    credentials::create_share_token(&env, credential_id, recipient, expires_at)
+}
+
+pub fn verify_by_token(env: Env, token: BytesN<32>) -> credentials::VerificationStatus {
+    credentials::verify_by_token(&env, token)
+}
+
+pub fn is_valid(env: Env, credential_id: u64) -> bool {
+    credentials::is_valid(&env, credential_id)
+}
+
+pub fn verify_credential_for(env: Env, credential_id: u64, holder: Address) -> bool {
+    credentials::verify_credential_for(&env, credential_id, holder)
+}
+
+pub fn get_issuer_stats(env: Env, issuer: Address) -> credentials::IssuerStats {
+    credentials::get_issuer_stats(&env, issuer)
+}
+
+pub fn get_issuer_reputation(env: Env, issuer: Address) -> u32 {
+    credentials::get_issuer_reputation(&env, issuer)
+}
+
+pub fn add_translation(
+    env: Env,
+    credential_id: u64,
+    lang: String,
+    title: String,
+    description: String,
+    issuer: Address,
+) {
+    credentials::add_translation(&env, credential_id, lang, title, description, issuer)
+}
+
+pub fn get_localized(env: Env, credential_id: u64, lang: String) -> (String, String) {
+    credentials::get_localized(&env, credential_id, lang)
+}
+
+pub fn attach_document(env: Env, credential_id: u64, ipfs_hash: String, issuer: Address) {
+    credentials::attach_document(&env, credential_id, ipfs_hash, issuer)
+}
+
+pub fn get_user_profile_summary(env: Env, user: Address) -> Vec<credentials::VerificationSummary> {
+    credentials::get_user_profile_summary(&env, user)
+}
+
+pub fn get_user_profile_summary_after(
+    env: Env,
+    user: Address,
+    after_id: u64,
+    limit: u32,
+) -> Vec<credentials::VerificationSummary> {
+    credentials::get_user_profile_summary_after(&env, user, after_id, limit)
+}
+
+pub fn set_course_issuers(env: Env, admin: Address, course_id: String, issuers: Vec<Address>) {
+    credentials::set_course_issuers(&env, admin, course_id, issuers)
+}
+
+pub fn get_course_issuers(env: Env, course_id: String) -> Vec<Address> {
+    credentials::get_course_issuers(&env, course_id)
+}
+
+pub fn reissue_credential(
+    env: Env,
+    issuer: Address,
+    original_id: u64,
+    new_expiry: u64,
+    new_ipfs_hash: String,
+) -> u64 {
+    credentials::reissue_credential(&env, issuer, original_id, new_expiry, new_ipfs_hash)
+}
+
+pub fn get_credential_lineage(env: Env, credential_id: u64) -> Vec<u64> {
+    credentials::get_credential_lineage(&env, credential_id)
+}
+
+pub fn peek_next_id(env: Env) -> u64 {
+    credentials::peek_next_id(&env)
+}
+
+pub fn get_revocations_by_reason(env: Env, reason: credentials::RevocationReason) -> Vec<u64> {
+    credentials::get_revocations_by_reason(&env, reason)
+}
+
+pub fn set_admin_signers(env: Env, admin: Address, signers: Vec<Address>, threshold: u32) {
+    credentials::set_admin_signers(&env, admin, signers, threshold)
+}
+
+pub fn set_admin_signers_action_hash(env: Env, signers: Vec<Address>, threshold: u32) -> BytesN<32> {
+    credentials::set_admin_signers_action_hash(&env, signers, threshold)
+}
+
+pub fn get_admin_signers(env: Env) -> (Vec<Address>, u32) {
+    credentials::get_admin_signers(&env)
+}
+
+pub fn approve_action(env: Env, signer: Address, action_hash: BytesN<32>) -> u32 {
+    credentials::approve_action(&env, signer, action_hash)
+}
+
+pub fn get_action_approvals(env: Env, action_hash: BytesN<32>) -> Vec<Address> {
+    credentials::get_action_approvals(&env, action_hash)
+}
+
+pub fn transfer_admin_action_hash(env: Env, new_admin: Address) -> BytesN<32> {
+    credentials::transfer_admin_action_hash(&env, new_admin)
+}
+
+pub fn transfer_admin(env: Env, new_admin: Address) {
+    credentials::transfer_admin(&env, new_admin)
+}
+
+pub fn anchor_document(env: Env, issuer: Address, content_sha256: BytesN<32>) -> u64 {
+    credentials::anchor_document(&env, issuer, content_sha256)
+}
+
+pub fn get_anchor(env: Env, anchor_id: u64) -> credentials::DocumentAnchor {
+    credentials::get_anchor(&env, anchor_id)
+}
+
+pub fn promote_anchor_to_credential(
+    env: Env,
+    anchor_id: u64,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u64 {
+    credentials::promote_anchor_to_credential(&env, anchor_id, recipient, title, description, course_id, ipfs_hash)
+}
+
+pub fn get_purgeable_credentials(env: Env, limit: u32) -> Vec<u64> {
+    credentials::get_purgeable_credentials(&env, limit)
+}
+
+pub fn add_operator(env: Env, issuer: Address, operator: Address) {
+    credentials::add_operator(&env, issuer, operator)
+}
+
+pub fn remove_operator(env: Env, issuer: Address, operator: Address) {
+    credentials::remove_operator(&env, issuer, operator)
+}
+
+pub fn get_operators(env: Env, issuer: Address) -> Vec<Address> {
+    credentials::get_operators(&env, issuer)
+}
+
+pub fn issue_as(
+    env: Env,
+    operator: Address,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u64 {
+    credentials::issue_as(&env, operator, issuer, recipient, title, description, course_id, ipfs_hash)
+}
+
+pub fn add_verification_note(env: Env, credential_id: u64, verifier: Address, note: String) {
+    credentials::add_verification_note(&env, credential_id, verifier, note)
+}
+
+pub fn get_verification_notes_paged(
+    env: Env,
+    credential_id: u64,
+    start: u32,
+    limit: u32,
+) -> Vec<credentials::VerificationNote> {
+    credentials::get_verification_notes_paged(&env, credential_id, start, limit)
+}
+
+pub fn get_issuer_credentials(env: Env, issuer: Address, caller: Option<Address>) -> Vec<u64> {
+    credentials::get_issuer_credentials(&env, issuer, caller)
+}
+
+pub fn export_issuer_credentials(env: Env, issuer: Address, caller: Option<Address>) -> Bytes {
+    credentials::export_issuer_credentials(&env, issuer, caller)
+}
+
+pub fn export_issuer_credentials_after(env: Env, issuer: Address, after_id: u64, caller: Option<Address>) -> Bytes {
+    credentials::export_issuer_credentials_after(&env, issuer, after_id, caller)
+}
+
+pub fn get_active_user_credentials(env: Env, user: Address, exclude_expired: bool) -> Vec<u64> {
+    credentials::get_active_user_credentials(&env, user, exclude_expired)
+}
+
+pub fn issue_credentials_batch(
+    env: Env,
+    issuer: Address,
+    recipients: Vec<Address>,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> Vec<u64> {
+    credentials::issue_credentials_batch(&env, issuer, recipients, title, description, course_id, ipfs_hash)
+}
+
+pub fn issue_credential_with_date(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    completion_date: u64,
+) -> u64 {
+    credentials::issue_credential_with_date(&env, issuer, recipient, title, description, course_id, ipfs_hash, completion_date)
+}
+
+pub fn set_completion_date_bounds(env: Env, admin: Address, min_completion_date: u64, max_future_skew: u64) {
+    credentials::set_completion_date_bounds(&env, admin, min_completion_date, max_future_skew)
+}
+
+pub fn get_completion_date_bounds(env: Env) -> (u64, u64) {
+    credentials::get_completion_date_bounds(&env)
+}
+
+pub fn get_contract_status(env: Env) -> credentials::ContractStatus {
+    credentials::get_contract_status(&env)
+}
+
+pub fn set_max_credentials_per_user(env: Env, admin: Address, max_credentials: u32) {
+    credentials::set_max_credentials_per_user(&env, admin, max_credentials)
+}
+
+pub fn get_max_credentials_per_user(env: Env) -> u32 {
+    credentials::get_max_credentials_per_user(&env)
+}
+
+pub fn get_common_courses(env: Env, user_a: Address, user_b: Address) -> Vec<String> {
+    credentials::get_common_courses(&env, user_a, user_b)
+}
+
+pub fn set_metadata(env: Env, caller: Address, credential_id: u64, key: String, value: String) {
+    credentials::set_metadata(&env, caller, credential_id, key, value)
+}
+
+pub fn set_metadata_batch(env: Env, caller: Address, ids: Vec<u64>, key: String, value: String) {
+    credentials::set_metadata_batch(&env, caller, ids, key, value)
+}
+
+pub fn get_metadata(env: Env, credential_id: u64, key: String) -> Option<String> {
+    credentials::get_metadata(&env, credential_id, key)
+}
+
+pub fn sweep_expired(env: Env, ids: Vec<u64>) -> u32 {
+    credentials::sweep_expired(&env, ids)
+}
+
+pub fn get_expired_count(env: Env) -> u64 {
+    credentials::get_expired_count(&env)
+}
+
+pub fn set_migration_target(env: Env, admin: Address, target: Address) {
+    credentials::set_migration_target(&env, admin, target)
+}
+
+pub fn get_migration_target(env: Env) -> Option<Address> {
+    credentials::get_migration_target(&env)
+}
+
+pub fn export_for_migration(env: Env, credential_id: u64, admin: Address) -> credentials::MigrationRecord {
+    credentials::export_for_migration(&env, credential_id, admin)
+}
+
+pub fn get_user_credentials_by_status(
+    env: Env,
+    user: Address,
+    status: credentials::VerificationStatus,
+) -> Vec<u64> {
+    credentials::get_user_credentials_by_status(&env, user, status)
+}
+
+pub fn set_verifier_hook(env: Env, issuer: Address, template_id: u64, hook: Address) {
+    credentials::set_verifier_hook(&env, issuer, template_id, hook)
+}
+
+pub fn remove_verifier_hook(env: Env, issuer: Address, template_id: u64) {
+    credentials::remove_verifier_hook(&env, issuer, template_id)
+}
+
+pub fn get_verifier_hook(env: Env, template_id: u64) -> Option<Address> {
+    credentials::get_verifier_hook(&env, template_id)
+}
+
+pub fn set_hook_failure_mode(env: Env, admin: Address, mode: credentials::HookFailureMode) {
+    credentials::set_hook_failure_mode(&env, admin, mode)
+}
+
+pub fn get_hook_failure_mode(env: Env) -> credentials::HookFailureMode {
+    credentials::get_hook_failure_mode(&env)
+}
+
+pub fn get_user_credentials_sorted(env: Env, user: Address) -> Vec<u64> {
+    credentials::get_user_credentials_sorted(&env, user)
+}
+
+pub fn block_recipient(env: Env, admin: Address, recipient: Address) {
+    credentials::block_recipient(&env, admin, recipient)
+}
+
+pub fn unblock_recipient(env: Env, admin: Address, recipient: Address) {
+    credentials::unblock_recipient(&env, admin, recipient)
+}
+
+pub fn is_recipient_blocked(env: Env, recipient: Address) -> bool {
+    credentials::is_recipient_blocked(&env, recipient)
+}
+
+pub fn search_by_title_prefix(env: Env, user: Address, prefix: String) -> Vec<u64> {
+    credentials::search_by_title_prefix(&env, user, prefix)
+}
+
+pub fn issue_credential_full(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> credentials::Credential {
+    credentials::issue_credential_full(&env, issuer, recipient, title, description, course_id, ipfs_hash)
+}
+
+pub fn set_max_batch_size(env: Env, admin: Address, max_batch_size: u32) {
+    credentials::set_max_batch_size(&env, admin, max_batch_size)
+}
+
+pub fn get_max_batch_size(env: Env) -> u32 {
+    credentials::get_max_batch_size(&env)
+}
+
+pub fn pin_credential(env: Env, recipient: Address, credential_id: u64) {
+    credentials::pin_credential(&env, recipient, credential_id)
+}
+
+pub fn unpin_credential(env: Env, recipient: Address, credential_id: u64) {
+    credentials::unpin_credential(&env, recipient, credential_id)
+}
+
+pub fn get_pinned_credentials(env: Env, user: Address) -> Vec<u64> {
+    credentials::get_pinned_credentials(&env, user)
+}
+
+pub fn issue_dependent_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    prerequisite_id: u64,
+    completion_date: Option<u64>,
+) -> u64 {
+    credentials::issue_dependent_credential(
+        &env,
+        issuer,
+        recipient,
+        title,
+        description,
+        course_id,
+        ipfs_hash,
+        prerequisite_id,
+        completion_date,
+    )
+}
+
+pub fn get_dependents(env: Env, prerequisite_id: u64) -> Vec<u64> {
+    credentials::get_dependents(&env, prerequisite_id)
+}
+
+pub fn set_cascade_mode(env: Env, admin: Address, mode: credentials::CascadeMode) {
+    credentials::set_cascade_mode(&env, admin, mode)
+}
+
+pub fn get_cascade_mode(env: Env) -> credentials::CascadeMode {
+    credentials::get_cascade_mode(&env)
+}
+
+pub fn get_review_flag(env: Env, credential_id: u64) -> Option<u64> {
+    credentials::get_review_flag(&env, credential_id)
+}
+
+pub fn clear_review_flag(env: Env, admin: Address, credential_id: u64) {
+    credentials::clear_review_flag(&env, admin, credential_id)
+}
+
+pub fn get_issuance_stats(env: Env, start_day: u64, end_day: u64) -> Vec<(u64, u32)> {
+    credentials::get_issuance_stats(&env, start_day, end_day)
+}
+
+pub fn set_renewal_config(env: Env, admin: Address, config: credentials::RenewalConfig) {
+    credentials::set_renewal_config(&env, admin, config)
+}
+
+pub fn get_renewal_config(env: Env) -> Option<credentials::RenewalConfig> {
+    credentials::get_renewal_config(&env)
+}
+
+pub fn set_renewal_fee(env: Env, issuer: Address, credential_id: u64, fee: i128) {
+    credentials::set_renewal_fee(&env, issuer, credential_id, fee)
+}
+
+pub fn renew(env: Env, credential_id: u64, payer: Address) -> u64 {
+    credentials::renew(&env, credential_id, payer)
+}
+
+/// Option-returning credential lookup. Exposed as `find_credential` because the generated
+/// client already has a `try_get_credential` for `get_credential`.
+pub fn find_credential(env: Env, credential_id: u64) -> Option<credentials::Credential> {
+    credentials::try_get_credential(&env, credential_id).map(|credential| credentials::mask_issuer(&env, credential))
+}
+
+pub fn set_issuer_signing_key(env: Env, issuer: Address, pubkey: BytesN<32>) {
+    credentials::set_issuer_signing_key(&env, issuer, pubkey)
+}
+
+pub fn get_issuer_signing_key(env: Env, issuer: Address) -> Option<BytesN<32>> {
+    credentials::get_issuer_signing_key(&env, issuer)
+}
+
+pub fn extend_expiry_signed(
+    env: Env,
+    credential_id: u64,
+    new_expiry: u64,
+    nonce: u64,
+    signature: BytesN<64>,
+    issuer_pubkey: BytesN<32>,
+) {
+    credentials::extend_expiry_signed(&env, credential_id, new_expiry, nonce, signature, issuer_pubkey)
+}
+
+pub fn get_extension_nonce(env: Env, credential_id: u64) -> u64 {
+    credentials::get_extension_nonce(&env, credential_id)
+}
+
+pub fn assign_cohort(env: Env, issuer: Address, credential_id: u64, cohort_id: String) {
+    credentials::assign_cohort(&env, issuer, credential_id, cohort_id)
+}
+
+pub fn get_cohort_credentials(env: Env, cohort_id: String) -> Vec<u64> {
+    credentials::get_cohort_credentials(&env, cohort_id)
+}
+
+pub fn revoke_cohort(env: Env, cohort_id: String, revoker: Address, reason: credentials::RevocationReason) -> u32 {
+    credentials::revoke_cohort(&env, cohort_id, revoker, reason)
+}
+
+pub fn check_revoked_batch(env: Env, ids: Vec<u64>) -> Vec<bool> {
+    credentials::check_revoked_batch(&env, ids)
+}
+
+pub fn set_event_namespace(env: Env, admin: Address, namespace: Symbol) {
+    credentials::set_event_namespace(&env, admin, namespace)
+}
+
+pub fn get_event_namespace(env: Env) -> Symbol {
+    credentials::get_event_namespace(&env)
+}
+
+pub fn update_credential_fields(env: Env, credential_id: u64, updates: credentials::CredentialUpdate, caller: Address) {
+    credentials::update_credential_fields(&env, credential_id, updates, caller)
+}
+
+pub fn verify_credential_strict(env: Env, credential_id: u64) -> credentials::VerificationStatus {
+    credentials::verify_credential_strict(&env, credential_id)
+}
+
+pub fn get_all_credential_ids(env: Env, start: u64, limit: u32) -> Vec<u64> {
+    credentials::get_all_credential_ids(&env, start, limit)
+}
+
+pub fn issue_credential_with_xp(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    xp: u32,
+) -> u64 {
+    credentials::issue_credential_with_xp(&env, issuer, recipient, title, description, course_id, ipfs_hash, xp)
+}
+
+pub fn get_user_xp(env: Env, user: Address) -> u64 {
+    credentials::get_user_xp(&env, user)
+}
+
+pub fn get_top_users(env: Env, limit: u32) -> Vec<(Address, u64)> {
+    credentials::get_top_users(&env, limit)
+}
+
+pub fn create_template(
+    env: Env,
+    issuer: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    expires_after: u64,
+) -> u64 {
+    credentials::create_template(&env, issuer, title, description, course_id, expires_after)
+}
+
+pub fn update_template(
+    env: Env,
+    issuer: Address,
+    template_id: u64,
+    title: String,
+    description: String,
+    expires_after: u64,
+) -> u32 {
+    credentials::update_template(&env, issuer, template_id, title, description, expires_after)
+}
+
+pub fn get_template(env: Env, template_id: u64) -> credentials::CredentialTemplate {
+    credentials::get_template(&env, template_id)
+}
+
+pub fn get_template_version(env: Env, template_id: u64) -> u32 {
+    credentials::get_template_version(&env, template_id)
+}
+
+pub fn issue_from_template(env: Env, issuer: Address, template_id: u64, recipient: Address, ipfs_hash: String) -> u64 {
+    credentials::issue_from_template(&env, issuer, template_id, recipient, ipfs_hash)
+}
+
+pub fn issue_encrypted_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    encryption_pubkey_hint: String,
+) -> u64 {
+    credentials::issue_encrypted_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, encryption_pubkey_hint)
+}
+
+pub fn get_course_credentials(env: Env, course_id: String) -> Vec<u64> {
+    credentials::get_course_credentials(&env, course_id)
+}
+
+pub fn remap_course(env: Env, admin: Address, old_course_id: String, new_course_id: String) -> u32 {
+    credentials::remap_course(&env, admin, old_course_id, new_course_id)
+}
+
+pub fn credentials_equal(env: Env, id_a: u64, id_b: u64, ignore_revocation: bool) -> bool {
+    credentials::credentials_equal(&env, id_a, id_b, ignore_revocation)
+}
+
+pub fn verify_credential_audited(env: Env, credential_id: u64, verifier: Address) -> credentials::VerificationStatus {
+    credentials::verify_credential_audited(&env, credential_id, verifier)
+}
+
+pub fn get_verification_log_paged(env: Env, credential_id: u64, start: u32, limit: u32) -> Vec<credentials::VerificationLogEntry> {
+    credentials::get_verification_log_paged(&env, credential_id, start, limit)
+}
+
+pub fn set_verification_log_limit(env: Env, admin: Address, limit: u32) {
+    credentials::set_verification_log_limit(&env, admin, limit)
+}
+
+pub fn get_verification_log_limit(env: Env) -> u32 {
+    credentials::get_verification_log_limit(&env)
+}
+
+pub fn issue_to_email(
+    env: Env,
+    issuer: Address,
+    email_hash: BytesN<32>,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+) -> u32 {
+    credentials::issue_to_email(&env, issuer, email_hash, title, description, course_id, ipfs_hash)
+}
+
+pub fn get_email_credentials(env: Env, email_hash: BytesN<32>) -> Vec<credentials::PendingEmailCredential> {
+    credentials::get_email_credentials(&env, email_hash)
+}
+
+pub fn set_claim_verifier_key(env: Env, admin: Address, pubkey: BytesN<32>) {
+    credentials::set_claim_verifier_key(&env, admin, pubkey)
+}
+
+pub fn claim_credential(env: Env, email_hash: BytesN<32>, proof: BytesN<64>, recipient: Address) -> Vec<u64> {
+    credentials::claim_credential(&env, email_hash, proof, recipient)
+}
+
+/// `credentials::get_credentials_by_issuer_and_course`, renamed to fit the 32-character limit
+pub fn get_issuer_course_credentials(env: Env, issuer: Address, course_id: String) -> Vec<u64> {
+    credentials::get_credentials_by_issuer_and_course(&env, issuer, course_id)
+}
+
+pub fn issue_credential_with_display_id(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    display_id: String,
+) -> u64 {
+    credentials::issue_credential_with_display_id(&env, issuer, recipient, title, description, course_id, ipfs_hash, display_id)
+}
+
+pub fn set_display_id_template(env: Env, admin: Address, template: String) {
+    credentials::set_display_id_template(&env, admin, template)
+}
+
+pub fn get_display_id_template(env: Env) -> Option<String> {
+    credentials::get_display_id_template(&env)
+}
+
+pub fn get_by_display_id(env: Env, display_id: String) -> Option<u64> {
+    credentials::get_by_display_id(&env, display_id)
+}
+
+pub fn revoke_credential_effective(
+    env: Env,
+    credential_id: u64,
+    revoker: Address,
+    reason: credentials::RevocationReason,
+    note: Option<String>,
+    effective_from: u64,
+) {
+    credentials::revoke_credential_effective(&env, credential_id, revoker, reason, note, effective_from)
+}
+
+pub fn get_config(env: Env) -> credentials::Config {
+    credentials::get_config(&env)
+}
+
+pub fn set_config(env: Env, admin: Address, config: credentials::Config) {
+    credentials::set_config(&env, admin, config)
+}
+
+pub fn set_credential_private(env: Env, credential_id: u64, recipient: Address, private: bool) {
+    credentials::set_credential_private(&env, credential_id, recipient, private)
+}
+
+pub fn grant_verifier(env: Env, credential_id: u64, recipient: Address, verifier: Address) {
+    credentials::grant_verifier(&env, credential_id, recipient, verifier)
+}
+
+pub fn revoke_verifier(env: Env, credential_id: u64, recipient: Address, verifier: Address) {
+    credentials::revoke_verifier(&env, credential_id, recipient, verifier)
+}
+
+pub fn get_verifier_grants(env: Env, credential_id: u64) -> Vec<Address> {
+    credentials::get_verifier_grants(&env, credential_id)
+}
+
+pub fn verify_credential_as(env: Env, credential_id: u64, verifier: Address) -> credentials::VerificationStatus {
+    credentials::verify_credential_as(&env, credential_id, verifier)
+}
+
+pub fn get_history(env: Env, credential_id: u64) -> Vec<credentials::StatusChange> {
+    credentials::get_history(&env, credential_id)
+}
+
+pub fn get_histories(env: Env, ids: Vec<u64>) -> Map<u64, Vec<credentials::StatusChange>> {
+    credentials::get_histories(&env, ids)
+}
+
+pub fn resolve_status(env: Env, credential_id: u64) -> credentials::VerificationStatus {
+    credentials::resolve_status(&env, credential_id)
+}
+
+pub fn add_quota(env: Env, admin: Address, issuer: Address, amount: u32) -> u32 {
+    credentials::add_quota(&env, admin, issuer, amount)
+}
+
+pub fn get_quota(env: Env, issuer: Address) -> Option<u32> {
+    credentials::get_quota(&env, issuer)
+}
+
+pub fn set_issuer_expiry(env: Env, admin: Address, issuer: Address, expiry: u64) {
+    credentials::set_issuer_expiry(&env, admin, issuer, expiry)
+}
+
+pub fn get_issuer_expiry(env: Env, issuer: Address) -> Option<u64> {
+    credentials::get_issuer_expiry(&env, issuer)
+}
+
+pub fn issue_graded_credential(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    title: String,
+    description: String,
+    course_id: String,
+    ipfs_hash: String,
+    grade: u32,
+) -> u64 {
+    credentials::issue_graded_credential(&env, issuer, recipient, title, description, course_id, ipfs_hash, grade)
+}
+
+pub fn get_best_per_course(env: Env, user: Address) -> Map<String, u64> {
+    credentials::get_best_per_course(&env, user)
+}
+
+pub fn recipient_hide_credential(env: Env, credential_id: u64, recipient: Address) {
+    credentials::recipient_hide_credential(&env, credential_id, recipient)
+}
+
+pub fn recipient_unhide(env: Env, credential_id: u64, recipient: Address) {
+    credentials::recipient_unhide(&env, credential_id, recipient)
+}
+
+pub fn import_migrated_credential(env: Env, admin: Address, record: credentials::MigrationRecord) -> u64 {
+    credentials::import_migrated_credential(&env, admin, record)
+}
+
+pub fn validate_imported(env: Env, credential_id: u64) -> bool {
+    credentials::validate_imported(&env, credential_id)
+}
+
+pub fn get_import_provenance(env: Env, credential_id: u64) -> Option<credentials::ImportedCredential> {
+    credentials::get_import_provenance(&env, credential_id)
+}
+
+pub fn get_soonest_expiring(env: Env, limit: u32) -> Vec<u64> {
+    credentials::get_soonest_expiring(&env, limit)
+}
+
+pub fn get_credential_as(env: Env, credential_id: u64, caller: Address) -> Credential {
+    credentials::get_credential_as(&env, credential_id, caller)
+}
+
+pub fn set_issuer_private(env: Env, issuer: Address, private: bool) {
+    credentials::set_issuer_private(&env, issuer, private)
+}
+
+pub fn is_issuer_private(env: Env, issuer: Address) -> bool {
+    credentials::is_issuer_private(&env, issuer)
+}
+
+pub fn time_to_expiry(env: Env, credential_id: u64) -> i64 {
+    credentials::time_to_expiry(&env, credential_id)
+}
+
+pub fn issue_bundle(
+    env: Env,
+    issuer: Address,
+    recipient: Address,
+    members: Vec<credentials::MemberSpec>,
+    bundle_title: String,
+) -> (u64, Vec<u64>) {
+    credentials::issue_bundle(&env, issuer, recipient, members, bundle_title)
+}
+
+pub fn get_bundle_members(env: Env, bundle_id: u64) -> Vec<u64> {
+    credentials::get_bundle_members(&env, bundle_id)
+}
+
+pub fn find_duplicate_credentials(env: Env, user: Address) -> Vec<(u64, u64)> {
+    credentials::find_duplicate_credentials(&env, user)
+}
+
+pub fn suspend_credential(env: Env, credential_id: u64, actor: Address) {
+    credentials::suspend_credential(&env, credential_id, actor)
+}
+
+pub fn unsuspend_credential(env: Env, credential_id: u64, actor: Address) {
+    credentials::unsuspend_credential(&env, credential_id, actor)
+}
+
+pub fn set_ipfs_gateway(env: Env, admin: Address, gateway: String) {
+    credentials::set_ipfs_gateway(&env, admin, gateway)
+}
+
+pub fn get_ipfs_gateway(env: Env) -> String {
+    credentials::get_ipfs_gateway(&env)
+}
+
+pub fn get_credential_document_url(env: Env, credential_id: u64) -> String {
+    credentials::get_credential_document_url(&env, credential_id)
+}
+
+pub fn revoke_by_fingerprints(env: Env, admin: Address, fingerprints: Vec<BytesN<32>>, reason: credentials::RevocationReason) -> u32 {
+    credentials::revoke_by_fingerprints(&env, admin, fingerprints, reason)
+}
+
+pub fn credential_storage_key(env: Env, credential_id: u64) -> Bytes {
+    credentials::credential_storage_key(&env, credential_id)
+}
+
+pub fn endorse_credential(env: Env, credential_id: u64, endorser: Address) {
+    credentials::endorse_credential(&env, credential_id, endorser)
+}
+
+pub fn get_endorsements(env: Env, credential_id: u64) -> Vec<credentials::Endorsement> {
+    credentials::get_endorsements(&env, credential_id)
+}
+
+pub fn get_endorsement_score(env: Env, credential_id: u64) -> u64 {
+    credentials::get_endorsement_score(&env, credential_id)
+}
+
+pub fn revoke_by_course_action_hash(env: Env, course_id: String, reason: credentials::RevocationReason) -> BytesN<32> {
+    credentials::revoke_by_course_action_hash(&env, course_id, reason)
+}
+
+pub fn revoke_cohort_action_hash(env: Env, cohort_id: String, reason: credentials::RevocationReason) -> BytesN<32> {
+    credentials::revoke_cohort_action_hash(&env, cohort_id, reason)
+}
+
+pub fn revoke_by_course(env: Env, admin: Address, course_id: String, reason: credentials::RevocationReason) -> u32 {
+    credentials::revoke_by_course(&env, admin, course_id, reason)
+}
+
+pub fn revoke_all_by_issuer_action_hash(env: Env, issuer: Address, reason: credentials::RevocationReason) -> BytesN<32> {
+    credentials::revoke_all_by_issuer_action_hash(&env, issuer, reason)
+}
+
+pub fn revoke_all_by_issuer(env: Env, admin: Address, issuer: Address, reason: credentials::RevocationReason) -> u32 {
+    credentials::revoke_all_by_issuer(&env, admin, issuer, reason)
+}
+
+pub fn undo_bulk_action(env: Env, admin: Address, action_id: u64) {
+    credentials::undo_bulk_action(&env, admin, action_id)
+}
+
+pub fn get_bulk_action(env: Env, action_id: u64) -> Option<credentials::BulkAction> {
+    credentials::get_bulk_action(&env, action_id)
+}
+
+pub fn get_bulk_action_count(env: Env) -> u64 {
+    credentials::get_bulk_action_count(&env)
+}
+
+pub fn set_bulk_undo_window(env: Env, admin: Address, window: u64) {
+    credentials::set_bulk_undo_window(&env, admin, window)
+}
+
+pub fn get_bulk_undo_window(env: Env) -> u64 {
+    credentials::get_bulk_undo_window(&env)
+}
+
+pub fn set_template_prerequisites(
+    env: Env,
+    issuer: Address,
+    template_id: u64,
+    prerequisite_courses: Vec<String>,
+    auto_bundle_prereqs: bool,
+) {
+    credentials::set_template_prerequisites(&env, issuer, template_id, prerequisite_courses, auto_bundle_prereqs)
+}
+
+pub fn set_issuer_name(env: Env, issuer: Address, name: String) {
+    credentials::set_issuer_name(&env, issuer, name)
+}
+
+pub fn get_issuer_name(env: Env, issuer: Address) -> String {
+    credentials::get_issuer_name(&env, issuer)
+}
+
+pub fn get_issuer_directory(env: Env, start: u32, limit: u32) -> Vec<credentials::IssuerInfo> {
+    credentials::get_issuer_directory(&env, start, limit)
+}
+
+pub fn set_appeal_timeout(env: Env, admin: Address, timeout: u64, auto_reinstate: bool) {
+    credentials::set_appeal_timeout(&env, admin, timeout, auto_reinstate)
+}
+
+pub fn get_appeal_timeout(env: Env) -> u64 {
+    credentials::get_appeal_timeout(&env)
+}
+
+pub fn get_appeal_auto_reinstate(env: Env) -> bool {
+    credentials::get_appeal_auto_reinstate(&env)
+}
+
+pub fn get_pending_appeals(env: Env) -> Vec<u64> {
+    credentials::get_pending_appeals(&env)
+}
+
+pub fn process_expired_appeals(env: Env) -> u32 {
+    credentials::process_expired_appeals(&env)
+}
+
+pub fn set_credential_credits(env: Env, credential_id: u64, credits: u32, caller: Address) {
+    credentials::set_credential_credits(&env, credential_id, credits, caller)
+}
+
+pub fn compute_gpa(env: Env, user: Address, course_prefix: String) -> u32 {
+    credentials::compute_gpa(&env, user, course_prefix)
+}
+
+pub fn set_revocation_listener(env: Env, admin: Address, listener: Address) {
+    credentials::set_revocation_listener(&env, admin, listener)
+}
+
+pub fn remove_revocation_listener(env: Env, admin: Address) {
+    credentials::remove_revocation_listener(&env, admin)
+}
+
+pub fn get_revocation_listener(env: Env) -> Option<Address> {
+    credentials::get_revocation_listener(&env)
+}
 }
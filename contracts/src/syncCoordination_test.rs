@@ -1,96 +1,120 @@
 #![cfg(test)]
 
-use soroban_sdk::{vec, Address, Env, String, Vec};
+use soroban_sdk::{testutils::{Address as _, Ledger}, vec, Address, Env, String};
 use crate::syncCoordination::{
-    SyncCoordinationContract, Device, DeviceType, SyncStatus, ConflictResolution, SyncEntry, 
-    SyncConflict, SyncSession, SyncCoordinationKey
+    ConflictResolution, DeviceType, SyncConflict, SyncCoordinationContract, SyncCoordinationContractClient,
+    SyncCoordinationKey, SyncStatus,
 };
 
+fn setup(env: &Env) -> (SyncCoordinationContractClient<'_>, Address) {
+    let contract_id = env.register_contract(None, SyncCoordinationContract);
+    let client = SyncCoordinationContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+// Conflict detection is not wired into `submit_sync_entry` yet, so seed a conflict between two
+// submitted entries (`entry_1` and `entry_2`) directly in storage
+fn seed_conflict(env: &Env, client: &SyncCoordinationContractClient, user: &Address, conflict_id: &String) {
+    let device_id = client.register_device(user, &DeviceType::Mobile, &String::from_str(env, "Phone"), &vec![env]);
+    let session_id = client.start_sync_session(user, &device_id);
+    for payload in ["first", "second"] {
+        client.submit_sync_entry(
+            &session_id,
+            &device_id,
+            &String::from_str(env, "settings"),
+            &String::from_str(env, "hash"),
+            &String::from_str(env, payload),
+        );
+    }
+    let conflict = SyncConflict {
+        id: conflict_id.clone(),
+        user_address: user.clone(),
+        entry_id_1: String::from_str(env, "entry_1"),
+        entry_id_2: String::from_str(env, "entry_2"),
+        conflict_type: String::from_str(env, "data"),
+        detected_at: env.ledger().timestamp(),
+        resolution: ConflictResolution::Unresolved,
+        resolved_at: None,
+        resolved_by: None,
+        winning_entry_id: None,
+    };
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&SyncCoordinationKey::SyncConflict(conflict_id.clone()), &conflict);
+    });
+}
+
 #[test]
 fn test_initialize() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
 
-    // Test successful initialization
-    SyncCoordinationContract::initialize(env.clone(), admin.clone());
-    
     // Verify admin is set
-    let stored_admin: Address = env.storage().instance()
-        .get(&SyncCoordinationKey::Admin)
-        .unwrap();
+    let stored_admin: Address = env.as_contract(&client.address, || {
+        env.storage().instance().get(&SyncCoordinationKey::Admin).unwrap()
+    });
     assert_eq!(stored_admin, admin);
 
     // Verify counters are initialized
-    assert_eq!(SyncCoordinationContract::get_device_count(env.clone()), 0);
-    assert_eq!(SyncCoordinationContract::get_entry_count(env.clone()), 0);
-    assert_eq!(SyncCoordinationContract::get_conflict_count(env.clone()), 0);
-    assert_eq!(SyncCoordinationContract::get_session_count(env.clone()), 0);
+    assert_eq!(client.get_device_count(), 0);
+    assert_eq!(client.get_entry_count(), 0);
+    assert_eq!(client.get_conflict_count(), 0);
+    assert_eq!(client.get_session_count(), 0);
 
     // Test double initialization fails
-    let result = std::panic::catch_unwind(|| {
-        SyncCoordinationContract::initialize(env, admin);
-    });
-    assert!(result.is_err());
+    assert!(client.try_initialize(&admin).is_err());
 }
 
 #[test]
 fn test_register_device() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register a mobile device
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "iPhone 14"),
-        vec![&env, String::from_str(&env, "read"), String::from_str(&env, "write")],
+    let device_id = client.register_device(
+        &user,
+        &DeviceType::Mobile,
+        &String::from_str(&env, "iPhone 14"),
+        &vec![&env, String::from_str(&env, "read"), String::from_str(&env, "write")],
     );
+    assert_eq!(device_id, String::from_str(&env, "device_1"));
 
     // Verify device was created
-    let device = SyncCoordinationContract::get_device(env.clone(), device_id.clone());
+    let device = client.get_device(&device_id);
     assert_eq!(device.id, device_id);
     assert_eq!(device.user_address, user);
     assert!(matches!(device.device_type, DeviceType::Mobile));
     assert_eq!(device.name, String::from_str(&env, "iPhone 14"));
-    assert_eq!(device.is_active, true);
+    assert!(device.is_active);
     assert_eq!(device.sync_version, 1);
 
     // Verify device is in user's device list
-    let user_devices = SyncCoordinationContract::get_user_devices(env, user);
+    let user_devices = client.get_user_devices(&user);
     assert!(user_devices.contains(&device_id));
 
     // Verify device count increased
-    assert_eq!(SyncCoordinationContract::get_device_count(env), 1);
+    assert_eq!(client.get_device_count(), 1);
 }
 
 #[test]
 fn test_start_sync_session() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
-
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
 
     // Register a device first
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Desktop,
-        String::from_str(&env, "Work Laptop"),
-        vec![&env],
-    );
+    let device_id = client.register_device(&user, &DeviceType::Desktop, &String::from_str(&env, "Work Laptop"), &vec![&env]);
 
     // Start sync session
-    let session_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    let session_id = client.start_sync_session(&user, &device_id);
+    assert_eq!(session_id, String::from_str(&env, "session_1"));
 
     // Verify session was created
-    let session = SyncCoordinationContract::get_sync_session(env.clone(), session_id.clone());
+    let session = client.get_sync_session(&session_id);
     assert_eq!(session.id, session_id);
     assert_eq!(session.user_address, user);
     assert_eq!(session.device_id, device_id);
@@ -99,210 +123,206 @@ fn test_start_sync_session() {
     assert_eq!(session.conflicts_resolved, 0);
 
     // Verify device last_seen was updated
-    let device = SyncCoordinationContract::get_device(env, device_id);
-    assert!(device.last_seen > 0);
+    let device = client.get_device(&device_id);
+    assert_eq!(device.last_seen, 2_000);
 
     // Verify session count increased
-    assert_eq!(SyncCoordinationContract::get_session_count(env), 1);
+    assert_eq!(client.get_session_count(), 1);
 }
 
 #[test]
 fn test_submit_sync_entry() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device and start session
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Web,
-        String::from_str(&env, "Browser"),
-        vec![&env],
-    );
-
-    let session_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
+    let device_id = client.register_device(&user, &DeviceType::Web, &String::from_str(&env, "Browser"), &vec![&env]);
+    let session_id = client.start_sync_session(&user, &device_id);
 
     // Submit sync entry
-    let entry_id = SyncCoordinationContract::submit_sync_entry(
-        env.clone(),
-        session_id.clone(),
-        device_id.clone(),
-        String::from_str(&env, "course_progress"),
-        String::from_str(&env, "hash123"),
-        String::from_str(&env, "progress_data"),
+    let entry_id = client.submit_sync_entry(
+        &session_id,
+        &device_id,
+        &String::from_str(&env, "course_progress"),
+        &String::from_str(&env, "hash123"),
+        &String::from_str(&env, "progress_data"),
     );
+    assert_eq!(entry_id, String::from_str(&env, "entry_1"));
 
     // Verify entry was created
-    let entry = SyncCoordinationContract::get_sync_entry(env.clone(), entry_id.clone());
+    let entry = client.get_sync_entry(&entry_id);
     assert_eq!(entry.id, entry_id);
     assert_eq!(entry.user_address, user);
     assert_eq!(entry.device_id, device_id);
     assert_eq!(entry.data_type, String::from_str(&env, "course_progress"));
     assert_eq!(entry.data_hash, String::from_str(&env, "hash123"));
     assert!(matches!(entry.sync_status, SyncStatus::Completed));
+    assert_eq!(entry.conflict_resolution, ConflictResolution::Unresolved);
 
     // Verify session was updated
-    let session = SyncCoordinationContract::get_sync_session(env, session_id);
+    let session = client.get_sync_session(&session_id);
     assert_eq!(session.entries_synced, 1);
 
     // Verify entry count increased
-    assert_eq!(SyncCoordinationContract::get_entry_count(env), 1);
+    assert_eq!(client.get_entry_count(), 1);
 }
 
 #[test]
 fn test_resolve_conflict_last_write_wins() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
-    // Create a conflict (simplified - in real scenario would be detected during sync)
     let conflict_id = String::from_str(&env, "conflict_1");
-    
+    seed_conflict(&env, &client, &user, &conflict_id);
+
     // Test last-write-wins resolution
-    let result = SyncCoordinationContract::resolve_conflict(
-        env.clone(),
-        conflict_id.clone(),
-        ConflictResolution::LastWriteWins,
-        String::from_str(&env, "entry_1"),
-        admin.clone(),
+    let result = client.resolve_conflict(
+        &conflict_id,
+        &ConflictResolution::LastWriteWins,
+        &String::from_str(&env, "entry_1"),
+        &admin,
     );
-
     assert!(result);
 
     // Verify conflict was resolved
-    let conflict = SyncCoordinationContract::get_sync_conflict(env, conflict_id);
-    assert_eq!(conflict.resolution, Some(ConflictResolution::LastWriteWins));
+    let conflict = client.get_sync_conflict(&conflict_id);
+    assert_eq!(conflict.resolution, ConflictResolution::LastWriteWins);
     assert_eq!(conflict.resolved_by, Some(admin));
     assert_eq!(conflict.winning_entry_id, Some(String::from_str(&env, "entry_1")));
+
+    let entry = client.get_sync_entry(&String::from_str(&env, "entry_1"));
+    assert_eq!(entry.conflict_resolution, ConflictResolution::LastWriteWins);
 }
 
 #[test]
 fn test_resolve_conflict_first_write_wins() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     let conflict_id = String::from_str(&env, "conflict_2");
-    
-    // Test first-write-wins resolution
-    let result = SyncCoordinationContract::resolve_conflict(
-        env.clone(),
-        conflict_id.clone(),
-        ConflictResolution::FirstWriteWins,
-        String::from_str(&env, "entry_1"),
-        admin.clone(),
+    seed_conflict(&env, &client, &user, &conflict_id);
+
+    // Test first-write-wins resolution; the conflict owner may resolve it
+    let result = client.resolve_conflict(
+        &conflict_id,
+        &ConflictResolution::FirstWriteWins,
+        &String::from_str(&env, "entry_1"),
+        &user,
     );
-
     assert!(result);
 
-    let conflict = SyncCoordinationContract::get_sync_conflict(env, conflict_id);
-    assert_eq!(conflict.resolution, Some(ConflictResolution::FirstWriteWins));
+    let conflict = client.get_sync_conflict(&conflict_id);
+    assert_eq!(conflict.resolution, ConflictResolution::FirstWriteWins);
+    assert_eq!(conflict.resolved_by, Some(user));
 }
 
 #[test]
 fn test_resolve_conflict_timestamp_wins() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     let conflict_id = String::from_str(&env, "conflict_3");
-    
+    seed_conflict(&env, &client, &user, &conflict_id);
+
     // Test timestamp-wins resolution
-    let result = SyncCoordinationContract::resolve_conflict(
-        env.clone(),
-        conflict_id.clone(),
-        ConflictResolution::TimestampWins,
-        String::from_str(&env, "entry_1"),
-        admin.clone(),
+    let result = client.resolve_conflict(
+        &conflict_id,
+        &ConflictResolution::TimestampWins,
+        &String::from_str(&env, "entry_1"),
+        &admin,
     );
-
     assert!(result);
 
-    let conflict = SyncCoordinationContract::get_sync_conflict(env, conflict_id);
-    assert_eq!(conflict.resolution, Some(ConflictResolution::TimestampWins));
+    let conflict = client.get_sync_conflict(&conflict_id);
+    assert_eq!(conflict.resolution, ConflictResolution::TimestampWins);
 }
 
 #[test]
 fn test_resolve_conflict_manual_review() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     let conflict_id = String::from_str(&env, "conflict_4");
-    
+    seed_conflict(&env, &client, &user, &conflict_id);
+
     // Test manual review resolution
-    let result = SyncCoordinationContract::resolve_conflict(
-        env.clone(),
-        conflict_id.clone(),
-        ConflictResolution::ManualReview,
-        String::from_str(&env, "entry_1"),
-        admin.clone(),
+    let result = client.resolve_conflict(
+        &conflict_id,
+        &ConflictResolution::ManualReview,
+        &String::from_str(&env, "entry_1"),
+        &admin,
     );
-
     assert!(result);
 
-    let conflict = SyncCoordinationContract::get_sync_conflict(env, conflict_id);
-    assert_eq!(conflict.resolution, Some(ConflictResolution::ManualReview));
+    let conflict = client.get_sync_conflict(&conflict_id);
+    assert_eq!(conflict.resolution, ConflictResolution::ManualReview);
 
     // Verify entry is marked as pending
-    let entry = SyncCoordinationContract::get_sync_entry(env, String::from_str(&env, "entry_1"));
+    let entry = client.get_sync_entry(&String::from_str(&env, "entry_1"));
     assert!(matches!(entry.sync_status, SyncStatus::Pending));
 }
 
 #[test]
-fn test_complete_sync_session() {
+fn test_resolve_conflict_merge_data() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, admin) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
+    let conflict_id = String::from_str(&env, "conflict_5");
+    seed_conflict(&env, &client, &user, &conflict_id);
 
-    // Register device and start session
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Tablet,
-        String::from_str(&env, "iPad"),
-        vec![&env],
-    );
+    client.resolve_conflict(&conflict_id, &ConflictResolution::MergeData, &String::from_str(&env, "entry_1"), &admin);
+
+    // The winning entry carries both payloads and records what it was merged with
+    let entry = client.get_sync_entry(&String::from_str(&env, "entry_1"));
+    assert_eq!(entry.payload, String::from_str(&env, "first|second"));
+    assert_eq!(entry.merged_with, vec![&env, String::from_str(&env, "entry_2")]);
+    assert_eq!(entry.conflict_resolution, ConflictResolution::MergeData);
+}
 
-    let session_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
+#[test]
+fn test_resolve_conflict_rejects_outsider_and_unresolved() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let user = Address::generate(&env);
 
-    // Complete session successfully
-    let result = SyncCoordinationContract::complete_sync_session(
-        env.clone(),
-        session_id.clone(),
-        true, // success
-        None, // no error
-    );
+    let conflict_id = String::from_str(&env, "conflict_6");
+    seed_conflict(&env, &client, &user, &conflict_id);
+    let entry_id = String::from_str(&env, "entry_1");
 
-    assert!(result);
+    let outsider = Address::generate(&env);
+    assert!(client.try_resolve_conflict(&conflict_id, &ConflictResolution::LastWriteWins, &entry_id, &outsider).is_err());
+    assert!(client.try_resolve_conflict(&conflict_id, &ConflictResolution::Unresolved, &entry_id, &admin).is_err());
+    assert_eq!(client.get_sync_conflict(&conflict_id).resolution, ConflictResolution::Unresolved);
+}
+
+#[test]
+fn test_complete_sync_session() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    // Register device and start session
+    let device_id = client.register_device(&user, &DeviceType::Tablet, &String::from_str(&env, "iPad"), &vec![&env]);
+    let session_id = client.start_sync_session(&user, &device_id);
+
+    // Complete session successfully
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert!(client.complete_sync_session(&session_id, &true, &None));
 
     // Verify session was completed
-    let session = SyncCoordinationContract::get_sync_session(env, session_id);
+    let session = client.get_sync_session(&session_id);
     assert!(matches!(session.status, SyncStatus::Completed));
     assert!(session.completed_at.is_some());
     assert!(session.error_message.is_none());
 
     // Verify device last_sync was updated
-    let device = SyncCoordinationContract::get_device(env, device_id);
+    let device = client.get_device(&device_id);
     assert!(device.last_sync > device.last_seen);
     assert_eq!(device.sync_version, 2);
 }
@@ -310,36 +330,19 @@ fn test_complete_sync_session() {
 #[test]
 fn test_complete_sync_session_with_error() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device and start session
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "Android Phone"),
-        vec![&env],
-    );
-
-    let session_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
+    let device_id = client.register_device(&user, &DeviceType::Mobile, &String::from_str(&env, "Android Phone"), &vec![&env]);
+    let session_id = client.start_sync_session(&user, &device_id);
 
     // Complete session with error
     let error_message = String::from_str(&env, "Network timeout");
-    let result = SyncCoordinationContract::complete_sync_session(
-        env.clone(),
-        session_id.clone(),
-        false, // failed
-        Some(error_message.clone()),
-    );
-
-    assert!(result);
+    assert!(client.complete_sync_session(&session_id, &false, &Some(error_message.clone())));
 
     // Verify session was marked as failed
-    let session = SyncCoordinationContract::get_sync_session(env, session_id);
+    let session = client.get_sync_session(&session_id);
     assert!(matches!(session.status, SyncStatus::Failed));
     assert_eq!(session.error_message, Some(error_message));
 }
@@ -347,146 +350,92 @@ fn test_complete_sync_session_with_error() {
 #[test]
 fn test_deactivate_device() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Desktop,
-        String::from_str(&env, "Work Computer"),
-        vec![&env],
-    );
+    let device_id = client.register_device(&user, &DeviceType::Desktop, &String::from_str(&env, "Work Computer"), &vec![&env]);
 
     // Verify device is active
-    let device = SyncCoordinationContract::get_device(env.clone(), device_id.clone());
-    assert!(device.is_active);
+    assert!(client.get_device(&device_id).is_active);
 
     // Deactivate device
-    let result = SyncCoordinationContract::deactivate_device(env.clone(), user.clone(), device_id.clone());
-    assert!(result);
+    assert!(client.deactivate_device(&user, &device_id));
 
     // Verify device is now inactive
-    let deactivated_device = SyncCoordinationContract::get_device(env, device_id);
-    assert!(!deactivated_device.is_active);
+    assert!(!client.get_device(&device_id).is_active);
 }
 
 #[test]
 fn test_update_device_capabilities() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Web,
-        String::from_str(&env, "Chrome Browser"),
-        vec![&env, String::from_str(&env, "read")],
+    let device_id = client.register_device(
+        &user,
+        &DeviceType::Web,
+        &String::from_str(&env, "Chrome Browser"),
+        &vec![&env, String::from_str(&env, "read")],
     );
 
     // Update capabilities
-    let new_capabilities = vec![&env, 
-        String::from_str(&env, "read"), 
-        String::from_str(&env, "write"), 
-        String::from_str(&env, "delete")
+    let new_capabilities = vec![
+        &env,
+        String::from_str(&env, "read"),
+        String::from_str(&env, "write"),
+        String::from_str(&env, "delete"),
     ];
-    let result = SyncCoordinationContract::update_device_capabilities(
-        env.clone(),
-        user.clone(),
-        device_id.clone(),
-        new_capabilities.clone(),
-    );
-
-    assert!(result);
+    assert!(client.update_device_capabilities(&user, &device_id, &new_capabilities));
 
     // Verify capabilities were updated
-    let updated_device = SyncCoordinationContract::get_device(env, device_id);
+    let updated_device = client.get_device(&device_id);
     assert_eq!(updated_device.capabilities.len(), 3);
-    assert!(updated_device.capabilities.contains(&String::from_str(&env, "delete")));
+    assert!(updated_device.capabilities.contains(String::from_str(&env, "delete")));
 }
 
 #[test]
 fn test_get_user_devices() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register multiple devices
-    let device1_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "iPhone"),
-        vec![&env],
-    );
-
-    let device2_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Desktop,
-        String::from_str(&env, "Laptop"),
-        vec![&env],
-    );
-
-    let device3_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Tablet,
-        String::from_str(&env, "iPad"),
-        vec![&env],
-    );
+    let device1_id = client.register_device(&user, &DeviceType::Mobile, &String::from_str(&env, "iPhone"), &vec![&env]);
+    let device2_id = client.register_device(&user, &DeviceType::Desktop, &String::from_str(&env, "Laptop"), &vec![&env]);
+    let device3_id = client.register_device(&user, &DeviceType::Tablet, &String::from_str(&env, "iPad"), &vec![&env]);
 
     // Get user's devices
-    let user_devices = SyncCoordinationContract::get_user_devices(env, user);
+    let user_devices = client.get_user_devices(&user);
     assert_eq!(user_devices.len(), 3);
     assert!(user_devices.contains(&device1_id));
     assert!(user_devices.contains(&device2_id));
     assert!(user_devices.contains(&device3_id));
 
     // Verify device count
-    assert_eq!(SyncCoordinationContract::get_device_count(env), 3);
+    assert_eq!(client.get_device_count(), 3);
 }
 
 #[test]
 fn test_get_user_sync_history() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device and create sync sessions
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "Test Device"),
-        vec![&env],
-    );
+    let device_id = client.register_device(&user, &DeviceType::Mobile, &String::from_str(&env, "Test Device"), &vec![&env]);
 
     // Create multiple sync sessions
-    let session1_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
-    SyncCoordinationContract::complete_sync_session(env.clone(), session1_id, true, None);
+    let session1_id = client.start_sync_session(&user, &device_id);
+    client.complete_sync_session(&session1_id, &true, &None);
 
-    let session2_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
-    SyncCoordinationContract::complete_sync_session(env.clone(), session2_id, true, None);
+    let session2_id = client.start_sync_session(&user, &device_id);
+    client.complete_sync_session(&session2_id, &true, &None);
 
     // Get sync history (simplified implementation)
-    let history = SyncCoordinationContract::get_user_sync_history(env, user.clone(), 10);
-    
+    let history = client.get_user_sync_history(&user, &10);
+
     // In production, this would return actual session IDs
     // For now, we just verify the function exists and returns a Vec
     assert!(history.is_empty()); // Simplified implementation returns empty
@@ -495,15 +444,12 @@ fn test_get_user_sync_history() {
 #[test]
 fn test_get_user_conflicts() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Get user conflicts (simplified implementation)
-    let conflicts = SyncCoordinationContract::get_user_conflicts(env, user);
-    
+    let conflicts = client.get_user_conflicts(&user);
+
     // In production, this would return actual conflict IDs
     // For now, we just verify the function exists and returns a Vec
     assert!(conflicts.is_empty()); // Simplified implementation returns empty
@@ -513,76 +459,48 @@ fn test_get_user_conflicts() {
 #[should_panic(expected = "Device does not belong to user")]
 fn test_unauthorized_device_access() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device for user1
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user1,
-        DeviceType::Mobile,
-        String::from_str(&env, "Phone"),
-        vec![&env],
-    );
+    let device_id = client.register_device(&user1, &DeviceType::Mobile, &String::from_str(&env, "Phone"), &vec![&env]);
 
     // Try to deactivate device with different user (should panic)
-    SyncCoordinationContract::deactivate_device(env, user2, device_id);
+    client.deactivate_device(&user2, &device_id);
 }
 
 #[test]
 #[should_panic(expected = "Device is not active")]
 fn test_sync_inactive_device() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "Phone"),
-        vec![&env],
-    );
+    let device_id = client.register_device(&user, &DeviceType::Mobile, &String::from_str(&env, "Phone"), &vec![&env]);
 
     // Deactivate device
-    SyncCoordinationContract::deactivate_device(env.clone(), user.clone(), device_id.clone());
+    client.deactivate_device(&user, &device_id);
 
     // Try to start sync session with inactive device (should panic)
-    SyncCoordinationContract::start_sync_session(env, user, device_id);
+    client.start_sync_session(&user, &device_id);
 }
 
 #[test]
-#[should_panic(expected = "Session is not active")]
+#[should_panic(expected = "Session is not in progress")]
 fn test_complete_completed_session() {
     let env = Env::default();
-    let admin = Address::generate(&env);
+    let (client, _) = setup(&env);
     let user = Address::generate(&env);
 
-    // Initialize contract
-    SyncCoordinationContract::initialize(env.clone(), admin);
-
     // Register device and start session
-    let device_id = SyncCoordinationContract::register_device(
-        env.clone(),
-        user.clone(),
-        DeviceType::Mobile,
-        String::from_str(&env, "Phone"),
-        vec![&env],
-    );
-
-    let session_id = SyncCoordinationContract::start_sync_session(env.clone(), user.clone(), device_id.clone());
+    let device_id = client.register_device(&user, &DeviceType::Mobile, &String::from_str(&env, "Phone"), &vec![&env]);
+    let session_id = client.start_sync_session(&user, &device_id);
 
     // Complete session
-    SyncCoordinationContract::complete_sync_session(env.clone(), session_id.clone(), true, None);
+    client.complete_sync_session(&session_id, &true, &None);
 
     // Try to complete same session again (should panic)
-    SyncCoordinationContract::complete_sync_session(env, session_id, true, None);
+    client.complete_sync_session(&session_id, &true, &None);
 }
@@ -1,4 +1,3 @@
-#![no_std]
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, symbol_short};
 
 #[contracttype]
@@ -18,7 +17,7 @@ pub struct UserProfile {
 }
 
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PrivacyLevel {
     Public,
     Private,
@@ -33,6 +32,7 @@ pub enum ProfileKey {
     Username(String),
     AchievementByUser(Address, u64),
     UserAchievements(Address),
+    UserCredentials(Address),
 }
 
 #[contracttype]
@@ -97,6 +97,8 @@ impl UserProfileContract {
                 created_at: env.ledger().timestamp(),
                 updated_at: env.ledger().timestamp(),
                 achievements: Vec::new(&env),
+                credentials: Vec::new(&env),
+                reputation: 0,
                 privacy_level,
             }
         };
@@ -223,7 +225,7 @@ impl UserProfileContract {
         if let Some(profile) = env.storage().instance().get::<_, UserProfile>(&ProfileKey::User(user)) {
             // Perform basic checks for authenticity
             // Here we just check that the profile exists and has a username
-            profile.username.len() > 0
+            !profile.username.is_empty()
         } else {
             false
         }
@@ -283,8 +285,9 @@ impl UserProfileContract {
             None
         }
     }
+}
 
-    /// Add a credential to user's profile
+/// Add a credential to user's profile
 pub fn add_credential(
     env: &Env,
     user: Address,
@@ -309,7 +312,7 @@ pub fn add_credential(
             }
         });
 
-    if !profile.credentials.contains(&credential_id) {
+    if !profile.credentials.contains(credential_id) {
         profile.credentials.push_back(credential_id);
         profile.updated_at = env.ledger().timestamp();
         env.storage().instance().set(&ProfileKey::User(user.clone()), &profile);
@@ -318,7 +321,7 @@ pub fn add_credential(
         let mut user_creds: Vec<u64> = env.storage().instance()
             .get(&ProfileKey::UserCredentials(user.clone()))
             .unwrap_or(Vec::new(env));
-        if !user_creds.contains(&credential_id) {
+        if !user_creds.contains(credential_id) {
             user_creds.push_back(credential_id);
             env.storage().instance().set(&ProfileKey::UserCredentials(user), &user_creds);
         }
@@ -331,6 +334,3 @@ pub fn get_user_credential_ids(env: &Env, user: Address) -> Vec<u64> {
         .get(&ProfileKey::UserCredentials(user))
         .unwrap_or(Vec::new(env))
 }
-
-    
-}
\ No newline at end of file
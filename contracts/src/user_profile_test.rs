@@ -1,10 +1,9 @@
 #![cfg(test)]
 
-use super::*;
 use soroban_sdk::{testutils::Address as _, Address, Env, String};
-use crate::user_profile::{UserProfileContract, UserProfileContractClient, PrivacyLevel, Achievement};
+use crate::user_profile::{UserProfileContract, UserProfileContractClient, PrivacyLevel};
 
-fn create_test_env() -> (Env, UserProfileContractClient, Address, Address) {
+fn create_test_env() -> (Env, UserProfileContractClient<'static>, Address, Address) {
     let env = Env::default();
     let contract_id = env.register_contract(None, UserProfileContract);
     let client = UserProfileContractClient::new(&env, &contract_id);
@@ -137,7 +136,7 @@ fn test_add_achievement() {
     assert_eq!(achievement.title, achievement_title);
     assert_eq!(achievement.description, achievement_description);
     assert_eq!(achievement.badge_url, badge_url);
-    assert_eq!(achievement.verified, false);
+    assert!(!achievement.verified);
 }
 
 #[test]
@@ -193,7 +192,7 @@ fn test_verify_achievement() {
     let username = String::from_str(&env, "testuser");
     let privacy_level = PrivacyLevel::Public;
     
-    env.mock_all_auths_multiple(&[&user, &admin]);
+    env.mock_all_auths();
     
     client.create_or_update_profile(
         &user,
@@ -211,15 +210,15 @@ fn test_verify_achievement() {
     
     // Initially, achievement should not be verified
     let achievement = client.get_achievement(&achievement_id).unwrap();
-    assert_eq!(achievement.verified, false);
+    assert!(!achievement.verified);
     
     // Verify the achievement
     let result = client.verify_achievement(&admin, &achievement_id);
-    assert_eq!(result, true);
+    assert!(result);
     
     // Now the achievement should be verified
     let achievement = client.get_achievement(&achievement_id).unwrap();
-    assert_eq!(achievement.verified, true);
+    assert!(achievement.verified);
 }
 
 #[test]
@@ -241,12 +240,12 @@ fn test_verify_profile_authenticity() {
     );
     
     let is_authentic = client.verify_profile_authenticity(&user);
-    assert_eq!(is_authentic, true);
+    assert!(is_authentic);
     
     // Test with non-existent user
     let fake_user = Address::generate(&env);
     let is_fake_authentic = client.verify_profile_authenticity(&fake_user);
-    assert_eq!(is_fake_authentic, false);
+    assert!(!is_fake_authentic);
 }
 
 #[test]
@@ -269,7 +268,7 @@ fn test_update_privacy_level() {
     
     // Change privacy level to Private
     let result = client.update_privacy_level(&user, &PrivacyLevel::Private);
-    assert_eq!(result, true);
+    assert!(result);
     
     let profile = client.get_profile(&user).unwrap();
     assert_eq!(profile.privacy_level, PrivacyLevel::Private);
@@ -323,17 +322,14 @@ fn test_username_uniqueness() {
     );
     
     // Second user tries to use same username - should panic
-    let result = std::panic::catch_unwind(|| {
-        env.mock_all_auths();
-        client.create_or_update_profile(
-            &user2,
-            &username,
-            &None,
-            &None,
-            &None,
-            &privacy_level,
-        );
-    });
+    let result = client.try_create_or_update_profile(
+        &user2,
+        &username,
+        &None,
+        &None,
+        &None,
+        &privacy_level,
+    );
     
     assert!(result.is_err());
 }
\ No newline at end of file